@@ -2,16 +2,29 @@
 
 use crate::ObfuseError;
 
-#[cfg(feature = "aes-256-gcm")]
+#[cfg(all(feature = "aes-256-gcm", not(feature = "multi-aes")))]
 pub use aes256::*;
 
+#[cfg(feature = "multi-aes")]
+pub use multi::*;
+
 #[cfg(all(feature = "aes-128-gcm", not(feature = "aes-256-gcm")))]
 pub use aes128::*;
 
-#[cfg(feature = "aes-256-gcm")]
+#[cfg(all(feature = "aes-256-gcm", not(feature = "multi-aes")))]
 mod aes256 {
     use super::ObfuseError;
-    use aes_gcm::{Aes256Gcm, KeyInit, Nonce, aead::Aead};
+    use aes_gcm::aead::generic_array::typenum::Unsigned;
+    use aes_gcm::aead::{AeadCore, KeySizeUser};
+    use aes_gcm::{Aes256Gcm, KeyInit};
+    #[cfg(not(feature = "streaming"))]
+    use aes_gcm::{Nonce, aead::Aead};
+
+    /// Human-readable algorithm name.
+    pub const ALGORITHM: &str = "AES-256-GCM";
+
+    /// Relative decryption work per ciphertext byte (XOR is 1).
+    pub const DECRYPT_COST_PER_BYTE: usize = 4;
 
     /// Key size for AES-256-GCM (32 bytes).
     pub const KEY_SIZE: usize = 32;
@@ -19,6 +32,19 @@ mod aes256 {
     /// Nonce size for AES-GCM (12 bytes).
     pub const NONCE_SIZE: usize = 12;
 
+    /// Largest plaintext AES-GCM can safely encrypt under one key and nonce
+    /// (2^36 - 32 bytes, per NIST SP 800-38D).
+    pub const MAX_PLAINTEXT_LEN: u64 = (1 << 36) - 32;
+
+    /// Largest well-formed ciphertext: the plaintext limit plus the 16-byte tag.
+    pub const MAX_CIPHERTEXT_LEN: u64 = MAX_PLAINTEXT_LEN + 16;
+
+    // The sizes are duplicated in the macro crate; pin both to the cipher
+    const _: () = assert!(KEY_SIZE == <Aes256Gcm as KeySizeUser>::KeySize::USIZE);
+    const _: () = assert!(NONCE_SIZE == <Aes256Gcm as AeadCore>::NonceSize::USIZE);
+    const _: () =
+        assert!(MAX_CIPHERTEXT_LEN - MAX_PLAINTEXT_LEN == <Aes256Gcm as AeadCore>::TagSize::U64);
+
     /// Decrypts ciphertext using AES-256-GCM.
     ///
     /// # Arguments
@@ -28,26 +54,57 @@ mod aes256 {
     ///
     /// # Returns
     /// Decrypted plaintext bytes or an error.
+    #[cfg_attr(feature = "decrypt-noinline", inline(never), cold)]
     pub fn decrypt(
         ciphertext: &[u8],
         key: &[u8; KEY_SIZE],
         nonce: &[u8; NONCE_SIZE],
     ) -> Result<Box<[u8]>, ObfuseError> {
-        let cipher =
-            Aes256Gcm::new_from_slice(key).map_err(|_| ObfuseError::AuthenticationFailed)?;
-        let nonce = Nonce::from_slice(nonce);
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(ObfuseError::authentication)?;
 
-        cipher
-            .decrypt(nonce, ciphertext)
+        #[cfg(feature = "streaming")]
+        let plaintext = crate::stream::decrypt(cipher, nonce, ciphertext);
+        #[cfg(not(feature = "streaming"))]
+        let plaintext = cipher.decrypt(Nonce::from_slice(nonce), ciphertext);
+
+        plaintext
             .map(Vec::into_boxed_slice)
-            .map_err(|_| ObfuseError::AuthenticationFailed)
+            .map_err(ObfuseError::authentication)
+    }
+
+    /// Encrypts plaintext using AES-256-GCM, appending the authentication tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `plaintext` is longer than [`MAX_PLAINTEXT_LEN`].
+    #[cfg(feature = "runtime-encrypt")]
+    pub fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+        let cipher = Aes256Gcm::new_from_slice(key).expect("key has the cipher's key size");
+
+        #[cfg(feature = "streaming")]
+        let ciphertext = crate::stream::encrypt(cipher, nonce, plaintext);
+        #[cfg(not(feature = "streaming"))]
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(nonce), plaintext)
+            .expect("plaintext is within the AES-GCM size limit");
+        ciphertext
     }
 }
 
 #[cfg(all(feature = "aes-128-gcm", not(feature = "aes-256-gcm")))]
 mod aes128 {
     use super::ObfuseError;
-    use aes_gcm::{Aes128Gcm, KeyInit, Nonce, aead::Aead};
+    use aes_gcm::aead::generic_array::typenum::Unsigned;
+    use aes_gcm::aead::{AeadCore, KeySizeUser};
+    use aes_gcm::{Aes128Gcm, KeyInit};
+    #[cfg(not(feature = "streaming"))]
+    use aes_gcm::{Nonce, aead::Aead};
+
+    /// Human-readable algorithm name.
+    pub const ALGORITHM: &str = "AES-128-GCM";
+
+    /// Relative decryption work per ciphertext byte (XOR is 1).
+    pub const DECRYPT_COST_PER_BYTE: usize = 3;
 
     /// Key size for AES-128-GCM (16 bytes).
     pub const KEY_SIZE: usize = 16;
@@ -55,19 +112,173 @@ mod aes128 {
     /// Nonce size for AES-GCM (12 bytes).
     pub const NONCE_SIZE: usize = 12;
 
+    /// Largest plaintext AES-GCM can safely encrypt under one key and nonce
+    /// (2^36 - 32 bytes, per NIST SP 800-38D).
+    pub const MAX_PLAINTEXT_LEN: u64 = (1 << 36) - 32;
+
+    /// Largest well-formed ciphertext: the plaintext limit plus the 16-byte tag.
+    pub const MAX_CIPHERTEXT_LEN: u64 = MAX_PLAINTEXT_LEN + 16;
+
+    // The sizes are duplicated in the macro crate; pin both to the cipher
+    const _: () = assert!(KEY_SIZE == <Aes128Gcm as KeySizeUser>::KeySize::USIZE);
+    const _: () = assert!(NONCE_SIZE == <Aes128Gcm as AeadCore>::NonceSize::USIZE);
+    const _: () =
+        assert!(MAX_CIPHERTEXT_LEN - MAX_PLAINTEXT_LEN == <Aes128Gcm as AeadCore>::TagSize::U64);
+
     /// Decrypts ciphertext using AES-128-GCM.
+    #[cfg_attr(feature = "decrypt-noinline", inline(never), cold)]
+    pub fn decrypt(
+        ciphertext: &[u8],
+        key: &[u8; KEY_SIZE],
+        nonce: &[u8; NONCE_SIZE],
+    ) -> Result<Box<[u8]>, ObfuseError> {
+        let cipher = Aes128Gcm::new_from_slice(key).map_err(ObfuseError::authentication)?;
+
+        #[cfg(feature = "streaming")]
+        let plaintext = crate::stream::decrypt(cipher, nonce, ciphertext);
+        #[cfg(not(feature = "streaming"))]
+        let plaintext = cipher.decrypt(Nonce::from_slice(nonce), ciphertext);
+
+        plaintext
+            .map(Vec::into_boxed_slice)
+            .map_err(ObfuseError::authentication)
+    }
+
+    /// Encrypts plaintext using AES-128-GCM, appending the authentication tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `plaintext` is longer than [`MAX_PLAINTEXT_LEN`].
+    #[cfg(feature = "runtime-encrypt")]
+    pub fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+        let cipher = Aes128Gcm::new_from_slice(key).expect("key has the cipher's key size");
+
+        #[cfg(feature = "streaming")]
+        let ciphertext = crate::stream::encrypt(cipher, nonce, plaintext);
+        #[cfg(not(feature = "streaming"))]
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(nonce), plaintext)
+            .expect("plaintext is within the AES-GCM size limit");
+        ciphertext
+    }
+}
+
+/// AES-128-GCM and AES-256-GCM in one build, selected per string.
+///
+/// Each ciphertext starts with a one-byte tag holding the key size in bytes
+/// (16 or 32). Keys are stored in a 32-byte array; AES-128 uses the first
+/// 16 bytes.
+#[cfg(feature = "multi-aes")]
+mod multi {
+    use super::ObfuseError;
+    use aes_gcm::aead::AeadInPlace;
+    use aes_gcm::aead::generic_array::typenum::Unsigned;
+    use aes_gcm::aead::{AeadCore, KeySizeUser};
+    use aes_gcm::{Aes128Gcm, Aes256Gcm, KeyInit};
+    #[cfg(not(feature = "streaming"))]
+    use aes_gcm::{Nonce, aead::Aead};
+
+    /// Human-readable algorithm name.
+    pub const ALGORITHM: &str = "AES-GCM";
+
+    /// Relative decryption work per ciphertext byte (XOR is 1), taking the
+    /// more expensive AES-256 variant.
+    pub const DECRYPT_COST_PER_BYTE: usize = 4;
+
+    /// Key storage size (32 bytes, large enough for either variant).
+    pub const KEY_SIZE: usize = 32;
+
+    /// Nonce size for AES-GCM (12 bytes).
+    pub const NONCE_SIZE: usize = 12;
+
+    /// Largest plaintext AES-GCM can safely encrypt under one key and nonce
+    /// (2^36 - 32 bytes, per NIST SP 800-38D).
+    pub const MAX_PLAINTEXT_LEN: u64 = (1 << 36) - 32;
+
+    /// Largest well-formed ciphertext: the plaintext limit plus the key-size
+    /// tag and the 16-byte authentication tag.
+    pub const MAX_CIPHERTEXT_LEN: u64 = MAX_PLAINTEXT_LEN + 17;
+
+    // The sizes are duplicated in the macro crate; pin both to the ciphers
+    const _: () = assert!(KEY_SIZE == <Aes256Gcm as KeySizeUser>::KeySize::USIZE);
+    const _: () = assert!(AES_128_TAG as usize == <Aes128Gcm as KeySizeUser>::KeySize::USIZE);
+    const _: () = assert!(AES_256_TAG as usize == <Aes256Gcm as KeySizeUser>::KeySize::USIZE);
+    const _: () = assert!(NONCE_SIZE == <Aes256Gcm as AeadCore>::NonceSize::USIZE);
+    const _: () = assert!(NONCE_SIZE == <Aes128Gcm as AeadCore>::NonceSize::USIZE);
+    const _: () = assert!(
+        MAX_CIPHERTEXT_LEN - MAX_PLAINTEXT_LEN == <Aes256Gcm as AeadCore>::TagSize::U64 + 1
+    );
+
+    /// Key-size tag for AES-128-GCM ciphertexts.
+    pub const AES_128_TAG: u8 = 16;
+
+    /// Key-size tag for AES-256-GCM ciphertexts.
+    pub const AES_256_TAG: u8 = 32;
+
+    /// Decrypts a key-size-tagged ciphertext with AES-128-GCM or AES-256-GCM.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - Key-size tag followed by the encrypted data and authentication tag
+    /// * `key` - 32-byte key storage (only the first 16 bytes are used for AES-128)
+    /// * `nonce` - 12-byte nonce
+    ///
+    /// # Returns
+    /// Decrypted plaintext bytes or an error.
+    #[cfg_attr(feature = "decrypt-noinline", inline(never), cold)]
     pub fn decrypt(
         ciphertext: &[u8],
         key: &[u8; KEY_SIZE],
         nonce: &[u8; NONCE_SIZE],
     ) -> Result<Box<[u8]>, ObfuseError> {
-        let cipher =
-            Aes128Gcm::new_from_slice(key).map_err(|_| ObfuseError::AuthenticationFailed)?;
-        let nonce = Nonce::from_slice(nonce);
+        let result = match ciphertext.split_first() {
+            Some((&AES_128_TAG, data)) => open::<Aes128Gcm>(&key[..16], nonce, data),
+            Some((&AES_256_TAG, data)) => open::<Aes256Gcm>(key, nonce, data),
+            _ => return Err(ObfuseError::authentication(aes_gcm::Error)),
+        };
 
-        cipher
-            .decrypt(nonce, ciphertext)
+        result
             .map(Vec::into_boxed_slice)
-            .map_err(|_| ObfuseError::AuthenticationFailed)
+            .map_err(ObfuseError::authentication)
+    }
+
+    /// Encrypts plaintext using AES-256-GCM, prefixed with its key-size tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `plaintext` is longer than [`MAX_PLAINTEXT_LEN`].
+    #[cfg(feature = "runtime-encrypt")]
+    pub fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+        let cipher = Aes256Gcm::new_from_slice(key).expect("key has the cipher's key size");
+
+        #[cfg(feature = "streaming")]
+        let sealed = crate::stream::encrypt(cipher, nonce, plaintext);
+        #[cfg(not(feature = "streaming"))]
+        let sealed = cipher
+            .encrypt(Nonce::from_slice(nonce), plaintext)
+            .expect("plaintext is within the AES-GCM size limit");
+
+        let mut ciphertext = vec![AES_256_TAG];
+        ciphertext.extend(sealed);
+        ciphertext
+    }
+
+    /// Decrypts untagged ciphertext with either variant, keyed by `key`.
+    ///
+    /// The cipher is built here rather than passed in: the streaming path
+    /// consumes it, the one-shot path only borrows it.
+    fn open<A>(
+        key: &[u8],
+        nonce: &[u8; NONCE_SIZE],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, aes_gcm::Error>
+    where
+        A: AeadInPlace<NonceSize = aes_gcm::aead::consts::U12> + KeyInit,
+    {
+        let cipher = A::new_from_slice(key).map_err(|_| aes_gcm::Error)?;
+        #[cfg(feature = "streaming")]
+        let plaintext = crate::stream::decrypt(cipher, nonce, ciphertext);
+        #[cfg(not(feature = "streaming"))]
+        let plaintext = cipher.decrypt(Nonce::from_slice(nonce), ciphertext);
+        plaintext
     }
 }
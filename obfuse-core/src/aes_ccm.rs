@@ -0,0 +1,92 @@
+//! AES-256-CCM decryption implementation.
+//!
+//! CCM (NIST SP 800-38C) authenticates with CBC-MAC and encrypts with CTR,
+//! using only the AES block cipher, where GCM needs the GHASH multiplier
+//! too. That makes it the usual choice where an approved mode list or a
+//! hardware AES engine rules out GCM. It makes two AES passes per block, so
+//! it is slower than GCM, and its message length is bounded by the nonce
+//! size: with the 12-byte nonce shared by every algorithm here, the length
+//! field has 3 bytes, capping a plaintext at 16 MiB.
+
+use crate::ObfuseError;
+use aes::Aes256;
+use ccm::aead::generic_array::typenum::Unsigned;
+use ccm::aead::{AeadCore, KeySizeUser};
+use ccm::consts::{U12, U16};
+use ccm::{Ccm, KeyInit};
+#[cfg(not(feature = "streaming"))]
+use ccm::{Nonce, aead::Aead};
+
+/// AES-256-CCM with a 16-byte tag and a 12-byte nonce.
+type Aes256Ccm = Ccm<Aes256, U16, U12>;
+
+/// Human-readable algorithm name.
+pub const ALGORITHM: &str = "AES-256-CCM";
+
+/// Relative decryption work per ciphertext byte (XOR is 1); CBC-MAC and CTR
+/// each take one AES pass.
+pub const DECRYPT_COST_PER_BYTE: usize = 6;
+
+/// Key size for AES-256-CCM (32 bytes).
+pub const KEY_SIZE: usize = 32;
+
+/// Nonce size for AES-CCM (12 bytes, leaving a 3-byte length field).
+pub const NONCE_SIZE: usize = 12;
+
+/// Largest plaintext the 3-byte CCM length field can describe (2^24 - 1
+/// bytes, per NIST SP 800-38C).
+pub const MAX_PLAINTEXT_LEN: u64 = (1 << 24) - 1;
+
+/// Largest well-formed ciphertext: the plaintext limit plus the 16-byte tag.
+pub const MAX_CIPHERTEXT_LEN: u64 = MAX_PLAINTEXT_LEN + 16;
+
+// The sizes are duplicated in the macro crate; pin both to the cipher
+const _: () = assert!(KEY_SIZE == <Aes256Ccm as KeySizeUser>::KeySize::USIZE);
+const _: () = assert!(NONCE_SIZE == <Aes256Ccm as AeadCore>::NonceSize::USIZE);
+const _: () =
+    assert!(MAX_CIPHERTEXT_LEN - MAX_PLAINTEXT_LEN == <Aes256Ccm as AeadCore>::TagSize::U64);
+
+/// Decrypts ciphertext using AES-256-CCM.
+///
+/// # Arguments
+/// * `ciphertext` - The encrypted data with authentication tag
+/// * `key` - 32-byte encryption key
+/// * `nonce` - 12-byte nonce
+///
+/// # Returns
+/// Decrypted plaintext bytes or an error.
+#[cfg_attr(feature = "decrypt-noinline", inline(never), cold)]
+pub fn decrypt(
+    ciphertext: &[u8],
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+) -> Result<Box<[u8]>, ObfuseError> {
+    let cipher = Aes256Ccm::new_from_slice(key).map_err(ObfuseError::authentication)?;
+
+    #[cfg(feature = "streaming")]
+    let plaintext = crate::stream::decrypt(cipher, nonce, ciphertext);
+    #[cfg(not(feature = "streaming"))]
+    let plaintext = cipher.decrypt(Nonce::from_slice(nonce), ciphertext);
+
+    plaintext
+        .map(Vec::into_boxed_slice)
+        .map_err(ObfuseError::authentication)
+}
+
+/// Encrypts plaintext using AES-256-CCM, appending the authentication tag.
+///
+/// # Panics
+///
+/// Panics if `plaintext` is longer than [`MAX_PLAINTEXT_LEN`].
+#[cfg(feature = "runtime-encrypt")]
+pub fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    let cipher = Aes256Ccm::new_from_slice(key).expect("key has the cipher's key size");
+
+    #[cfg(feature = "streaming")]
+    let ciphertext = crate::stream::encrypt(cipher, nonce, plaintext);
+    #[cfg(not(feature = "streaming"))]
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(nonce), plaintext)
+        .expect("plaintext is within the AES-CCM size limit");
+    ciphertext
+}
@@ -0,0 +1,28 @@
+//! Best-effort debugger detection for the `anti-debug` feature.
+//!
+//! This only raises the bar for casual inspection: an analyst can hide the
+//! tracer, patch the check out, or read the plaintext after it is cached.
+//! The crate forbids `unsafe`, which rules out the native APIs
+//! (`IsDebuggerPresent` on Windows, `sysctl` with `P_TRACED` on macOS), so
+//! only platforms exposing the tracer through procfs are checked.
+
+/// Returns `true` if a tracer is attached to the current process.
+///
+/// Reads `TracerPid` from `/proc/self/status`. Returns `false` when the file
+/// cannot be read or parsed.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn debugger_present() -> bool {
+    std::fs::read_to_string("/proc/self/status").is_ok_and(|status| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("TracerPid:"))
+            .and_then(|pid| pid.trim().parse::<u32>().ok())
+            .is_some_and(|pid| pid != 0)
+    })
+}
+
+/// Returns `false`: detection on this platform needs `unsafe` FFI.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn debugger_present() -> bool {
+    false
+}
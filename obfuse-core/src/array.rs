@@ -0,0 +1,128 @@
+//! The `ObfuseStrArray` type - a fixed list of strings sharing one ciphertext.
+
+use std::fmt;
+
+use crate::obfuse_str::decryption_failed;
+use crate::{ObfuseError, ObfuseStr};
+
+/// Size of the little-endian `u32` length prefixed to each element.
+const PREFIX_SIZE: usize = 4;
+
+/// A fixed list of obfuscated strings, created by `obfuse_array!`.
+///
+/// The elements are stored as one plaintext, each prefixed with its length
+/// as a little-endian `u32`, and encrypted under a single key and nonce. A
+/// list of N strings therefore embeds one key, nonce and tag instead of N,
+/// and the first access decrypts (and caches) every element at once.
+pub struct ObfuseStrArray {
+    inner: ObfuseStr,
+    len: usize,
+}
+
+impl ObfuseStrArray {
+    /// Wraps the encrypted concatenation of `len` length-prefixed elements.
+    ///
+    /// This is called by the `obfuse_array!` macro and should not be used
+    /// directly.
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn new(inner: ObfuseStr, len: usize) -> Self {
+        Self { inner, len }
+    }
+
+    /// Returns the number of elements. Nothing is decrypted.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements. Nothing is decrypted.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the element at `index`, or `None` if it is out of bounds.
+    ///
+    /// Decrypts the whole list on first access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if decryption fails. For fallible access, use [`try_get`].
+    ///
+    /// [`try_get`]: Self::try_get
+    #[track_caller]
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    pub fn get(&self, index: usize) -> Option<&str> {
+        match self.try_get(index) {
+            Ok(element) => element,
+            Err(e) => decryption_failed(&e),
+        }
+    }
+
+    /// Returns the element at `index`, `None` if it is out of bounds, or an
+    /// error if decryption fails.
+    ///
+    /// An out-of-bounds index is rejected without decrypting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails, [`ObfuseError::MalformedArray`]
+    /// if a length prefix runs past the plaintext, or
+    /// [`ObfuseError::InvalidUtf8`] if the element is not valid UTF-8. The
+    /// last two are only reachable with XOR, which is not authenticated.
+    pub fn try_get(&self, index: usize) -> Result<Option<&str>, ObfuseError> {
+        if index >= self.len {
+            return Ok(None);
+        }
+
+        let mut rest = self.inner.try_as_bytes()?;
+        for _ in 0..index {
+            rest = split_element(rest)?.1;
+        }
+        let element = split_element(rest)?.0;
+        Ok(Some(std::str::from_utf8(element)?))
+    }
+}
+
+/// Splits the first length-prefixed element off `bytes`, returning it and
+/// the remaining elements.
+fn split_element(bytes: &[u8]) -> Result<(&[u8], &[u8]), ObfuseError> {
+    let (prefix, rest) = bytes
+        .split_first_chunk::<PREFIX_SIZE>()
+        .ok_or(ObfuseError::MalformedArray)?;
+    usize::try_from(u32::from_le_bytes(*prefix))
+        .ok()
+        .and_then(|len| rest.split_at_checked(len))
+        .ok_or(ObfuseError::MalformedArray)
+}
+
+impl fmt::Debug for ObfuseStrArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObfuseStrArray")
+            .field("len", &self.len)
+            .field("values", &"[REDACTED]")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_element_rejects_overlong_prefix() {
+        assert_eq!(
+            split_element(b"\x02\0\0\0abc").unwrap(),
+            (&b"ab"[..], &b"c"[..])
+        );
+        for bytes in [&b"\x04\0\0\0abc"[..], b"\x01\0"] {
+            assert!(matches!(
+                split_element(bytes),
+                Err(ObfuseError::MalformedArray)
+            ));
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Standard (RFC 4648) base64, for `ObfuseStr::to_base64` exports.
+//!
+//! Mirrors the decoder the macros use for `base64 = "..."` payloads, but is
+//! strict: padding is required and whitespace is rejected.
+
+use zeroize::Zeroizing;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes `bytes` as padded base64.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let buffer = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | u32::from(b) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (buffer >> (18 - 6 * i)) & 0x3f;
+                output.push(char::from(ALPHABET[index as usize]));
+            } else {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+/// Decodes padded base64, returning `None` on any malformed input.
+///
+/// The output is wiped on drop, since decoded keys pass through it.
+pub fn decode(input: &str) -> Option<Zeroizing<Vec<u8>>> {
+    let digits = input.as_bytes();
+    if digits.len() % 4 != 0 {
+        return None;
+    }
+
+    let mut output = Zeroizing::new(Vec::with_capacity(digits.len() / 4 * 3));
+    let last = digits.len() / 4;
+
+    for (n, quad) in digits.chunks_exact(4).enumerate() {
+        let padding = quad.iter().rev().take_while(|&&d| d == b'=').count();
+        if padding > 2 || (padding > 0 && n + 1 != last) {
+            return None;
+        }
+
+        let mut buffer = 0u32;
+        for &digit in &quad[..4 - padding] {
+            buffer = buffer << 6 | u32::from(value(digit)?);
+        }
+        buffer <<= 6 * padding;
+
+        let bytes = buffer.to_be_bytes();
+        // Non-canonical encodings carry set bits after the last byte
+        if bytes[4 - padding..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        output.extend_from_slice(&bytes[1..4 - padding]);
+    }
+
+    Some(output)
+}
+
+fn value(digit: u8) -> Option<u8> {
+    match digit {
+        b'A'..=b'Z' => Some(digit - b'A'),
+        b'a'..=b'z' => Some(digit - b'a' + 26),
+        b'0'..=b'9' => Some(digit - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc4648_vectors() {
+        for (plain, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(encode(plain.as_bytes()), encoded);
+            assert_eq!(decode(encoded).unwrap().as_slice(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed() {
+        for input in ["Zg", "Zg=", "Z===", "Zg==Zg==", "Zm9v!A==", "Zh==", "Zm9 v"] {
+            assert!(decode(input).is_none(), "{input}");
+        }
+    }
+}
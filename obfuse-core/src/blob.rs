@@ -0,0 +1,40 @@
+//! C-ABI description of an obfuscated string, for blobs generated outside Rust.
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{KEY_SIZE, NONCE_SIZE};
+
+/// Key material for a ciphertext produced by a non-Rust build step.
+///
+/// Read it back with [`ObfuseStr::from_c_blob`](crate::ObfuseStr::from_c_blob).
+/// The layout is fixed, so C or C++ code generators can emit it directly
+/// (shown for the default AES-256-GCM build):
+///
+/// ```c
+/// struct ObfuseBlob {
+///     size_t  ciphertext_len;
+///     uint8_t key[32];   /* KEY_SIZE */
+///     uint8_t nonce[12]; /* NONCE_SIZE */
+///     uint8_t layers;
+/// };
+/// ```
+///
+/// The ciphertext itself is passed separately as a slice: turning a C
+/// pointer and length into one is the caller's `unsafe`, which keeps this
+/// crate free of raw-pointer handling. `ciphertext_len` is checked against
+/// that slice. The key is stored in the clear and wiped on drop.
+#[repr(C)]
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct ObfuseBlob {
+    /// Length of the ciphertext in bytes, in the same format `obfuse!` embeds.
+    pub ciphertext_len: usize,
+
+    /// Unmasked encryption key.
+    pub key: [u8; KEY_SIZE],
+
+    /// Nonce the ciphertext was sealed with.
+    pub nonce: [u8; NONCE_SIZE],
+
+    /// Number of encryption layers, `1..=MAX_LAYERS`.
+    pub layers: u8,
+}
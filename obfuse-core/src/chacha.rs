@@ -1,7 +1,17 @@
 //! ChaCha20-Poly1305 decryption implementation.
 
 use crate::ObfuseError;
-use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce, aead::Aead};
+use chacha20poly1305::aead::generic_array::typenum::Unsigned;
+use chacha20poly1305::aead::{AeadCore, KeySizeUser};
+use chacha20poly1305::{ChaCha20Poly1305, KeyInit};
+#[cfg(not(feature = "streaming"))]
+use chacha20poly1305::{Nonce, aead::Aead};
+
+/// Human-readable algorithm name.
+pub const ALGORITHM: &str = "ChaCha20-Poly1305";
+
+/// Relative decryption work per ciphertext byte (XOR is 1).
+pub const DECRYPT_COST_PER_BYTE: usize = 3;
 
 /// Key size for ChaCha20-Poly1305 (32 bytes).
 pub const KEY_SIZE: usize = 32;
@@ -9,6 +19,19 @@ pub const KEY_SIZE: usize = 32;
 /// Nonce size for ChaCha20-Poly1305 (12 bytes).
 pub const NONCE_SIZE: usize = 12;
 
+/// Largest plaintext ChaCha20-Poly1305 can encrypt under one key and nonce
+/// (2^38 - 64 bytes, per RFC 8439).
+pub const MAX_PLAINTEXT_LEN: u64 = (1 << 38) - 64;
+
+/// Largest well-formed ciphertext: the plaintext limit plus the 16-byte tag.
+pub const MAX_CIPHERTEXT_LEN: u64 = MAX_PLAINTEXT_LEN + 16;
+
+// The sizes are duplicated in the macro crate; pin both to the cipher
+const _: () = assert!(KEY_SIZE == <ChaCha20Poly1305 as KeySizeUser>::KeySize::USIZE);
+const _: () = assert!(NONCE_SIZE == <ChaCha20Poly1305 as AeadCore>::NonceSize::USIZE);
+const _: () =
+    assert!(MAX_CIPHERTEXT_LEN - MAX_PLAINTEXT_LEN == <ChaCha20Poly1305 as AeadCore>::TagSize::U64);
+
 /// Decrypts ciphertext using ChaCha20-Poly1305.
 ///
 /// # Arguments
@@ -18,17 +41,38 @@ pub const NONCE_SIZE: usize = 12;
 ///
 /// # Returns
 /// Decrypted plaintext bytes or an error.
+#[cfg_attr(feature = "decrypt-noinline", inline(never), cold)]
 pub fn decrypt(
     ciphertext: &[u8],
     key: &[u8; KEY_SIZE],
     nonce: &[u8; NONCE_SIZE],
 ) -> Result<Box<[u8]>, ObfuseError> {
-    let cipher =
-        ChaCha20Poly1305::new_from_slice(key).map_err(|_| ObfuseError::AuthenticationFailed)?;
-    let nonce = Nonce::from_slice(nonce);
+    let cipher = ChaCha20Poly1305::new_from_slice(key).map_err(ObfuseError::authentication)?;
+
+    #[cfg(feature = "streaming")]
+    let plaintext = crate::stream::decrypt(cipher, nonce, ciphertext);
+    #[cfg(not(feature = "streaming"))]
+    let plaintext = cipher.decrypt(Nonce::from_slice(nonce), ciphertext);
 
-    cipher
-        .decrypt(nonce, ciphertext)
+    plaintext
         .map(Vec::into_boxed_slice)
-        .map_err(|_| ObfuseError::AuthenticationFailed)
+        .map_err(ObfuseError::authentication)
+}
+
+/// Encrypts plaintext using ChaCha20-Poly1305, appending the authentication tag.
+///
+/// # Panics
+///
+/// Panics if `plaintext` is longer than [`MAX_PLAINTEXT_LEN`].
+#[cfg(feature = "runtime-encrypt")]
+pub fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    let cipher = ChaCha20Poly1305::new_from_slice(key).expect("key has the cipher's key size");
+
+    #[cfg(feature = "streaming")]
+    let ciphertext = crate::stream::encrypt(cipher, nonce, plaintext);
+    #[cfg(not(feature = "streaming"))]
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(nonce), plaintext)
+        .expect("plaintext is within the ChaCha20-Poly1305 size limit");
+    ciphertext
 }
@@ -0,0 +1,54 @@
+//! The `Cipher` trait - user-supplied algorithms for strings encrypted at
+//! runtime (`runtime-encrypt` feature).
+
+use crate::{KEY_SIZE, NONCE_SIZE, ObfuseError};
+
+/// Algorithm name reported for strings from [`ObfuseStr::encrypt_with`],
+/// e.g. by [`Secret::algorithm`](crate::Secret::algorithm).
+///
+/// [`ObfuseStr::encrypt_with`]: crate::ObfuseStr::encrypt_with
+pub const CUSTOM_ALGORITHM: &str = "custom";
+
+/// An encryption algorithm for [`ObfuseStr::encrypt_with`].
+///
+/// The macros always use the algorithm selected by the crate features; this
+/// trait only covers strings encrypted at runtime, so an application can
+/// protect them with an algorithm the feature set does not offer.
+///
+/// The trait is used as a type parameter, never as `dyn Cipher`: each
+/// `ObfuseStr` remembers the monomorphized [`decrypt`](Self::decrypt) as a
+/// function pointer. Keys and nonces are stored in the build's fixed-size
+/// arrays, so [`KEY_SIZE`](Self::KEY_SIZE) and
+/// [`NONCE_SIZE`](Self::NONCE_SIZE) may not exceed [`crate::KEY_SIZE`] and
+/// [`crate::NONCE_SIZE`]; `encrypt_with` fails to compile otherwise.
+///
+/// [`ObfuseStr::encrypt_with`]: crate::ObfuseStr::encrypt_with
+pub trait Cipher {
+    /// Key length in bytes.
+    const KEY_SIZE: usize;
+
+    /// Nonce length in bytes.
+    const NONCE_SIZE: usize;
+
+    /// Encrypts `plaintext`. `key` and `nonce` are random and exactly
+    /// `KEY_SIZE` and `NONCE_SIZE` bytes long.
+    fn encrypt(plaintext: &[u8], key: &[u8], nonce: &[u8]) -> Vec<u8>;
+
+    /// Reverses [`encrypt`](Self::encrypt) under the same key and nonce.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error (typically [`ObfuseError::AuthenticationFailed`]) if
+    /// the ciphertext is corrupted.
+    fn decrypt(ciphertext: &[u8], key: &[u8], nonce: &[u8]) -> Result<Box<[u8]>, ObfuseError>;
+}
+
+/// Adapts `C::decrypt` to the built-in `decrypt` signature, passing the leading `C::KEY_SIZE`
+/// and `C::NONCE_SIZE` bytes.
+pub(crate) fn decrypt_with<C: Cipher>(
+    ciphertext: &[u8],
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+) -> Result<Box<[u8]>, ObfuseError> {
+    C::decrypt(ciphertext, &key[..C::KEY_SIZE], &nonce[..C::NONCE_SIZE])
+}
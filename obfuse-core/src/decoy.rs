@@ -0,0 +1,68 @@
+//! Decoy decryption path for the `decoy-code` feature.
+//!
+//! `ObfuseStr` guards a call to [`decrypt`] behind [`opaque_false`], a
+//! predicate that is always `false` but that the optimizer cannot fold. The
+//! dead branch survives into the binary and looks like a second, equally
+//! plausible decryption routine to static analysis tools.
+
+use std::hint::black_box;
+
+use zeroize::Zeroize;
+
+use crate::{KEY_SIZE, NONCE_SIZE, ObfuseError};
+
+/// Signature shared by the per-algorithm `decrypt` functions.
+pub type DecryptFn =
+    fn(&[u8], &[u8; KEY_SIZE], &[u8; NONCE_SIZE]) -> Result<Box<[u8]>, ObfuseError>;
+
+/// Returns `false` for every input, in a way the compiler cannot prove.
+///
+/// `n * (n + 1)` is a product of consecutive integers and therefore always
+/// even, including under wrapping arithmetic. Routing both the input and the
+/// product through `black_box` keeps the check opaque to the optimizer.
+#[inline]
+pub fn opaque_false(n: usize) -> bool {
+    let n = black_box(n);
+    black_box(n.wrapping_mul(n.wrapping_add(1))) % 2 == 1
+}
+
+/// Decrypts with key material derived from swapped key and nonce bytes.
+///
+/// This produces garbage (or an authentication failure) and must never be
+/// reached at runtime.
+#[inline(never)]
+pub fn decrypt(
+    decrypt: DecryptFn,
+    ciphertext: &[u8],
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+) -> Result<Box<[u8]>, ObfuseError> {
+    let mut decoy_key = [0u8; KEY_SIZE];
+    let mut decoy_nonce = [0u8; NONCE_SIZE];
+
+    for (i, byte) in decoy_key.iter_mut().enumerate() {
+        *byte = nonce[i % NONCE_SIZE] ^ key[KEY_SIZE - 1 - i];
+    }
+    for (i, byte) in decoy_nonce.iter_mut().enumerate() {
+        *byte = key[i % KEY_SIZE].rotate_left(3);
+    }
+
+    let result = decrypt(ciphertext, &decoy_key, &decoy_nonce);
+
+    decoy_key.zeroize();
+    decoy_nonce.zeroize();
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opaque_predicate_is_false() {
+        for n in (0..4096).chain([usize::MAX - 1, usize::MAX]) {
+            assert!(!opaque_false(n));
+        }
+    }
+}
@@ -10,20 +10,107 @@ pub enum ObfuseError {
 
     /// AEAD authentication tag verification failed.
     /// Indicates ciphertext tampering or algorithm mismatch.
-    AuthenticationFailed,
+    ///
+    /// Carries the underlying AEAD-crate error, available via
+    /// [`source()`](std::error::Error::source). AEAD errors are deliberately
+    /// opaque, so the source adds little information beyond supporting
+    /// error-chain tooling.
+    AuthenticationFailed(Box<dyn std::error::Error + Send + Sync>),
 
     /// Decrypted bytes are not valid UTF-8.
     InvalidUtf8(std::str::Utf8Error),
+
+    /// Decrypted bytes are not a valid C string: the terminating NUL is
+    /// missing or a NUL appears before the end.
+    InteriorNul(std::ffi::FromBytesWithNulError),
+
+    /// An inner layer of a layered string is too short to hold its key and
+    /// nonce. Only reachable with XOR, whose layers are not authenticated.
+    ///
+    /// With `ciphertext-padding`, also returned when the stored ciphertext is
+    /// shorter than its junk padding.
+    TruncatedLayer,
+
+    /// An element's length prefix runs past the plaintext of an
+    /// [`ObfuseStrArray`](crate::ObfuseStrArray). Only reachable with XOR,
+    /// which is not authenticated.
+    MalformedArray,
+
+    /// The ciphertext is longer than the algorithm can encrypt under one key
+    /// and nonce, so it cannot have come from a valid encryption.
+    SizeLimitExceeded,
+
+    /// A stored password hash could not be parsed or uses an unsupported
+    /// algorithm or parameters.
+    ///
+    /// Carries the underlying `password-hash` error, available via
+    /// [`source()`](std::error::Error::source).
+    InvalidHash(Box<dyn std::error::Error + Send + Sync>),
+
+    /// A debugger is attached, so decryption was refused (`anti-debug`
+    /// feature). Nothing is cached; a later access without a debugger
+    /// succeeds.
+    DebuggerDetected,
+
+    /// A base64 export passed to
+    /// [`ObfuseStr::from_base64`](crate::ObfuseStr::from_base64) is malformed:
+    /// bad base64, wrong field count or lengths, or another algorithm.
+    InvalidEncoding(&'static str),
+
+    /// An [`ObfuseBlob`](crate::ObfuseBlob) passed to
+    /// [`ObfuseStr::from_c_blob`](crate::ObfuseStr::from_c_blob) does not
+    /// describe its ciphertext: the lengths differ or the layer count is out
+    /// of range.
+    InvalidBlob(&'static str),
+
+    /// The plaintext does not have the length the caller asked for, e.g. in
+    /// [`ObfuseStr::try_into_fixed`](crate::ObfuseStr::try_into_fixed).
+    WrongLength {
+        /// Length the caller expected, in bytes.
+        expected: usize,
+        /// Actual plaintext length, in bytes.
+        actual: usize,
+    },
+
+    /// The deadline passed to
+    /// [`ObfuseStr::decrypt_with_budget`](crate::ObfuseStr::decrypt_with_budget)
+    /// expired before decryption finished. Nothing is cached; a later access
+    /// decrypts from the start.
+    TimedOut,
+
+    /// The value was encrypted with a user-supplied `Cipher`
+    /// (`runtime-encrypt` feature), which a
+    /// [`ObfuseStr::to_base64`](crate::ObfuseStr::to_base64) export cannot
+    /// record.
+    CustomCipher,
 }
 
 impl fmt::Display for ObfuseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::AllocationFailed => write!(f, "memory allocation failed during decryption"),
-            Self::AuthenticationFailed => {
+            Self::AuthenticationFailed(_) => {
                 write!(f, "authentication failed - ciphertext may be corrupted")
             }
             Self::InvalidUtf8(e) => write!(f, "decrypted data is not valid UTF-8: {e}"),
+            Self::InteriorNul(e) => write!(f, "decrypted data is not a valid C string: {e}"),
+            Self::TruncatedLayer => write!(f, "inner encryption layer is truncated"),
+            Self::MalformedArray => write!(f, "array element runs past the decrypted data"),
+            Self::SizeLimitExceeded => {
+                write!(f, "ciphertext exceeds the algorithm's size limit")
+            }
+            Self::InvalidHash(e) => write!(f, "invalid password hash: {e}"),
+            Self::DebuggerDetected => write!(f, "decryption refused: debugger detected"),
+            Self::InvalidEncoding(reason) => write!(f, "invalid base64 export: {reason}"),
+            Self::InvalidBlob(reason) => write!(f, "invalid C blob: {reason}"),
+            Self::WrongLength { expected, actual } => {
+                write!(f, "plaintext is {actual} bytes, expected {expected}")
+            }
+            Self::TimedOut => write!(f, "decryption did not finish before the deadline"),
+            Self::CustomCipher => write!(
+                f,
+                "values encrypted with a custom cipher cannot be exported"
+            ),
         }
     }
 }
@@ -31,14 +118,174 @@ impl fmt::Display for ObfuseError {
 impl std::error::Error for ObfuseError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
+            Self::AuthenticationFailed(e) | Self::InvalidHash(e) => Some(e.as_ref()),
             Self::InvalidUtf8(e) => Some(e),
-            _ => None,
+            Self::InteriorNul(e) => Some(e),
+            Self::AllocationFailed
+            | Self::TruncatedLayer
+            | Self::MalformedArray
+            | Self::SizeLimitExceeded
+            | Self::DebuggerDetected
+            | Self::InvalidEncoding(_)
+            | Self::InvalidBlob(_)
+            | Self::WrongLength { .. }
+            | Self::TimedOut
+            | Self::CustomCipher => None,
         }
     }
 }
 
+/// Diagnostic codes and help for `miette` reports.
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for ObfuseError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        let code = match self {
+            Self::AllocationFailed => "obfuse::alloc_failed",
+            Self::AuthenticationFailed(_) => "obfuse::auth_failed",
+            Self::InvalidUtf8(_) => "obfuse::invalid_utf8",
+            Self::InteriorNul(_) => "obfuse::invalid_c_str",
+            Self::TruncatedLayer => "obfuse::truncated_layer",
+            Self::MalformedArray => "obfuse::malformed_array",
+            Self::SizeLimitExceeded => "obfuse::size_limit",
+            Self::InvalidHash(_) => "obfuse::invalid_hash",
+            Self::DebuggerDetected => "obfuse::debugger_detected",
+            Self::InvalidEncoding(_) => "obfuse::invalid_encoding",
+            Self::InvalidBlob(_) => "obfuse::invalid_blob",
+            Self::WrongLength { .. } => "obfuse::wrong_length",
+            Self::TimedOut => "obfuse::timed_out",
+            Self::CustomCipher => "obfuse::custom_cipher",
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        let help = match self {
+            Self::AllocationFailed => "the process ran out of memory while decrypting",
+            Self::AuthenticationFailed(_) => {
+                "the binary may be corrupted or patched, or the ciphertext was \
+                 produced with a different algorithm feature or key"
+            }
+            Self::InvalidUtf8(_) => {
+                "the secret holds binary data; use `try_as_bytes` or `reveal_bytes` \
+                 instead of the string accessors"
+            }
+            Self::InteriorNul(_) => {
+                "C strings need exactly one trailing NUL, e.g. `obfuse!(\"secret\\0\")`"
+            }
+            Self::TruncatedLayer => {
+                "the layered ciphertext is corrupted or was built with a different `layers` count"
+            }
+            Self::MalformedArray => {
+                "the array's ciphertext is corrupted; XOR cannot detect tampering, so \
+                 the damage only shows once the element lengths are read"
+            }
+            Self::SizeLimitExceeded => {
+                "the ciphertext was not produced by `obfuse`; see \
+                 `obfuse_core::MAX_PLAINTEXT_LEN` for the compiled-in algorithm's limit"
+            }
+            Self::InvalidHash(_) => {
+                "expected an Argon2 PHC string such as `$argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>`"
+            }
+            Self::DebuggerDetected => {
+                "the process is being traced; detach the debugger or build without the \
+                 `anti-debug` feature"
+            }
+            Self::InvalidEncoding(_) => {
+                "expected `ALGORITHM|nonce|ciphertext|key` from `ObfuseStr::to_base64`, \
+                 exported by a build with the same algorithm feature"
+            }
+            Self::InvalidBlob(_) => {
+                "the generator must set `ciphertext_len` to the length of the ciphertext \
+                 passed alongside the blob, and `layers` to the count it encrypted with"
+            }
+            Self::WrongLength { .. } => {
+                "the embedded secret has a different size than the key or buffer it \
+                 is meant for; check the literal"
+            }
+            Self::TimedOut => {
+                "the secret could not be decrypted within the budget; retry with a later \
+                 deadline or decrypt it ahead of the latency-sensitive path"
+            }
+            Self::CustomCipher => {
+                "the export names only the build's algorithm; persist the plaintext's \
+                 source instead and re-encrypt it with `encrypt_with` after loading"
+            }
+        };
+        Some(Box::new(help))
+    }
+}
+
+/// Errors from [`ObfuseStr::parse`](crate::ObfuseStr::parse).
+#[derive(Debug)]
+pub enum ParseSecretError<E> {
+    /// Decryption failed, or the plaintext is not valid UTF-8.
+    Decrypt(ObfuseError),
+
+    /// The plaintext decrypted but `T::from_str` rejected it.
+    ///
+    /// Parse errors may echo their input; avoid logging them verbatim if the
+    /// target type's error does.
+    Parse(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ParseSecretError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decrypt(e) => write!(f, "{e}"),
+            Self::Parse(e) => write!(f, "failed to parse decrypted value: {e}"),
+        }
+    }
+}
+
+impl<E> std::error::Error for ParseSecretError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Decrypt(e) => Some(e),
+            Self::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl<E> From<ObfuseError> for ParseSecretError<E> {
+    fn from(e: ObfuseError) -> Self {
+        Self::Decrypt(e)
+    }
+}
+
+#[cfg(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+))]
+impl ObfuseError {
+    /// Wraps an AEAD-crate error as [`ObfuseError::AuthenticationFailed`].
+    pub(crate) fn authentication<E>(error: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Self::AuthenticationFailed(Box::new(error))
+    }
+}
+
+#[cfg(feature = "password-hash")]
+impl From<argon2::password_hash::Error> for ObfuseError {
+    fn from(e: argon2::password_hash::Error) -> Self {
+        Self::InvalidHash(Box::new(e))
+    }
+}
+
 impl From<std::str::Utf8Error> for ObfuseError {
     fn from(e: std::str::Utf8Error) -> Self {
         Self::InvalidUtf8(e)
     }
 }
+
+impl From<std::ffi::FromBytesWithNulError> for ObfuseError {
+    fn from(e: std::ffi::FromBytesWithNulError) -> Self {
+        Self::InteriorNul(e)
+    }
+}
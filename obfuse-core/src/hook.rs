@@ -0,0 +1,60 @@
+//! Process-global hook for decryption failures.
+//!
+//! A failed decryption of an embedded string usually means a patched or
+//! corrupted binary, which is worth reporting centrally. The hook receives
+//! the algorithm name and the error, never plaintext or key material.
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::OnceLock;
+
+use crate::{ALGORITHM, ObfuseError};
+
+/// Signature of a decryption-failure hook: the algorithm name and the error.
+pub type DecryptErrorHook = fn(&'static str, &ObfuseError);
+
+static HOOK: OnceLock<DecryptErrorHook> = OnceLock::new();
+
+thread_local! {
+    /// Set while this thread runs the hook, so failures inside it are not
+    /// reported again.
+    static IN_HOOK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Registers `hook` to run whenever an `ObfuseStr` fails to decrypt.
+///
+/// The hook runs on the failing thread, after the failure and before the
+/// error is returned. Running out of a
+/// [`decrypt_with_budget`](crate::ObfuseStr::decrypt_with_budget) deadline
+/// is not a failure and is not reported. It is process-global and can be registered once;
+/// later calls return `false` and leave the first hook in place.
+///
+/// The hook cannot disturb the decryption path: a panic inside it is
+/// caught and discarded, and decryption failures caused by the hook itself
+/// (e.g. it reads another broken secret) do not invoke it again.
+///
+/// # Examples
+///
+/// ```ignore
+/// obfuse::on_decrypt_error(|algorithm, error| {
+///     eprintln!("obfuse: {algorithm} decryption failed: {error}");
+/// });
+/// ```
+pub fn on_decrypt_error(hook: DecryptErrorHook) -> bool {
+    HOOK.set(hook).is_ok()
+}
+
+/// Runs the registered hook for `error`, if any.
+pub(crate) fn report(error: &ObfuseError) {
+    let Some(hook) = HOOK.get() else {
+        return;
+    };
+    if IN_HOOK.replace(true) {
+        return;
+    }
+
+    // The hook only sees shared references, so a panic cannot leave state
+    // that this path relies on half-updated
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(ALGORITHM, error)));
+    IN_HOOK.set(false);
+}
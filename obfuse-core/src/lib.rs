@@ -9,16 +9,93 @@
 //!
 //! - `aes-256-gcm` (default) - AES-256 in GCM mode
 //! - `aes-128-gcm` - AES-128 in GCM mode
+//! - `aes-256-ccm` - AES-256 in CCM mode, for compliance profiles without GCM
 //! - `chacha20-poly1305` - ChaCha20-Poly1305 AEAD
 //! - `xor` - Simple XOR cipher (fast, less secure)
+//! - `polymorphic` - XOR with a per-string byte-transform program in the nonce
+//! - `multi-aes` - AES-128-GCM and AES-256-GCM together, chosen per string
+//!
+//! Optional extras:
+//!
+//! - `manifest` - HMAC manifest over embedded ciphertexts for tamper detection
+//! - `decoy-code` - Unreachable decoy decryption path to mislead static analysis
+//! - `defmt` - Redacted `defmt::Format` implementation for embedded logging
+//! - `secrecy` - `ObfuseStr::to_secret_string` for `secrecy` interop
+//! - `access-metrics` - Per-string access counter, `ObfuseStr::access_count`
+//! - `miette` - `miette::Diagnostic` for `ObfuseError`, with codes and help text
+//! - `permute-ciphertext` - Embedded ciphertext stored in a nonce-seeded shuffle
+//! - `ciphertext-padding` - Nonce-derived junk bytes around the embedded
+//!   ciphertext, stripped before decryption
+//! - `honeytoken` - Callback run on a string's first decryption, for `obfuse_honey!`
+//! - `runtime-encrypt` - `ObfuseStr::from_reader`, encrypting runtime secrets,
+//!   and the `Cipher` trait for `ObfuseStr::encrypt_with`
+//! - `decrypt-noinline` - Marks the cipher `decrypt` functions `#[inline(never)]`
+//!   and `#[cold]`
+//! - `password-hash` - `ObfuseStr::verify_against`, Argon2 password verification
+//! - `tokio` - `ObfuseStr::warm_async`, decrypting on Tokio's blocking pool
+//! - `serialize-redacted` - `serde::Serialize` as `"[REDACTED]"`, plus the
+//!   opt-in `serde_ciphertext` field helpers
+//! - `transport-safe` - Redacting `Display` and `serde::Serialize` impls
+//! - `serde-config` - `serde::Deserialize` from plaintext, encrypting each
+//!   value under a fresh random key as it is parsed
+//! - `anti-debug` - Best-effort refusal to decrypt while a debugger is attached
+//! - `utf16` - `ObfuseStr::with_utf16` and `with_utf16_nul`, scoped UTF-16
+//!   copies for Windows wide-string APIs
+//! - `streaming` - Chunked `aead::stream` ciphertexts with one tag per 64 KiB,
+//!   for very large secrets (AEAD algorithms only)
+//! - `pii-redact` - `ObfuseStr::reveal_redacted_for_log`, masking emails,
+//!   card numbers or custom regexes in a revealed copy
+//! - `redaction-policy` - `RedactionPolicy`, choosing how much `ObfuseStr`'s
+//!   `Debug` output shows, globally or per site with `ObfuseStr::debug_with`
 
 #![forbid(unsafe_code)]
 #![deny(missing_docs)]
 #![deny(clippy::all)]
 #![warn(clippy::pedantic)]
 
+#[cfg(feature = "anti-debug")]
+mod anti_debug;
+mod array;
+mod base64;
+mod blob;
+#[cfg(feature = "runtime-encrypt")]
+mod cipher;
+#[cfg(feature = "decoy-code")]
+mod decoy;
 mod error;
+mod hook;
+#[cfg(feature = "manifest")]
+mod manifest;
+mod mask;
 mod obfuse_str;
+mod once;
+#[cfg(feature = "ciphertext-padding")]
+mod padding;
+#[cfg(feature = "permute-ciphertext")]
+mod permute;
+#[cfg(feature = "polymorphic")]
+mod program;
+#[cfg(feature = "pii-redact")]
+mod redact;
+#[cfg(feature = "redaction-policy")]
+mod redaction;
+mod reveal;
+#[cfg(feature = "runtime-encrypt")]
+mod runtime;
+mod secret;
+mod secret_vec;
+#[cfg(feature = "serialize-redacted")]
+pub mod serde_ciphertext;
+#[cfg(all(
+    feature = "streaming",
+    any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
+        feature = "chacha20-poly1305"
+    )
+))]
+mod stream;
 
 // Only compile the module that's actually selected (mutually exclusive features)
 #[cfg(any(
@@ -28,9 +105,19 @@ mod obfuse_str;
 mod aes;
 
 #[cfg(all(
-    feature = "chacha20-poly1305",
+    feature = "aes-256-ccm",
     not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
 ))]
+mod aes_ccm;
+
+#[cfg(all(
+    feature = "chacha20-poly1305",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm"
+    ))
+))]
 mod chacha;
 
 #[cfg(all(
@@ -38,45 +125,95 @@ mod chacha;
     not(any(
         feature = "aes-256-gcm",
         feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
         feature = "chacha20-poly1305"
     ))
 ))]
 mod xor;
 
-pub use error::ObfuseError;
+pub use array::ObfuseStrArray;
+pub use blob::ObfuseBlob;
+#[cfg(feature = "runtime-encrypt")]
+pub use cipher::{CUSTOM_ALGORITHM, Cipher};
+pub use error::{ObfuseError, ParseSecretError};
+pub use hook::{DecryptErrorHook, on_decrypt_error};
+#[cfg(feature = "manifest")]
+pub use manifest::{MANIFEST_SIZE, Manifest, manifest_hmac};
 pub use obfuse_str::ObfuseStr;
+pub use once::ObfuseOnceStr;
+#[cfg(feature = "ciphertext-padding")]
+pub use padding::{MAX_PADDING, pad_ciphertext};
+#[cfg(feature = "permute-ciphertext")]
+pub use permute::permute_ciphertext;
+#[cfg(feature = "polymorphic")]
+#[doc(hidden)]
+pub use program::mul_inverse;
+#[cfg(feature = "pii-redact")]
+pub use redact::RedactConfig;
+#[cfg(feature = "redaction-policy")]
+pub use redaction::{Redaction, RedactionPolicy, set_redaction_policy};
+pub use reveal::{AlignedBytesGuard, Revealed, SecretBytesGuard};
+pub use secret::Secret;
+pub use secret_vec::SecretVec;
+
+/// Maximum number of encryption layers a string can be wrapped in.
+pub const MAX_LAYERS: u8 = 8;
 
 // Re-export constants for use by the macro crate
 #[cfg(feature = "aes-256-gcm")]
-pub use aes::{KEY_SIZE, NONCE_SIZE};
+pub use aes::{ALGORITHM, DECRYPT_COST_PER_BYTE, KEY_SIZE, MAX_PLAINTEXT_LEN, NONCE_SIZE};
 
 #[cfg(all(feature = "aes-128-gcm", not(feature = "aes-256-gcm")))]
-pub use aes::{KEY_SIZE, NONCE_SIZE};
+pub use aes::{ALGORITHM, DECRYPT_COST_PER_BYTE, KEY_SIZE, MAX_PLAINTEXT_LEN, NONCE_SIZE};
 
 #[cfg(all(
-    feature = "chacha20-poly1305",
+    feature = "aes-256-ccm",
     not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
 ))]
-pub use chacha::{KEY_SIZE, NONCE_SIZE};
+pub use aes_ccm::{ALGORITHM, DECRYPT_COST_PER_BYTE, KEY_SIZE, MAX_PLAINTEXT_LEN, NONCE_SIZE};
+
+#[cfg(all(
+    feature = "chacha20-poly1305",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm"
+    ))
+))]
+pub use chacha::{ALGORITHM, DECRYPT_COST_PER_BYTE, KEY_SIZE, MAX_PLAINTEXT_LEN, NONCE_SIZE};
 
 #[cfg(all(
     feature = "xor",
     not(any(
         feature = "aes-256-gcm",
         feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
         feature = "chacha20-poly1305"
     ))
 ))]
-pub use xor::{KEY_SIZE, NONCE_SIZE};
+pub use xor::{ALGORITHM, DECRYPT_COST_PER_BYTE, KEY_SIZE, MAX_PLAINTEXT_LEN, NONCE_SIZE};
 
 // Compile-time check: ensure at least one algorithm is enabled
 #[cfg(not(any(
     feature = "aes-256-gcm",
     feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
     feature = "chacha20-poly1305",
     feature = "xor"
 )))]
 compile_error!(
     "At least one encryption algorithm feature must be enabled: \
-     aes-256-gcm, aes-128-gcm, chacha20-poly1305, or xor"
+     aes-256-gcm, aes-128-gcm, aes-256-ccm, chacha20-poly1305, or xor"
 );
+
+// Compile-time check: streaming needs an AEAD to chunk
+#[cfg(all(
+    feature = "streaming",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
+        feature = "chacha20-poly1305"
+    ))
+))]
+compile_error!("The streaming feature requires an AEAD algorithm, not xor");
@@ -0,0 +1,103 @@
+//! HMAC manifest over embedded ciphertexts for tamper detection.
+//!
+//! The `obfuse_const!` macro computes an HMAC-SHA256 over the ciphertext of
+//! every string it declares and embeds the result in a [`Manifest`]. At
+//! runtime, [`Manifest::verify`] recomputes the HMAC over the live
+//! ciphertexts, detecting binaries whose ciphertext was patched.
+//!
+//! A manifest is scoped to one `obfuse_const!` block rather than the whole
+//! crate. A proc macro has no reliable crate-wide state: an accumulator
+//! written across expansions (e.g. under `OUT_DIR`) goes stale when
+//! incremental builds or IDEs re-expand call sites independently, so a
+//! precomputed crate-wide HMAC could not be trusted to match the binary.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::ObfuseStr;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Size of the HMAC key and tag (32 bytes).
+pub const MANIFEST_SIZE: usize = 32;
+
+/// A precomputed HMAC over a fixed set of obfuscated strings.
+///
+/// Like the strings themselves, the HMAC key is embedded in the binary, so
+/// this detects naive patching of individual ciphertexts, not an attacker who
+/// also rewrites the manifest.
+pub struct Manifest {
+    /// Strings covered by the manifest, in declaration order.
+    strings: &'static [&'static ObfuseStr],
+
+    /// HMAC key (embedded in binary).
+    key: [u8; MANIFEST_SIZE],
+
+    /// HMAC computed over the ciphertexts at compile time.
+    expected: [u8; MANIFEST_SIZE],
+}
+
+impl Manifest {
+    /// Creates a new `Manifest` from precomputed data.
+    ///
+    /// This is called by the `obfuse_const!` macro and should not be used directly.
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn new(
+        strings: &'static [&'static ObfuseStr],
+        key: [u8; MANIFEST_SIZE],
+        expected: [u8; MANIFEST_SIZE],
+    ) -> Self {
+        Self {
+            strings,
+            key,
+            expected,
+        }
+    }
+
+    /// Returns the HMAC computed at compile time.
+    #[must_use]
+    pub fn expected(&self) -> &[u8; MANIFEST_SIZE] {
+        &self.expected
+    }
+
+    /// Recomputes the HMAC over the live ciphertexts.
+    #[must_use]
+    pub fn compute(&self) -> [u8; MANIFEST_SIZE] {
+        manifest_hmac(&self.key, self.strings.iter().map(|s| s.ciphertext()))
+    }
+
+    /// Returns `true` if no covered ciphertext has been modified.
+    ///
+    /// The comparison is constant-time.
+    #[must_use]
+    pub fn verify(&self) -> bool {
+        new_mac(&self.key, self.strings.iter().map(|s| s.ciphertext()))
+            .verify_slice(&self.expected)
+            .is_ok()
+    }
+}
+
+/// Computes the manifest HMAC over a sequence of ciphertexts.
+///
+/// Each ciphertext is length-prefixed so that moving bytes between adjacent
+/// strings changes the result.
+#[must_use]
+pub fn manifest_hmac<'a>(
+    key: &[u8; MANIFEST_SIZE],
+    ciphertexts: impl IntoIterator<Item = &'a [u8]>,
+) -> [u8; MANIFEST_SIZE] {
+    new_mac(key, ciphertexts).finalize().into_bytes().into()
+}
+
+fn new_mac<'a>(
+    key: &[u8; MANIFEST_SIZE],
+    ciphertexts: impl IntoIterator<Item = &'a [u8]>,
+) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    for ciphertext in ciphertexts {
+        mac.update(&(ciphertext.len() as u64).to_le_bytes());
+        mac.update(ciphertext);
+    }
+    mac
+}
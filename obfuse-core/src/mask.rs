@@ -0,0 +1,155 @@
+//! Key masking: `ObfuseStr` stores `key XOR expand(nonce)`, with its bytes
+//! shuffled into a nonce-derived order, not the raw key.
+//!
+//! This keeps the raw key from sitting next to its nonce in the binary, which
+//! defeats naive key-recovery heuristics such as "a high-entropy 32-byte
+//! array next to a 12-byte one". It does not change the security model:
+//! anyone who finds these functions can undo the mask. The macro crate
+//! carries identical copies of [`nonce_rng`], [`expand_nonce`] and
+//! [`key_order`]; they must stay in sync.
+
+use zeroize::Zeroizing;
+
+use crate::{KEY_SIZE, NONCE_SIZE};
+
+/// Returns a `SplitMix64` generator seeded from `nonce` under `domain`.
+///
+/// The nonce is hashed with 64-bit FNV-1a, starting from `domain ^` the
+/// offset basis, into the generator's state. Every nonce-derived
+/// value (key mask, key order, padding, permutation) draws from its own
+/// domain, so they are independent of each other.
+pub(crate) fn nonce_rng(nonce: &[u8; NONCE_SIZE], domain: u64) -> impl FnMut() -> u64 {
+    let mut state = nonce
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64 ^ domain, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    move || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Domain of the XOR mask (the plain FNV-1a offset basis).
+const MASK_DOMAIN: u64 = 0;
+
+/// Deterministically stretches the nonce to `KEY_SIZE` bytes, eight bytes
+/// per [`nonce_rng`] output.
+pub fn expand_nonce(nonce: &[u8; NONCE_SIZE]) -> [u8; KEY_SIZE] {
+    let mut next = nonce_rng(nonce, MASK_DOMAIN);
+
+    let mut mask = [0u8; KEY_SIZE];
+    for chunk in mask.chunks_mut(8) {
+        chunk.copy_from_slice(&next().to_le_bytes()[..chunk.len()]);
+    }
+    mask
+}
+
+/// Domain separator keeping the key order independent of the XOR mask.
+const ORDER_DOMAIN: u64 = 0x6b65_795f_6f72_6465;
+
+/// Returns the order in which key bytes are stored for `nonce`.
+///
+/// A Fisher-Yates shuffle driven by [`nonce_rng`]; stored byte `i` holds
+/// masked key byte `order[i]`.
+pub fn key_order(nonce: &[u8; NONCE_SIZE]) -> [usize; KEY_SIZE] {
+    let mut next = nonce_rng(nonce, ORDER_DOMAIN);
+
+    let mut order: [usize; KEY_SIZE] = std::array::from_fn(|i| i);
+    for i in (1..KEY_SIZE).rev() {
+        // Only the low bits of the random value matter
+        #[allow(clippy::cast_possible_truncation)]
+        let j = (next() as usize) % (i + 1);
+        order.swap(i, j);
+    }
+    order
+}
+
+/// Masks a raw key in place into its stored form.
+pub fn hide(key: &mut [u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) {
+    xor(key, nonce);
+    let masked = Zeroizing::new(*key);
+    for (byte, source) in key.iter_mut().zip(key_order(nonce)) {
+        *byte = masked[source];
+    }
+}
+
+/// Recovers the raw key in place from its stored form.
+pub fn reveal(key: &mut [u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) {
+    let stored = Zeroizing::new(*key);
+    for (&byte, target) in stored.iter().zip(key_order(nonce)) {
+        key[target] = byte;
+    }
+    xor(key, nonce);
+}
+
+/// Applies or removes the XOR mask in place; the operation is its own inverse.
+fn xor(key: &mut [u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) {
+    for (byte, mask) in key.iter_mut().zip(expand_nonce(nonce)) {
+        *byte ^= mask;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_round_trips() {
+        let original: [u8; KEY_SIZE] = std::array::from_fn(|i| u8::try_from(i).unwrap());
+        let mut key = original;
+
+        hide(&mut key, &[7; NONCE_SIZE]);
+        assert_ne!(key, original);
+        reveal(&mut key, &[7; NONCE_SIZE]);
+        assert_eq!(key, original);
+    }
+
+    #[test]
+    fn test_stored_order_is_shuffled() {
+        let nonce = [7; NONCE_SIZE];
+        let original: [u8; KEY_SIZE] = std::array::from_fn(|i| u8::try_from(i).unwrap());
+
+        let mut xored = original;
+        xor(&mut xored, &nonce);
+        let mut stored = original;
+        hide(&mut stored, &nonce);
+
+        // Same bytes as the plain XOR mask, in a different order
+        assert_ne!(stored, xored);
+        let (mut sorted_stored, mut sorted_xored) = (stored, xored);
+        sorted_stored.sort_unstable();
+        sorted_xored.sort_unstable();
+        assert_eq!(sorted_stored, sorted_xored);
+    }
+
+    #[test]
+    fn test_key_order_is_bijection() {
+        for seed in 0..16 {
+            let mut order = key_order(&[seed; NONCE_SIZE]);
+            order.sort_unstable();
+            assert_eq!(order, std::array::from_fn(|i| i));
+        }
+    }
+
+    #[test]
+    fn test_nonce_rng_known_answer() {
+        // Pinned so the copy in the macro crate can be checked against it
+        let mut next = nonce_rng(&[7; NONCE_SIZE], ORDER_DOMAIN);
+        assert_eq!(
+            [next(), next()],
+            [0xd9a8_8f92_7417_a6aa, 0xc3ea_8af6_ac3e_9238]
+        );
+    }
+
+    #[test]
+    fn test_mask_depends_on_nonce() {
+        assert_ne!(
+            expand_nonce(&[0; NONCE_SIZE]),
+            expand_nonce(&[1; NONCE_SIZE])
+        );
+    }
+}
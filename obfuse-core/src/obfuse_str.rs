@@ -1,35 +1,69 @@
 //! The `ObfuseStr` type - lazy-decrypting obfuscated string with secure memory handling.
 
+use std::borrow::Cow;
+use std::ffi::{CStr, OsStr};
 use std::fmt;
+use std::hash::{BuildHasher, Hash, Hasher, RandomState};
 use std::ops::Deref;
-use std::sync::OnceLock;
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+#[cfg(feature = "honeytoken")]
+use std::sync::atomic::AtomicBool;
+#[cfg(feature = "access-metrics")]
+use std::sync::atomic::AtomicU64;
+#[cfg(any(feature = "access-metrics", feature = "honeytoken"))]
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
-use zeroize::Zeroize;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
-use crate::error::ObfuseError;
+use crate::blob::ObfuseBlob;
+use crate::error::{ObfuseError, ParseSecretError};
+use crate::reveal::{AlignedBytesGuard, Revealed, SecretBytesGuard};
+use crate::secret_vec::{self, SecretVec};
+use crate::{ALGORITHM, MAX_LAYERS, base64, hook, mask};
 
 // Import the appropriate crypto module based on features
 #[cfg(feature = "aes-256-gcm")]
-use crate::aes::{KEY_SIZE, NONCE_SIZE, decrypt};
+use crate::aes::{KEY_SIZE, MAX_CIPHERTEXT_LEN, NONCE_SIZE, decrypt};
 
 #[cfg(all(feature = "aes-128-gcm", not(feature = "aes-256-gcm")))]
-use crate::aes::{KEY_SIZE, NONCE_SIZE, decrypt};
+use crate::aes::{KEY_SIZE, MAX_CIPHERTEXT_LEN, NONCE_SIZE, decrypt};
 
 #[cfg(all(
-    feature = "chacha20-poly1305",
+    feature = "aes-256-ccm",
     not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
 ))]
-use crate::chacha::{KEY_SIZE, NONCE_SIZE, decrypt};
+use crate::aes_ccm::{KEY_SIZE, MAX_CIPHERTEXT_LEN, NONCE_SIZE, decrypt};
+
+#[cfg(all(
+    feature = "chacha20-poly1305",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm"
+    ))
+))]
+use crate::chacha::{KEY_SIZE, MAX_CIPHERTEXT_LEN, NONCE_SIZE, decrypt};
 
 #[cfg(all(
     feature = "xor",
     not(any(
         feature = "aes-256-gcm",
         feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
         feature = "chacha20-poly1305"
     ))
 ))]
-use crate::xor::{KEY_SIZE, NONCE_SIZE, decrypt};
+use crate::xor::{KEY_SIZE, MAX_CIPHERTEXT_LEN, NONCE_SIZE, decrypt};
+
+/// Signature shared by the built-in `decrypt` functions, and by user-supplied
+/// ciphers adapted to it.
+pub(crate) type DecryptFn =
+    fn(&[u8], &[u8; KEY_SIZE], &[u8; NONCE_SIZE]) -> Result<Box<[u8]>, ObfuseError>;
 
 /// An obfuscated string that decrypts lazily on first access.
 ///
@@ -44,29 +78,76 @@ use crate::xor::{KEY_SIZE, NONCE_SIZE, decrypt};
 ///
 /// # Thread Safety
 ///
-/// `ObfuseStr` is thread-safe. Multiple threads can call `as_str()` concurrently;
-/// decryption happens exactly once via `OnceLock`.
+/// `ObfuseStr` is `Send + Sync`, so it can live in a `static` and be shared
+/// across threads. Multiple threads can call `as_str()` concurrently;
+/// decryption happens exactly once via `OnceLock`. The guarantee is checked
+/// at compile time by `tests/thread_safety.rs`, and also holds for
+/// [`ObfuseError`] and [`Revealed`].
+///
+/// A panic during decryption (from a honeytoken callback) caches nothing,
+/// and a `OnceLock` has no poisoned state, so the next access simply
+/// decrypts again: it returns the plaintext without running the callback a
+/// second time. `ObfuseStr` is `UnwindSafe` and `RefUnwindSafe` accordingly.
+/// A panicking [`DecryptErrorHook`] never reaches the accessor; the panic is
+/// caught and discarded, and the original error is returned.
+///
+/// [`DecryptErrorHook`]: crate::DecryptErrorHook
+///
+/// # Key Size
+///
+/// Key and nonce storage is sized for the build's algorithm ([`KEY_SIZE`],
+/// [`NONCE_SIZE`]), so every `ObfuseStr` in a binary has the same layout. To
+/// mix AES-128 and AES-256 strings, enable `multi-aes`: keys are stored at
+/// 32 bytes and each ciphertext records which variant sealed it.
+///
+/// [`KEY_SIZE`]: crate::KEY_SIZE
+/// [`NONCE_SIZE`]: crate::NONCE_SIZE
 ///
 /// # Memory Safety
 ///
-/// On drop, all sensitive memory (key, nonce, decrypted plaintext) is zeroed
-/// using volatile writes that cannot be optimized away.
+/// On drop, all sensitive memory (key, nonce, decrypted plaintext, and owned
+/// ciphertext) is zeroed using volatile writes that cannot be optimized away.
 pub struct ObfuseStr {
-    /// Encrypted ciphertext (static lifetime from macro).
-    encrypted: &'static [u8],
+    /// Encrypted ciphertext (static from the macro, or owned).
+    encrypted: Cow<'static, [u8]>,
 
-    /// Encryption key (embedded in binary).
+    /// Encryption key, masked and shuffled by the nonce (see `mask`).
     key: [u8; KEY_SIZE],
 
     /// Nonce/IV for decryption.
     nonce: [u8; NONCE_SIZE],
 
+    /// Number of encryption layers; inner layers carry their own key and nonce.
+    layers: u8,
+
     /// Lazily initialized decrypted plaintext.
     decrypted: OnceLock<Box<[u8]>>,
+
+    /// Keyed hash of the plaintext, computed on the first `Hash::hash`.
+    plaintext_hash: OnceLock<u64>,
+
+    /// Shared copy of the plaintext handed out by `decrypt_cached_arc`.
+    shared: OnceLock<Arc<str>>,
+
+    /// User-supplied decryption from `encrypt_with`, replacing the built-in one.
+    #[cfg(feature = "runtime-encrypt")]
+    cipher: Option<DecryptFn>,
+
+    /// Number of `try_as_bytes` calls, including cache hits.
+    #[cfg(feature = "access-metrics")]
+    accesses: AtomicU64,
+
+    /// Honeytoken callback, run on the first decryption.
+    #[cfg(feature = "honeytoken")]
+    on_access: Option<fn()>,
+
+    /// Set once `on_access` has been run.
+    #[cfg(feature = "honeytoken")]
+    honey_fired: AtomicBool,
 }
 
 impl ObfuseStr {
-    /// Creates a new `ObfuseStr` from encrypted data.
+    /// Creates a new `ObfuseStr` from encrypted data and a masked key.
     ///
     /// This is called by the `obfuse!` macro and should not be used directly.
     #[doc(hidden)]
@@ -76,25 +157,233 @@ impl ObfuseStr {
         key: [u8; KEY_SIZE],
         nonce: [u8; NONCE_SIZE],
     ) -> Self {
+        Self::new_layered(encrypted, key, nonce, 1)
+    }
+
+    /// Creates a new `ObfuseStr` from data encrypted in `layers` layers.
+    ///
+    /// This is called by the `obfuse!` macro for `layers = N` and should not
+    /// be used directly.
+    ///
+    /// # Panics
+    ///
+    /// Panics (at compile time in `static` initializers) if `layers` is not
+    /// in `1..=MAX_LAYERS`.
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn new_layered(
+        encrypted: &'static [u8],
+        key: [u8; KEY_SIZE],
+        nonce: [u8; NONCE_SIZE],
+        layers: u8,
+    ) -> Self {
+        assert!(layers >= 1 && layers <= MAX_LAYERS, "layers out of range");
+
+        Self {
+            encrypted: Cow::Borrowed(encrypted),
+            key,
+            nonce,
+            layers,
+            decrypted: OnceLock::new(),
+            plaintext_hash: OnceLock::new(),
+            shared: OnceLock::new(),
+            #[cfg(feature = "runtime-encrypt")]
+            cipher: None,
+            #[cfg(feature = "access-metrics")]
+            accesses: AtomicU64::new(0),
+            #[cfg(feature = "honeytoken")]
+            on_access: None,
+            #[cfg(feature = "honeytoken")]
+            honey_fired: AtomicBool::new(false),
+        }
+    }
+
+    /// Marks the string as a honeytoken: `callback` runs on its first decryption.
+    ///
+    /// This is called by the `obfuse_honey!` macro and should not be used
+    /// directly.
+    #[cfg(feature = "honeytoken")]
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn with_on_access(mut self, callback: fn()) -> Self {
+        self.on_access = Some(callback);
+        self
+    }
+
+    /// Creates an `ObfuseStr` from owned parts assembled at runtime.
+    ///
+    /// The owned counterpart to the `const` constructor used by the macros,
+    /// for ciphertext loaded or built at runtime. The ciphertext is zeroed
+    /// when the `ObfuseStr` is dropped.
+    ///
+    /// The parts are not validated here. The caller must ensure that:
+    /// - `ciphertext` was produced by the enabled algorithm under `key` and
+    ///   `nonce`, in the same format the macros emit (including the AEAD tag,
+    ///   the `multi-aes` key-size prefix, or the `polymorphic` program); with
+    ///   `ciphertext-padding`, wrap it with `pad_ciphertext` first, and with
+    ///   `permute-ciphertext`, then shuffle it with `permute_ciphertext`
+    /// - for AEAD algorithms, `ciphertext` is at least as long as the tag
+    ///
+    /// Violating these is not memory-unsafe: decryption fails, so the `try_*`
+    /// accessors return an error and the panicking accessors panic.
+    #[must_use]
+    pub fn new_from_owned(
+        ciphertext: Vec<u8>,
+        mut key: [u8; KEY_SIZE],
+        nonce: [u8; NONCE_SIZE],
+    ) -> Self {
+        mask::hide(&mut key, &nonce);
+
         Self {
-            encrypted,
+            encrypted: Cow::Owned(ciphertext),
             key,
             nonce,
+            layers: 1,
             decrypted: OnceLock::new(),
+            plaintext_hash: OnceLock::new(),
+            shared: OnceLock::new(),
+            #[cfg(feature = "runtime-encrypt")]
+            cipher: None,
+            #[cfg(feature = "access-metrics")]
+            accesses: AtomicU64::new(0),
+            #[cfg(feature = "honeytoken")]
+            on_access: None,
+            #[cfg(feature = "honeytoken")]
+            honey_fired: AtomicBool::new(false),
+        }
+    }
+
+    /// Exports the string as one copy-pasteable line, for config files or
+    /// passing between processes: `ALGORITHM|nonce|ciphertext|key`, with the
+    /// binary fields in padded base64 and `|layers` appended for layered
+    /// strings. Nothing is decrypted.
+    ///
+    /// The export carries the key in the clear, so it is exactly as secret
+    /// as the plaintext. Read it back with [`from_base64`](Self::from_base64)
+    /// in a build with the same algorithm feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ObfuseError::CustomCipher`] if the string was encrypted with
+    /// `encrypt_with` (`runtime-encrypt` feature), whose cipher the export
+    /// cannot name.
+    pub fn to_base64(&self) -> Result<String, ObfuseError> {
+        #[cfg(feature = "runtime-encrypt")]
+        if self.cipher.is_some() {
+            return Err(ObfuseError::CustomCipher);
+        }
+
+        let mut key = Zeroizing::new(self.key);
+        mask::reveal(&mut key, &self.nonce);
+
+        let layers = if self.layers > 1 {
+            format!("|{}", self.layers)
+        } else {
+            String::new()
+        };
+        Ok(format!(
+            "{ALGORITHM}|{}|{}|{}{layers}",
+            base64::encode(&self.nonce),
+            base64::encode(&self.encrypted),
+            base64::encode(key.as_slice()),
+        ))
+    }
+
+    /// Parses an export from [`to_base64`](Self::to_base64).
+    ///
+    /// The ciphertext is not authenticated here; tampering surfaces on first
+    /// access as [`ObfuseError::AuthenticationFailed`] (or garbage with XOR).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ObfuseError::InvalidEncoding`] if the export is malformed:
+    /// a wrong field count, invalid or unpadded base64, a key or nonce of the
+    /// wrong length, a layer count outside `1..=MAX_LAYERS`, or an algorithm
+    /// other than the compiled-in one. Returns
+    /// [`ObfuseError::SizeLimitExceeded`] if the ciphertext is too long.
+    pub fn from_base64(export: &str) -> Result<Self, ObfuseError> {
+        let mut fields = export.trim().split('|');
+        let (Some(algorithm), Some(nonce), Some(ciphertext), Some(key)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(ObfuseError::InvalidEncoding("expected at least 4 fields"));
+        };
+        let layers = match (fields.next(), fields.next()) {
+            (None, _) => 1,
+            (Some(layers), None) => layers
+                .parse()
+                .ok()
+                .filter(|layers| (1..=MAX_LAYERS).contains(layers))
+                .ok_or(ObfuseError::InvalidEncoding("layer count out of range"))?,
+            (Some(_), Some(_)) => return Err(ObfuseError::InvalidEncoding("too many fields")),
+        };
+
+        if algorithm != ALGORITHM {
+            return Err(ObfuseError::InvalidEncoding(
+                "exported with another algorithm",
+            ));
+        }
+        let decode =
+            |field| base64::decode(field).ok_or(ObfuseError::InvalidEncoding("bad base64"));
+        let nonce = <[u8; NONCE_SIZE]>::try_from(decode(nonce)?.as_slice())
+            .map_err(|_| ObfuseError::InvalidEncoding("wrong nonce length"))?;
+        let key = Zeroizing::new(
+            <[u8; KEY_SIZE]>::try_from(decode(key)?.as_slice())
+                .map_err(|_| ObfuseError::InvalidEncoding("wrong key length"))?,
+        );
+        let ciphertext = decode(ciphertext)?;
+        check_size_limit(ciphertext.len())?;
+
+        let mut secret = Self::new_from_owned(ciphertext.to_vec(), *key, nonce);
+        secret.layers = layers;
+        Ok(secret)
+    }
+
+    /// Builds an `ObfuseStr` from a C-ABI [`ObfuseBlob`] and its ciphertext,
+    /// for secrets encrypted by a non-Rust build step and linked in.
+    ///
+    /// The ciphertext is copied, so `ciphertext` may point into memory owned
+    /// by foreign code. As with [`new_from_owned`](Self::new_from_owned), it
+    /// is not authenticated here; a mismatch surfaces on first access.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ObfuseError::InvalidBlob`] if `ciphertext` is not
+    /// `blob.ciphertext_len` bytes long or `blob.layers` is outside
+    /// `1..=MAX_LAYERS`, and [`ObfuseError::SizeLimitExceeded`] if the
+    /// ciphertext is too long.
+    pub fn from_c_blob(blob: &ObfuseBlob, ciphertext: &[u8]) -> Result<Self, ObfuseError> {
+        if ciphertext.len() != blob.ciphertext_len {
+            return Err(ObfuseError::InvalidBlob(
+                "ciphertext length does not match the blob",
+            ));
+        }
+        if !(1..=MAX_LAYERS).contains(&blob.layers) {
+            return Err(ObfuseError::InvalidBlob("layer count out of range"));
         }
+        check_size_limit(ciphertext.len())?;
+
+        let mut secret = Self::new_from_owned(ciphertext.to_vec(), blob.key, blob.nonce);
+        secret.layers = blob.layers;
+        Ok(secret)
     }
 
     /// Returns the decrypted string, decrypting on first access.
     ///
     /// # Panics
     ///
-    /// Panics if decryption fails. For fallible decryption, use [`try_as_str`].
+    /// Panics if decryption fails, reporting the caller's location. For fallible
+    /// decryption, use [`try_as_str`].
     ///
     /// [`try_as_str`]: Self::try_as_str
     #[inline]
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    #[track_caller]
     pub fn as_str(&self) -> &str {
-        self.try_as_str()
-            .unwrap_or_else(|e| panic!("ObfuseStr decryption failed: {e}"))
+        match self.try_as_str() {
+            Ok(value) => value,
+            Err(e) => decryption_failed(&e),
+        }
     }
 
     /// Returns the decrypted string, or an error if decryption fails.
@@ -107,20 +396,153 @@ impl ObfuseStr {
     /// Returns an error if:
     /// - Decryption fails (authentication error or corrupted data)
     /// - The decrypted bytes are not valid UTF-8
+    #[must_use = "decrypting without using the result widens the exposure window"]
     pub fn try_as_str(&self) -> Result<&str, ObfuseError> {
         let bytes = self.try_as_bytes()?;
         std::str::from_utf8(bytes).map_err(ObfuseError::from)
     }
 
+    /// Returns the decrypted string together with the time spent decrypting it.
+    ///
+    /// A profiling aid for tuning startup. The duration covers decryption
+    /// and UTF-8 validation on the first access, and is zero when the
+    /// plaintext was already cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    pub fn as_str_timed(&self) -> Result<(&str, Duration), ObfuseError> {
+        if self.is_decrypted() {
+            return Ok((self.try_as_str()?, Duration::ZERO));
+        }
+
+        let start = Instant::now();
+        let plaintext = self.try_as_str()?;
+        Ok((plaintext, start.elapsed()))
+    }
+
+    /// Returns the decrypted string, giving up once `deadline` passes.
+    ///
+    /// With the `streaming` feature the deadline is checked before each
+    /// 64 KiB chunk, so a large secret stops early, wiping the chunks
+    /// decrypted so far. Otherwise decryption is all-or-nothing: the
+    /// deadline is only checked before starting. A cached plaintext is
+    /// returned regardless of the deadline.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ObfuseError::TimedOut`] if the deadline passes first,
+    /// leaving nothing cached, or another error if decryption fails or the
+    /// plaintext is not valid UTF-8.
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    pub fn decrypt_with_budget(&self, deadline: Instant) -> Result<&str, ObfuseError> {
+        if self.is_decrypted() {
+            return self.try_as_str();
+        }
+        if Instant::now() >= deadline {
+            return Err(ObfuseError::TimedOut);
+        }
+
+        #[cfg(feature = "streaming")]
+        return crate::stream::with_deadline(deadline, || self.try_as_str());
+        #[cfg(not(feature = "streaming"))]
+        self.try_as_str()
+    }
+
+    /// Returns the decrypted string only if `predicate` returns `true`.
+    ///
+    /// For secrets that are often not needed (e.g. credentials for an
+    /// optional backend): when the predicate is false, nothing is decrypted
+    /// or cached and `Ok(None)` is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predicate holds and decryption fails or the
+    /// plaintext is not valid UTF-8.
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    pub fn decrypt_if(
+        &self,
+        predicate: impl FnOnce() -> bool,
+    ) -> Result<Option<&str>, ObfuseError> {
+        if predicate() {
+            self.try_as_str().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the decrypted string with invalid UTF-8 replaced by `U+FFFD`.
+    ///
+    /// A best-effort view for legacy secrets that may not be valid UTF-8,
+    /// e.g. for diagnostics. Unlike [`try_as_str`], which is strict, this
+    /// never returns [`ObfuseError::InvalidUtf8`]. Valid plaintext is borrowed
+    /// from the cache; otherwise the replaced copy is an ordinary `String`
+    /// that is not wiped on drop.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails (authentication error or corrupted data).
+    ///
+    /// [`try_as_str`]: Self::try_as_str
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    pub fn as_str_lossy(&self) -> Result<Cow<'_, str>, ObfuseError> {
+        self.try_as_bytes().map(String::from_utf8_lossy)
+    }
+
+    /// Returns `true` if the plaintext is entirely ASCII.
+    ///
+    /// Decrypts (and caches) on first access, then delegates to
+    /// [`str::is_ascii`], which remains available through `Deref` as a
+    /// panicking variant.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    pub fn is_ascii(&self) -> Result<bool, ObfuseError> {
+        self.try_as_str().map(str::is_ascii)
+    }
+
+    /// Returns `true` if the plaintext is empty.
+    ///
+    /// Decrypts (and caches) on first access, like [`is_ascii`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    ///
+    /// [`is_ascii`]: Self::is_ascii
+    pub fn is_empty(&self) -> Result<bool, ObfuseError> {
+        self.try_as_str().map(str::is_empty)
+    }
+
+    /// Returns the number of `char`s in the plaintext.
+    ///
+    /// Unlike [`str::len`], which counts bytes, this counts Unicode scalar
+    /// values. Decrypts (and caches) on first access, like [`is_ascii`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    ///
+    /// [`is_ascii`]: Self::is_ascii
+    pub fn char_count(&self) -> Result<usize, ObfuseError> {
+        self.try_as_str().map(|plaintext| plaintext.chars().count())
+    }
+
     /// Returns the decrypted bytes, decrypting on first access.
     ///
     /// # Panics
     ///
     /// Panics if decryption fails.
     #[inline]
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    #[track_caller]
     pub fn as_bytes(&self) -> &[u8] {
-        self.try_as_bytes()
-            .unwrap_or_else(|e| panic!("ObfuseStr decryption failed: {e}"))
+        match self.try_as_bytes() {
+            Ok(value) => value,
+            Err(e) => decryption_failed(&e),
+        }
     }
 
     /// Returns the decrypted bytes, or an error if decryption fails.
@@ -134,15 +556,31 @@ impl ObfuseStr {
     ///
     /// This function should never panic under normal circumstances. The internal
     /// `expect` is a safeguard that triggers only if the `OnceLock` fails to store
-    /// a value, which cannot happen in correct usage.
+    /// a value, which cannot happen in correct usage. A panicking honeytoken
+    /// callback propagates, leaving the cache empty; the next call decrypts
+    /// without running the callback again. A panicking decrypt-error hook is
+    /// caught and discarded, so the error is still returned.
+    #[must_use = "decrypting without using the result widens the exposure window"]
     pub fn try_as_bytes(&self) -> Result<&[u8], ObfuseError> {
+        #[cfg(feature = "access-metrics")]
+        self.accesses.fetch_add(1, Ordering::Relaxed);
+
         // Use get_or_init with internal error handling since get_or_try_init is unstable
         if let Some(cached) = self.decrypted.get() {
             return Ok(cached.as_ref());
         }
 
         // Perform decryption
-        let plaintext = decrypt(self.encrypted, &self.key, &self.nonce)?;
+        #[cfg(not(feature = "decoy-code"))]
+        let plaintext = self.decrypt_layers()?;
+
+        // Never taken; leaves a plausible second decrypt routine in the binary
+        #[cfg(feature = "decoy-code")]
+        let plaintext = if crate::decoy::opaque_false(self.encrypted.len()) {
+            crate::decoy::decrypt(decrypt, &self.encrypted, &self.key, &self.nonce)?
+        } else {
+            self.decrypt_layers()?
+        };
 
         // Try to store result, handling race condition gracefully
         // If another thread beat us, their result is equivalent
@@ -154,97 +592,1375 @@ impl ObfuseStr {
         Ok(self.decrypted.get().expect("value was just set").as_ref())
     }
 
-    /// Returns `true` if the string has already been decrypted.
+    /// Returns the decrypted string as an [`OsStr`], decrypting on first access.
     ///
-    /// This can be used to check if accessing the string will trigger decryption.
+    /// # Panics
+    ///
+    /// Panics if decryption fails. For fallible decryption, use [`try_as_os_str`].
+    ///
+    /// [`try_as_os_str`]: Self::try_as_os_str
     #[inline]
-    pub fn is_decrypted(&self) -> bool {
-        self.decrypted.get().is_some()
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    #[track_caller]
+    pub fn as_os_str(&self) -> &OsStr {
+        match self.try_as_os_str() {
+            Ok(value) => value,
+            Err(e) => decryption_failed(&e),
+        }
     }
 
-    /// Pre-decrypts the string without returning the value.
+    /// Returns the decrypted string as an [`OsStr`], or an error if decryption fails.
     ///
-    /// Useful for warming up the cache before time-critical operations.
+    /// On Unix the raw plaintext bytes are used as-is, so non-UTF-8 paths are
+    /// supported. On other platforms the plaintext must be valid UTF-8.
     ///
     /// # Errors
     ///
-    /// Returns an error if decryption fails.
-    pub fn try_decrypt(&self) -> Result<(), ObfuseError> {
-        self.try_as_bytes().map(|_| ())
+    /// Returns an error if decryption fails, or (on non-Unix platforms) if the
+    /// decrypted bytes are not valid UTF-8.
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    pub fn try_as_os_str(&self) -> Result<&OsStr, ObfuseError> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            self.try_as_bytes().map(OsStr::from_bytes)
+        }
+
+        #[cfg(not(unix))]
+        {
+            self.try_as_str().map(OsStr::new)
+        }
     }
 
-    /// Manually zeros all sensitive memory.
+    /// Returns the decrypted string as a [`CStr`] for FFI, decrypting on first access.
     ///
-    /// This is also called automatically on drop, but can be used to
-    /// clear memory earlier if needed.
+    /// # Panics
     ///
-    /// # Note
+    /// Panics if decryption fails or the plaintext is not a valid C string.
+    /// For fallible access, use [`try_as_c_str`].
     ///
-    /// After calling this, the `ObfuseStr` will re-decrypt on next access
-    /// (though the `OnceLock` prevents this - this method exists for the Drop impl).
-    pub fn zeroize(&mut self) {
-        self.key.zeroize();
-        self.nonce.zeroize();
+    /// [`try_as_c_str`]: Self::try_as_c_str
+    #[inline]
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    #[track_caller]
+    pub fn as_c_str(&self) -> &CStr {
+        match self.try_as_c_str() {
+            Ok(value) => value,
+            Err(e) => decryption_failed(&e),
+        }
+    }
 
-        // Zero the decrypted plaintext if it exists
-        if let Some(decrypted) = self.decrypted.get_mut() {
-            decrypted.zeroize();
+    /// Returns the decrypted string as a [`CStr`], or an error if it is not one.
+    ///
+    /// The plaintext must end with a single NUL terminator and contain no other
+    /// NUL bytes, e.g. `obfuse!("secret\0")`. No copy is made; the returned
+    /// `CStr` borrows the cached plaintext.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails, or [`ObfuseError::InteriorNul`] if
+    /// the terminator is missing or a NUL appears earlier in the plaintext.
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    pub fn try_as_c_str(&self) -> Result<&CStr, ObfuseError> {
+        Ok(CStr::from_bytes_with_nul(self.try_as_bytes()?)?)
+    }
+
+    /// Decrypts a fresh copy into a NUL-terminated buffer and passes it to `f`
+    /// as a [`CStr`].
+    ///
+    /// For handing a secret to a C function without caching it in `self`.
+    /// A terminator is appended unless the plaintext already ends with one,
+    /// so both `obfuse!("secret")` and `obfuse!("secret\0")` work. The buffer
+    /// is wiped as soon as `f` returns; `f` must not keep the pointer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails, or [`ObfuseError::InteriorNul`] if
+    /// a NUL appears before the end of the plaintext.
+    pub fn with_c_str<R>(&self, f: impl FnOnce(&CStr) -> R) -> Result<R, ObfuseError> {
+        let mut plaintext = self.decrypt_layers()?;
+
+        // Copy into a buffer with room for the terminator, so pushing it
+        // cannot reallocate and leave an unwiped copy behind
+        let mut buffer = Zeroizing::new(Vec::with_capacity(plaintext.len() + 1));
+        buffer.extend_from_slice(&plaintext);
+        plaintext.zeroize();
+        if buffer.last() != Some(&0) {
+            buffer.push(0);
         }
+
+        let c_str = CStr::from_bytes_with_nul(&buffer)?;
+        Ok(f(c_str))
     }
-}
 
-impl Deref for ObfuseStr {
-    type Target = str;
+    /// Decrypts a fresh copy and sets it as environment variable `var` for a
+    /// child process spawned from `command`.
+    ///
+    /// The decrypted scratch is wiped before this returns and nothing is
+    /// cached in `self`, but `Command` keeps its own copy of the value that
+    /// is never wiped, and the variable is readable by the child, anything
+    /// it spawns, and (on most systems) by other processes of the same user
+    /// via `/proc/<pid>/environ` or `ps e`. Prefer a pipe or file descriptor
+    /// when the child supports one.
+    ///
+    /// The current process's own environment is not touched: setting it
+    /// with `std::env::set_var` is `unsafe` in Rust 2024 (it races with
+    /// other threads reading the environment), which this crate forbids.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid
+    /// UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut child = Command::new("psql");
+    /// obfuse!("hunter2").reveal_to_env(&mut child, "PGPASSWORD")?;
+    /// child.status()?;
+    /// ```
+    pub fn reveal_to_env(&self, command: &mut Command, var: &str) -> Result<(), ObfuseError> {
+        let plaintext = Zeroizing::new(self.decrypt_layers()?);
+        command.env(var, std::str::from_utf8(&plaintext)?);
+        Ok(())
+    }
 
-    #[inline]
-    fn deref(&self) -> &Self::Target {
-        self.as_str()
+    /// Decrypts a fresh copy, encodes it as UTF-16 and passes the code units
+    /// to `f`, e.g. for Win32 `W` APIs that take a length.
+    ///
+    /// Like [`with_c_str`](Self::with_c_str), nothing is cached in `self` and
+    /// both the decrypted bytes and the UTF-16 buffer are wiped before this
+    /// returns; `f` must not keep the pointer. Use
+    /// [`with_utf16_nul`](Self::with_utf16_nul) for `PCWSTR` parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    #[cfg(feature = "utf16")]
+    pub fn with_utf16<R>(&self, f: impl FnOnce(&[u16]) -> R) -> Result<R, ObfuseError> {
+        self.encode_utf16_scoped(false, f)
     }
-}
 
-impl AsRef<str> for ObfuseStr {
-    #[inline]
-    fn as_ref(&self) -> &str {
-        self.as_str()
+    /// Like [`with_utf16`](Self::with_utf16), but the code units end with a
+    /// NUL terminator, as `PCWSTR` expects.
+    ///
+    /// A terminator is appended unless the plaintext already ends with one.
+    /// An earlier NUL is passed through, so C-style callees see the string
+    /// cut short there.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    #[cfg(feature = "utf16")]
+    pub fn with_utf16_nul<R>(&self, f: impl FnOnce(&[u16]) -> R) -> Result<R, ObfuseError> {
+        self.encode_utf16_scoped(true, f)
     }
-}
 
-impl AsRef<[u8]> for ObfuseStr {
-    #[inline]
-    fn as_ref(&self) -> &[u8] {
-        self.as_bytes()
+    /// Shared body of `with_utf16` and `with_utf16_nul`.
+    #[cfg(feature = "utf16")]
+    fn encode_utf16_scoped<R>(
+        &self,
+        nul_terminate: bool,
+        f: impl FnOnce(&[u16]) -> R,
+    ) -> Result<R, ObfuseError> {
+        let plaintext = Zeroizing::new(self.decrypt_layers()?);
+        let text = std::str::from_utf8(&plaintext)?;
+
+        // Size the buffer up front so growing it cannot leave an unwiped copy
+        let mut wide = Zeroizing::new(Vec::with_capacity(
+            text.encode_utf16().count() + usize::from(nul_terminate),
+        ));
+        wide.extend(text.encode_utf16());
+        if nul_terminate && wide.last() != Some(&0) {
+            wide.push(0);
+        }
+
+        Ok(f(&wide))
     }
-}
 
-impl fmt::Debug for ObfuseStr {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("ObfuseStr")
-            .field("value", &"[REDACTED]")
-            .field("decrypted", &self.is_decrypted())
-            .finish()
+    /// Returns the decrypted string as a [`Path`], decrypting on first access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if decryption fails. For fallible decryption, use [`try_as_path`].
+    ///
+    /// [`try_as_path`]: Self::try_as_path
+    #[inline]
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    #[track_caller]
+    pub fn as_path(&self) -> &Path {
+        Path::new(self.as_os_str())
     }
-}
 
-impl fmt::Display for ObfuseStr {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.as_str())
+    /// Returns the decrypted string as a [`Path`], or an error if decryption fails.
+    ///
+    /// # Errors
+    ///
+    /// See [`try_as_os_str`](Self::try_as_os_str).
+    #[must_use = "decrypting without using the result widens the exposure window"]
+    pub fn try_as_path(&self) -> Result<&Path, ObfuseError> {
+        self.try_as_os_str().map(Path::new)
     }
-}
 
-impl Drop for ObfuseStr {
-    fn drop(&mut self) {
-        self.zeroize();
+    /// Returns the embedded ciphertext without decrypting it.
+    ///
+    /// Useful for integrity checks over the data stored in the binary. With
+    /// `permute-ciphertext`, this is the stored (shuffled) order.
+    #[inline]
+    #[must_use]
+    pub fn ciphertext(&self) -> &[u8] {
+        &self.encrypted
     }
-}
 
-// Note: ObfuseStr is Send + Sync because:
-// - &'static [u8] is Send + Sync
-// - [u8; N] arrays are Send + Sync
-// - OnceLock<Box<[u8]>> is Send + Sync
-// The derive is automatic since all fields are Send + Sync.
+    /// Decrypts a fresh copy of the string into a guard that wipes it on drop.
+    ///
+    /// The plaintext is not cached in `self`, so it exists in memory only
+    /// while the returned [`Revealed`] is alive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if decryption fails. For fallible decryption, use [`try_reveal`].
+    ///
+    /// [`try_reveal`]: Self::try_reveal
+    #[track_caller]
+    pub fn reveal(&self) -> Revealed {
+        match self.try_reveal() {
+            Ok(value) => value,
+            Err(e) => decryption_failed(&e),
+        }
+    }
+
+    /// Decrypts a fresh copy of the string, or returns an error if decryption fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Decryption fails (authentication error or corrupted data)
+    /// - The decrypted bytes are not valid UTF-8
+    pub fn try_reveal(&self) -> Result<Revealed, ObfuseError> {
+        self.decrypt_string().map(Revealed::new)
+    }
+
+    /// Decrypts a fresh copy into a [`secrecy::SecretString`].
+    ///
+    /// For handing the secret across a trust boundary to code built on
+    /// `secrecy`. The decrypted buffer is moved into the `SecretString`
+    /// without copying, so no other plaintext copy remains; nothing is cached
+    /// in `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    #[cfg(feature = "secrecy")]
+    pub fn to_secret_string(&self) -> Result<secrecy::SecretString, ObfuseError> {
+        self.decrypt_string().map(secrecy::SecretString::from)
+    }
+
+    /// Decrypts a fresh, uncached `String`, wiping the bytes if they are not UTF-8.
+    pub(crate) fn decrypt_string(&self) -> Result<String, ObfuseError> {
+        let plaintext = self.decrypt_layers()?;
+
+        String::from_utf8(plaintext.into_vec()).map_err(|e| {
+            let error = e.utf8_error();
+            e.into_bytes().zeroize();
+            ObfuseError::from(error)
+        })
+    }
+
+    /// Decrypts a fresh copy of the string and parses it with [`FromStr`].
+    ///
+    /// For secrets consumed as numbers, URLs, UUIDs and the like. The
+    /// plaintext is decrypted into a temporary [`Revealed`] that is wiped
+    /// before returning, so neither it nor an intermediate `String` outlives
+    /// the call. Nothing is cached in `self`.
+    ///
+    /// Shadows [`str::parse`], which remains available as
+    /// `secret.as_str().parse()` (and caches the plaintext).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseSecretError::Decrypt`] if decryption fails or the
+    /// plaintext is not valid UTF-8, and [`ParseSecretError::Parse`] if
+    /// `T::from_str` fails.
+    pub fn parse<T: FromStr>(&self) -> Result<T, ParseSecretError<T::Err>> {
+        let revealed = self.try_reveal()?;
+        revealed.parse().map_err(ParseSecretError::Parse)
+    }
+
+    /// Decrypts a fresh copy of the raw bytes into a guard that wipes it on drop.
+    ///
+    /// The bytes-oriented counterpart of [`try_reveal`] for binary secrets:
+    /// the plaintext is not required to be UTF-8 and is not cached in `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails (authentication error or corrupted data).
+    ///
+    /// [`try_reveal`]: Self::try_reveal
+    pub fn reveal_bytes(&self) -> Result<SecretBytesGuard<'_>, ObfuseError> {
+        self.decrypt_layers().map(SecretBytesGuard::new)
+    }
+
+    /// Decrypts a fresh copy into a buffer whose first byte is aligned to
+    /// `A` bytes, e.g. `decrypt_aligned::<16>()` for 128-bit SIMD loads.
+    ///
+    /// Saves consumers a realigning copy of their own; the plaintext is
+    /// copied once into the aligned buffer internally, and that scratch copy
+    /// is wiped. Nothing is cached in `self`. `A` must be a power of two,
+    /// checked at compile time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails, or
+    /// [`ObfuseError::AllocationFailed`] if the buffer cannot be allocated.
+    pub fn decrypt_aligned<const A: usize>(&self) -> Result<AlignedBytesGuard<'_, A>, ObfuseError> {
+        AlignedBytesGuard::new(self.decrypt_layers()?)
+    }
+
+    /// Returns an owned copy of the plaintext bytes that wipes itself on drop.
+    ///
+    /// Decrypts (and caches) on first access, then copies the cached bytes,
+    /// so the result can be moved to another thread without borrowing from
+    /// `self`. Unlike [`reveal_bytes`], the plaintext stays cached here.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails, or [`ObfuseError::AllocationFailed`]
+    /// if the copy cannot be allocated.
+    ///
+    /// [`reveal_bytes`]: Self::reveal_bytes
+    pub fn try_clone_decrypted(&self) -> Result<Zeroizing<Vec<u8>>, ObfuseError> {
+        let plaintext = self.try_as_bytes()?;
+
+        let mut copy = Vec::new();
+        copy.try_reserve_exact(plaintext.len())
+            .map_err(|_| ObfuseError::AllocationFailed)?;
+        copy.extend_from_slice(plaintext);
+        Ok(Zeroizing::new(copy))
+    }
+
+    /// Returns the plaintext as an [`Arc<str>`], decrypting it on the first
+    /// call and handing out clones of the same `Arc` afterwards.
+    ///
+    /// For sharing the secret between components that must not borrow from
+    /// `self`, e.g. across threads or tasks. The `Arc` is built from a fresh
+    /// decryption and does not fill the [`as_str`](Self::as_str) cache.
+    ///
+    /// This trades exposure for shareability: the plaintext stays in memory
+    /// as long as any clone of the `Arc` exists, even after `self` is
+    /// dropped. [`zeroize`](Self::zeroize) and drop wipe it only if no clone
+    /// is still held, since a shared `str` cannot be overwritten.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    pub fn decrypt_cached_arc(&self) -> Result<Arc<str>, ObfuseError> {
+        if let Some(shared) = self.shared.get() {
+            return Ok(Arc::clone(shared));
+        }
+
+        // If another thread stores its copy first, ours stays in the wiped `String`
+        let plaintext = Zeroizing::new(self.decrypt_string()?);
+        let shared = self.shared.get_or_init(|| Arc::from(plaintext.as_str()));
+        Ok(Arc::clone(shared))
+    }
+
+    /// Decrypts a fresh copy into a fixed-size array, e.g. a 32-byte key.
+    ///
+    /// The plaintext is not cached in `self`, and the intermediate buffer is
+    /// wiped before this returns. The array itself is a plain `Copy` value:
+    /// wrap it in [`Zeroizing`] to have it wiped on drop.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails, or [`ObfuseError::WrongLength`]
+    /// if the plaintext is not exactly `N` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let key = Zeroizing::new(obfuse!("...32 bytes...").try_into_fixed::<32>()?);
+    /// ```
+    pub fn try_into_fixed<const N: usize>(&self) -> Result<[u8; N], ObfuseError> {
+        let plaintext = Zeroizing::new(self.decrypt_layers()?);
+
+        <[u8; N]>::try_from(&plaintext[..]).map_err(|_| ObfuseError::WrongLength {
+            expected: N,
+            actual: plaintext.len(),
+        })
+    }
+
+    /// Decrypts a fresh copy and XORs it with `mask`, for split-secret schemes.
+    ///
+    /// The result is one share of a two-way XOR split: applying `mask` to it
+    /// again recombines the plaintext. The plaintext is not cached in `self`;
+    /// it is masked in place, so no unmasked copy outlives the call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails, or [`ObfuseError::WrongLength`]
+    /// if `mask` is not exactly as long as the plaintext.
+    pub fn xor_with(&self, mask: &[u8]) -> Result<Zeroizing<Vec<u8>>, ObfuseError> {
+        let mut plaintext = Zeroizing::new(self.decrypt_layers()?.into_vec());
+        if mask.len() != plaintext.len() {
+            return Err(ObfuseError::WrongLength {
+                expected: mask.len(),
+                actual: plaintext.len(),
+            });
+        }
+
+        for (byte, m) in plaintext.iter_mut().zip(mask) {
+            *byte ^= m;
+        }
+        Ok(plaintext)
+    }
+
+    /// Decrypts a fresh copy and appends it to `out`.
+    ///
+    /// For assembling one buffer from several secrets, e.g. `user:pass`. The
+    /// plaintext is not required to be UTF-8 and is not cached in `self`;
+    /// wiping `out` is up to the caller. If `out` has to grow, its old
+    /// contents are copied into the new allocation and the old one is wiped
+    /// before it is freed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails, or [`ObfuseError::AllocationFailed`]
+    /// if `out` cannot grow. `out` is left unchanged on error.
+    pub fn decrypt_append(&self, out: &mut Vec<u8>) -> Result<(), ObfuseError> {
+        let plaintext = Zeroizing::new(self.decrypt_layers()?);
+        secret_vec::grow_wiping(out, plaintext.len())?;
+        out.extend_from_slice(&plaintext);
+        Ok(())
+    }
+
+    /// Decrypts a fresh copy into `buffer`, replacing its contents.
+    ///
+    /// For decrypting repeatedly into one long-lived buffer: the old contents
+    /// are wiped, the allocation is reused when it is large enough, and if it
+    /// has to grow the old allocation is wiped before it is freed. The
+    /// plaintext is not cached in `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails, or [`ObfuseError::AllocationFailed`]
+    /// if `buffer` cannot grow. `buffer` is left unchanged on error.
+    pub fn decrypt_into_secret_vec(&self, buffer: &mut SecretVec) -> Result<(), ObfuseError> {
+        let plaintext = Zeroizing::new(self.decrypt_layers()?);
+        let additional = plaintext.len().saturating_sub(buffer.len());
+        buffer.try_reserve(additional)?;
+        buffer.clear();
+        buffer.try_extend_from_slice(&plaintext)
+    }
+
+    /// Decrypts every layer, reporting a failure to the hook registered with
+    /// [`on_decrypt_error`](crate::on_decrypt_error). A timeout is not a
+    /// failure of the ciphertext, so it is not reported.
+    fn decrypt_layers(&self) -> Result<Box<[u8]>, ObfuseError> {
+        let result = self.decrypt_each_layer();
+        // A chunk refused at the deadline surfaces as an AEAD error
+        #[cfg(feature = "streaming")]
+        let result = result.map_err(|e| {
+            if crate::stream::take_expired() {
+                ObfuseError::TimedOut
+            } else {
+                e
+            }
+        });
+        result.inspect_err(|e| {
+            if !matches!(e, ObfuseError::TimedOut) {
+                hook::report(e);
+            }
+        })
+    }
+
+    /// Decrypts every layer, outermost first, wiping each intermediate layer.
+    fn decrypt_each_layer(&self) -> Result<Box<[u8]>, ObfuseError> {
+        // Every decryption path ends up here, so a honeytoken cannot be read
+        // without tripping its callback
+        #[cfg(feature = "honeytoken")]
+        if let Some(callback) = self.on_access {
+            if !self.honey_fired.swap(true, Ordering::SeqCst) {
+                callback();
+            }
+        }
+
+        #[cfg(feature = "anti-debug")]
+        if crate::anti_debug::debugger_present() {
+            return Err(ObfuseError::DebuggerDetected);
+        }
+
+        let mut key = Zeroizing::new(self.key);
+        mask::reveal(&mut key, &self.nonce);
+
+        #[cfg(feature = "permute-ciphertext")]
+        let unpermuted = crate::permute::unpermute(&self.encrypted, &self.nonce);
+        #[cfg(feature = "permute-ciphertext")]
+        let stored = unpermuted.as_slice();
+        #[cfg(not(feature = "permute-ciphertext"))]
+        let stored: &[u8] = &self.encrypted;
+
+        #[cfg(feature = "ciphertext-padding")]
+        let stored = crate::padding::strip(stored, &self.nonce)?;
+
+        #[cfg(feature = "runtime-encrypt")]
+        let custom = self.cipher;
+        #[cfg(not(feature = "runtime-encrypt"))]
+        let custom: Option<DecryptFn> = None;
+
+        let mut plaintext = if let Some(decrypt_custom) = custom {
+            decrypt_custom(stored, &key, &self.nonce)?
+        } else {
+            check_size_limit(stored.len())?;
+            decrypt(stored, &key, &self.nonce)?
+        };
+
+        for _ in 1..self.layers {
+            let inner = peel(&plaintext);
+            plaintext.zeroize();
+            plaintext = inner?;
+        }
+
+        Ok(plaintext)
+    }
+
+    /// Compares the plaintext bytes with `other` in constant time.
+    ///
+    /// For binary tokens such as HMAC keys. The plaintext is decrypted into
+    /// a temporary [`SecretBytesGuard`] that is wiped before returning, and
+    /// the comparison does not exit early on the first differing byte.
+    /// Slices of different lengths compare unequal immediately; only the
+    /// length is revealed by timing, not the contents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if decryption fails. For fallible comparison, use [`try_bytes_eq`].
+    ///
+    /// [`try_bytes_eq`]: Self::try_bytes_eq
+    #[must_use]
+    #[track_caller]
+    pub fn bytes_eq(&self, other: &[u8]) -> bool {
+        match self.try_bytes_eq(other) {
+            Ok(value) => value,
+            Err(e) => decryption_failed(&e),
+        }
+    }
+
+    /// Compares the plaintext bytes with `other` in constant time, or returns
+    /// an error if decryption fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails (authentication error or corrupted data).
+    pub fn try_bytes_eq(&self, other: &[u8]) -> Result<bool, ObfuseError> {
+        let plaintext = self.reveal_bytes()?;
+        Ok(plaintext.ct_eq(other).into())
+    }
+
+    /// Checks the plaintext against an allowlist in constant time.
+    ///
+    /// Decrypts once and compares against every needle, with no early exit,
+    /// so the time taken does not reveal which entry (if any) matched. As
+    /// with [`try_bytes_eq`](Self::try_bytes_eq), a needle whose length
+    /// differs from the plaintext is rejected faster than one of equal
+    /// length. The plaintext is not cached. An empty list never matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails.
+    pub fn timing_safe_contains_any(&self, needles: &[&str]) -> Result<bool, ObfuseError> {
+        Ok(self.timing_safe_position(needles)?.is_some())
+    }
+
+    /// Like [`timing_safe_contains_any`](Self::timing_safe_contains_any),
+    /// but returns the index of the matching needle (the last one, if the
+    /// list has duplicates).
+    ///
+    /// The scan itself is constant-time, but the returned index is not
+    /// secret: whatever the caller does with it next (indexing a table,
+    /// branching per role) may leak which entry matched through timing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails.
+    pub fn timing_safe_position(&self, needles: &[&str]) -> Result<Option<usize>, ObfuseError> {
+        let plaintext = self.reveal_bytes()?;
+
+        let mut found = Choice::from(0);
+        let mut position = 0u64;
+        for (i, needle) in (0u64..).zip(needles) {
+            let matched = plaintext.ct_eq(needle.as_bytes());
+            position.conditional_assign(&i, matched);
+            found |= matched;
+        }
+
+        // `position` counts slice indices, so it always fits back into usize
+        Ok(bool::from(found)
+            .then_some(position)
+            .and_then(|position| usize::try_from(position).ok()))
+    }
+
+    /// Returns a checksum of the plaintext, for detecting whether a secret
+    /// changed (e.g. across config reloads) without keeping the plaintext.
+    ///
+    /// The plaintext is decrypted into a temporary [`SecretBytesGuard`] that
+    /// is wiped before returning, and nothing is cached in `self`. Equal
+    /// plaintexts always yield equal checksums within one process.
+    ///
+    /// This is `SipHash` under per-process random keys, the same value
+    /// [`Hash`] feeds to a map's hasher: not a cryptographic digest, so
+    /// collisions are unlikely but possible (about one in 2^64 per pair),
+    /// and not stable across runs, so do not persist it. Hashing runs in
+    /// time that depends on the plaintext length, and the checksum itself
+    /// is only a guessing oracle for whoever can also call this method.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails.
+    pub fn checksum(&self) -> Result<u64, ObfuseError> {
+        let plaintext = self.reveal_bytes()?;
+        Ok(plaintext_hasher().hash_one(&*plaintext))
+    }
+
+    /// Checks the plaintext, as a password, against a stored Argon2 hash.
+    ///
+    /// `hash` is a PHC string such as `$argon2id$v=19$m=19456,t=2,p=1$...`;
+    /// its algorithm, version and cost parameters are honored. The plaintext
+    /// is decrypted into a temporary [`SecretBytesGuard`] that is wiped before
+    /// returning, and the final digest comparison is constant-time. The hash
+    /// is parsed before anything is decrypted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ObfuseError::InvalidHash`] if `hash` is not a valid Argon2
+    /// PHC string, or an error if decryption fails.
+    #[cfg(feature = "password-hash")]
+    pub fn verify_against(&self, hash: &str) -> Result<bool, ObfuseError> {
+        use argon2::password_hash::{self, PasswordHash, PasswordVerifier};
+
+        let hash = PasswordHash::new(hash)?;
+        let password = self.reveal_bytes()?;
+
+        match argon2::Argon2::default().verify_password(&password, &hash) {
+            Ok(()) => Ok(true),
+            Err(password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Splits a fresh copy of the string on the first occurrence of `delim`.
+    ///
+    /// Useful for compound secrets such as `user:pass`. The full plaintext is
+    /// decrypted into a temporary [`Revealed`] that is wiped before returning;
+    /// each half is an independent [`Revealed`] that wipes itself on drop.
+    /// Nothing is cached in `self`.
+    ///
+    /// Named to avoid shadowing [`str::split_once`], which remains available
+    /// through `Deref` (and caches the plaintext).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    /// Returns `Ok(None)` if `delim` does not occur.
+    pub fn split_at_secret(
+        &self,
+        delim: char,
+    ) -> Result<Option<(Revealed, Revealed)>, ObfuseError> {
+        let full = self.try_reveal()?;
+
+        Ok(full.split_once(delim).map(|(head, tail)| {
+            (
+                Revealed::new(head.to_owned()),
+                Revealed::new(tail.to_owned()),
+            )
+        }))
+    }
+
+    /// Passes each whitespace-separated token of the plaintext to `f`.
+    ///
+    /// For secrets holding a space-separated list, such as several API keys
+    /// or scopes. Tokens are split as by [`str::split_whitespace`], so runs
+    /// of whitespace yield no empty tokens. The plaintext is decrypted into a
+    /// temporary [`Revealed`] that is wiped once `f` has seen every token;
+    /// the tokens borrow from it and nothing is cached in `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid
+    /// UTF-8, in which case `f` is never called.
+    pub fn for_each_token(&self, f: impl FnMut(&str)) -> Result<(), ObfuseError> {
+        let revealed = self.try_reveal()?;
+        revealed.split_whitespace().for_each(f);
+        Ok(())
+    }
+
+    /// Returns a masked preview keeping `keep` characters at each end, e.g.
+    /// `s******g` for `keep = 1`.
+    ///
+    /// Enough to tell secrets apart in support logs without leaking them. A
+    /// secret of at most `2 * keep` characters is masked entirely, as is
+    /// every secret when `keep` is 0. Each masked character becomes one `*`,
+    /// so the preview reveals the length in characters. The plaintext is
+    /// decrypted into a temporary [`Revealed`] that is wiped before
+    /// returning; nothing is cached in `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    pub fn redacted_preview(&self, keep: usize) -> Result<String, ObfuseError> {
+        let revealed = self.try_reveal()?;
+        let count = revealed.chars().count();
+
+        let preview = if count <= keep.saturating_mul(2) {
+            "*".repeat(count)
+        } else {
+            revealed
+                .chars()
+                .enumerate()
+                .map(|(i, c)| {
+                    if i < keep || i >= count - keep {
+                        c
+                    } else {
+                        '*'
+                    }
+                })
+                .collect()
+        };
+        Ok(preview)
+    }
+
+    /// Returns a copy of the plaintext with every match of `config`'s
+    /// patterns masked, e.g. the email in a connection string.
+    ///
+    /// Only the matches are masked; the rest of the plaintext is returned
+    /// verbatim, so this suits values that are loggable apart from embedded
+    /// PII, not passwords or keys. The plaintext is decrypted into a
+    /// temporary [`Revealed`] and every intermediate copy is wiped before
+    /// returning; nothing is cached in `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let dsn = obfuse!("postgres://alice@example.com@db/app");
+    /// let line = dsn.reveal_redacted_for_log(&RedactConfig::default())?;
+    /// assert_eq!(line, "postgres://[REDACTED]@db/app");
+    /// ```
+    #[cfg(feature = "pii-redact")]
+    pub fn reveal_redacted_for_log(
+        &self,
+        config: &crate::RedactConfig,
+    ) -> Result<String, ObfuseError> {
+        let revealed = self.try_reveal()?;
+        Ok(config.apply(&revealed))
+    }
+
+    /// Decrypts a template and fills its `{name}` placeholders from `args`.
+    ///
+    /// For log message templates such as `"user {user} logged in"`. `{{` and
+    /// `}}` produce literal braces. A placeholder with no matching argument,
+    /// and any unpaired brace, is kept verbatim. If a name appears more than
+    /// once in `args`, the first entry wins.
+    ///
+    /// The template is decrypted into a temporary [`Revealed`] that is wiped
+    /// before returning; nothing is cached in `self`. The result is sized
+    /// up front, so no partially filled copies are left behind by growth.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    pub fn render(&self, args: &[(&str, &str)]) -> Result<String, ObfuseError> {
+        let template = self.try_reveal()?;
+
+        let mut len = 0;
+        substitute(&template, args, |piece| len += piece.len());
+        let mut rendered = String::with_capacity(len);
+        substitute(&template, args, |piece| rendered.push_str(piece));
+        Ok(rendered)
+    }
+
+    /// Writes the plaintext to `w` if `reveal` is set, `[REDACTED]` otherwise.
+    ///
+    /// Gives each write site explicit control when assembling log lines or
+    /// other larger buffers. A revealed value is decrypted into a temporary
+    /// [`Revealed`] that is wiped after writing; nothing is cached in `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`fmt::Error`] if `w` fails, or if `reveal` is set and
+    /// decryption fails.
+    pub fn write_to<W: fmt::Write>(&self, w: &mut W, reveal: bool) -> fmt::Result {
+        if !reveal {
+            return w.write_str("[REDACTED]");
+        }
+
+        let revealed = self.try_reveal().map_err(|_| fmt::Error)?;
+        w.write_str(&revealed)
+    }
+
+    /// Returns a `Debug` view of the secret formatted with `policy` instead
+    /// of the global one (`redaction-policy` feature).
+    ///
+    /// For containing types that implement `Debug` by hand and want their
+    /// own level of detail. Nothing is decrypted.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use obfuse::Redaction;
+    ///
+    /// let token = obfuse!("token");
+    /// let debug = format!("{:?}", token.debug_with(&Redaction::Nothing));
+    /// assert_eq!(debug, "ObfuseStr { .. }");
+    /// ```
+    #[cfg(feature = "redaction-policy")]
+    pub fn debug_with<'a>(
+        &'a self,
+        policy: &'a dyn crate::RedactionPolicy,
+    ) -> impl fmt::Debug + 'a {
+        crate::redaction::DebugWith {
+            secret: self,
+            policy,
+        }
+    }
+
+    /// Returns how many times the plaintext has been accessed.
+    ///
+    /// Counts every [`try_as_bytes`] call, whether it decrypted or hit the
+    /// cache; the other borrowing accessors (`as_str`, `Deref`, `Display`,
+    /// ...) all go through it. Fresh-copy methods such as [`reveal`] are not
+    /// counted. An unexpectedly high count can indicate something repeatedly
+    /// probing the secret.
+    ///
+    /// [`try_as_bytes`]: Self::try_as_bytes
+    /// [`reveal`]: Self::reveal
+    #[cfg(feature = "access-metrics")]
+    #[must_use]
+    pub fn access_count(&self) -> u64 {
+        self.accesses.load(Ordering::Relaxed)
+    }
+
+    /// Estimates the relative work needed to decrypt the string.
+    ///
+    /// A unitless heuristic for deciding which secrets to warm up first: the
+    /// ciphertext length times [`DECRYPT_COST_PER_BYTE`] for the compiled-in
+    /// algorithm, times the number of layers. Returns 0 once the plaintext is
+    /// cached, since borrowing it needs no further decryption.
+    ///
+    /// [`DECRYPT_COST_PER_BYTE`]: crate::DECRYPT_COST_PER_BYTE
+    #[must_use]
+    pub fn needs_decryption_cost(&self) -> usize {
+        if self.is_decrypted() {
+            return 0;
+        }
+
+        self.encrypted
+            .len()
+            .saturating_mul(crate::DECRYPT_COST_PER_BYTE)
+            .saturating_mul(usize::from(self.layers))
+    }
+
+    /// Returns `true` if the string has already been decrypted.
+    ///
+    /// This can be used to check if accessing the string will trigger decryption.
+    #[inline]
+    #[must_use]
+    pub fn is_decrypted(&self) -> bool {
+        self.decrypted.get().is_some()
+    }
+
+    /// Pre-decrypts the string, returning it for chaining.
+    ///
+    /// The panicking counterpart of [`try_decrypt`], for paying the
+    /// decryption cost at startup: `let key = SECRET.preload();`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if decryption fails.
+    ///
+    /// [`try_decrypt`]: Self::try_decrypt
+    #[track_caller]
+    pub fn preload(&self) -> &Self {
+        if let Err(e) = self.try_decrypt() {
+            decryption_failed(&e);
+        }
+        self
+    }
+
+    /// Pre-decrypts the string without returning the value.
+    ///
+    /// Useful for warming up the cache before time-critical operations.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails.
+    pub fn try_decrypt(&self) -> Result<(), ObfuseError> {
+        self.try_as_bytes().map(|_| ())
+    }
+
+    /// Pre-decrypts the string on Tokio's blocking pool.
+    ///
+    /// The async counterpart of [`try_decrypt`], for warming secrets at
+    /// startup without stalling the reactor. The returned future is `Send`
+    /// and `'static`, so it can itself be spawned. Takes `&'static self`
+    /// because the blocking task may outlive the caller; use it with
+    /// `obfuse_static!` or `obfuse_const!` strings.
+    ///
+    /// If the runtime is shutting down and the task is cancelled, the string
+    /// is decrypted on the calling thread instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime, or resumes the panic of the
+    /// decryption task if it panicked.
+    ///
+    /// [`try_decrypt`]: Self::try_decrypt
+    #[cfg(feature = "tokio")]
+    pub fn warm_async(
+        &'static self,
+    ) -> impl Future<Output = Result<(), ObfuseError>> + Send + 'static {
+        let task = tokio::task::spawn_blocking(|| self.try_decrypt());
+
+        async move {
+            match task.await {
+                Ok(result) => result,
+                Err(e) => match e.try_into_panic() {
+                    Ok(panic) => std::panic::resume_unwind(panic),
+                    Err(_) => self.try_decrypt(),
+                },
+            }
+        }
+    }
+
+    /// Wipes all sensitive memory and drops the string.
+    ///
+    /// Equivalent to `drop(secret)`, which wipes via [`zeroize`], but makes
+    /// the end of the secret's lifetime explicit at the call site. Prefer it
+    /// over calling [`zeroize`] directly when the value is no longer needed.
+    ///
+    /// [`zeroize`]: Self::zeroize
+    pub fn drop_now(self) {
+        drop(self);
+    }
+
+    /// Decrypts the string and leaks the plaintext as a `&'static str`.
+    ///
+    /// **This permanently exposes the plaintext and leaks its memory**: the
+    /// returned buffer is never wiped or freed, so the secret sits in the
+    /// heap in clear for the rest of the process. Meant for rare
+    /// startup-time use, e.g. handing a value to an API that demands
+    /// `'static`, never in a loop.
+    ///
+    /// Only the plaintext is given up. A cached plaintext is moved out
+    /// rather than copied, and the key and owned ciphertext are still wiped
+    /// when `self` is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid
+    /// UTF-8; nothing is leaked then.
+    pub fn leak(mut self) -> Result<&'static str, ObfuseError> {
+        let plaintext = match self.decrypted.take() {
+            Some(cached) => cached,
+            None => self.decrypt_layers()?,
+        };
+
+        match String::from_utf8(plaintext.into_vec()) {
+            Ok(plaintext) => Ok(Box::leak(plaintext.into_boxed_str())),
+            Err(e) => {
+                let error = e.utf8_error();
+                e.into_bytes().zeroize();
+                Err(error.into())
+            }
+        }
+    }
+
+    /// Manually zeros all sensitive memory.
+    ///
+    /// This is also called automatically on drop, but can be used to
+    /// clear memory earlier if needed.
+    ///
+    /// # Note
+    ///
+    /// The instance stays alive but unusable: the key and nonce are zeroed,
+    /// and a cached plaintext is overwritten in place rather than removed, so
+    /// later accesses return zero bytes instead of re-decrypting. To wipe a
+    /// string you are done with, use [`drop_now`].
+    ///
+    /// Borrowed (`&'static`) ciphertext embedded by the macros is never
+    /// written to; only owned ciphertext is wiped.
+    ///
+    /// [`drop_now`]: Self::drop_now
+    pub fn zeroize(&mut self) {
+        self.key.zeroize();
+        self.nonce.zeroize();
+
+        if let Cow::Owned(ciphertext) = &mut self.encrypted {
+            ciphertext.zeroize();
+        }
+
+        // Zero the decrypted plaintext if it exists
+        if let Some(decrypted) = self.decrypted.get_mut() {
+            decrypted.zeroize();
+        }
+
+        self.plaintext_hash.take();
+
+        if let Some(mut shared) = self.shared.take() {
+            wipe_shared(&mut shared);
+        }
+    }
+
+    /// Whether the ciphertext was embedded by the macros rather than owned.
+    #[cfg(feature = "runtime-encrypt")]
+    pub(crate) fn is_embedded(&self) -> bool {
+        matches!(self.encrypted, Cow::Borrowed(_))
+    }
+
+    /// Takes over `replacement`'s ciphertext, key and nonce, wiping the old
+    /// ones and dropping the cached plaintext. Access counts and honeytoken
+    /// state carry over.
+    #[cfg(feature = "runtime-encrypt")]
+    pub(crate) fn replace_with(&mut self, mut replacement: Self) {
+        self.zeroize();
+        self.decrypted = OnceLock::new();
+        std::mem::swap(&mut self.encrypted, &mut replacement.encrypted);
+        std::mem::swap(&mut self.key, &mut replacement.key);
+        std::mem::swap(&mut self.nonce, &mut replacement.nonce);
+        self.layers = replacement.layers;
+        self.cipher = replacement.cipher;
+    }
+
+    /// Decrypts with `decrypt` instead of the built-in algorithm.
+    #[cfg(feature = "runtime-encrypt")]
+    pub(crate) fn with_cipher(mut self, decrypt: DecryptFn) -> Self {
+        self.cipher = Some(decrypt);
+        self
+    }
+
+    /// Name of the algorithm this string decrypts with: [`ALGORITHM`], or
+    /// [`CUSTOM_ALGORITHM`](crate::CUSTOM_ALGORITHM) for strings from
+    /// `encrypt_with`.
+    #[cfg_attr(not(feature = "runtime-encrypt"), allow(clippy::unused_self))]
+    pub(crate) fn algorithm(&self) -> &'static str {
+        #[cfg(feature = "runtime-encrypt")]
+        if self.cipher.is_some() {
+            return crate::CUSTOM_ALGORITHM;
+        }
+        ALGORITHM
+    }
+}
+
+/// Wipes a shared plaintext copy unless other clones of it are still alive.
+fn wipe_shared(shared: &mut Arc<str>) {
+    if let Some(plaintext) = Arc::get_mut(shared) {
+        plaintext.zeroize();
+    }
+}
+
+/// Panics with the message shared by the panicking accessors.
+///
+/// A direct call rather than `unwrap_or_else(|e| panic!(..))`: closures do
+/// not forward `#[track_caller]`, so the panic would point into this crate
+/// instead of at the caller.
+#[cold]
+#[track_caller]
+pub(crate) fn decryption_failed(error: &ObfuseError) -> ! {
+    panic!("ObfuseStr decryption failed: {error}")
+}
+
+/// Passes the pieces of `template` with placeholders filled to `emit`, in order.
+fn substitute(template: &str, args: &[(&str, &str)], mut emit: impl FnMut(&str)) {
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        emit(&rest[..pos]);
+        let tail = &rest[pos..];
+
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            emit(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+
+        let value = tail
+            .strip_prefix('{')
+            .and_then(|inner| inner.split_once('}'))
+            .and_then(|(name, after)| {
+                args.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| (*value, after))
+            });
+        if let Some((value, after)) = value {
+            emit(value);
+            rest = after;
+        } else {
+            emit(&tail[..1]);
+            rest = &tail[1..];
+        }
+    }
+
+    emit(rest);
+}
+
+/// Decrypts the next layer from `key || nonce || ciphertext`.
+fn peel(layer: &[u8]) -> Result<Box<[u8]>, ObfuseError> {
+    let (key, rest) = layer
+        .split_first_chunk::<KEY_SIZE>()
+        .ok_or(ObfuseError::TruncatedLayer)?;
+    let (nonce, ciphertext) = rest
+        .split_first_chunk::<NONCE_SIZE>()
+        .ok_or(ObfuseError::TruncatedLayer)?;
+
+    check_size_limit(ciphertext.len())?;
+    decrypt(ciphertext, key, nonce)
+}
+
+/// Largest ciphertext the selected algorithm can have produced.
+#[cfg(not(feature = "streaming"))]
+const CIPHERTEXT_LIMIT: u64 = MAX_CIPHERTEXT_LEN;
+
+/// Largest ciphertext the selected algorithm can have produced, counting one
+/// tag per streamed chunk.
+#[cfg(feature = "streaming")]
+const CIPHERTEXT_LIMIT: u64 = crate::stream::max_ciphertext_len(MAX_CIPHERTEXT_LEN);
+
+/// Rejects ciphertext longer than the algorithm can have produced.
+///
+/// Owned ciphertext comes from outside the macros, so its length is not
+/// trusted to respect the AEAD limits.
+// Always passes with XOR, which has no limit
+#[allow(clippy::absurd_extreme_comparisons)]
+fn check_size_limit(len: usize) -> Result<(), ObfuseError> {
+    match u64::try_from(len) {
+        Ok(len) if len <= CIPHERTEXT_LIMIT => Ok(()),
+        _ => Err(ObfuseError::SizeLimitExceeded),
+    }
+}
+
+impl Deref for ObfuseStr {
+    type Target = str;
+
+    #[inline]
+    #[track_caller]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for ObfuseStr {
+    #[inline]
+    #[track_caller]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for ObfuseStr {
+    #[inline]
+    #[track_caller]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<OsStr> for ObfuseStr {
+    #[inline]
+    #[track_caller]
+    fn as_ref(&self) -> &OsStr {
+        self.as_os_str()
+    }
+}
+
+impl AsRef<Path> for ObfuseStr {
+    #[inline]
+    #[track_caller]
+    fn as_ref(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+/// Per-process random `SipHash` keys shared by [`Hash`] and
+/// [`ObfuseStr::checksum`].
+fn plaintext_hasher() -> &'static RandomState {
+    static KEYS: OnceLock<RandomState> = OnceLock::new();
+    KEYS.get_or_init(RandomState::new)
+}
+
+/// Hashes the plaintext, decrypting on first use.
+///
+/// The plaintext is hashed once with `SipHash` under per-process random keys,
+/// and the resulting `u64` is cached, so later calls (e.g. `HashMap`
+/// lookups) only feed that value to the map's hasher. The keys keep the
+/// cached value from serving as an offline guessing oracle, but hashing
+/// still touches the plaintext in variable time on the first call, so do not
+/// key maps by secrets an attacker can probe by timing.
+///
+/// # Panics
+///
+/// Panics if decryption fails.
+impl Hash for ObfuseStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let hash = *self
+            .plaintext_hash
+            .get_or_init(|| plaintext_hasher().hash_one(self.as_bytes()));
+        state.write_u64(hash);
+    }
+}
+
+/// Compares plaintexts in constant time, decrypting both on first use.
+///
+/// Consistent with [`Hash`]. Plaintexts of different lengths compare unequal
+/// immediately.
+///
+/// # Panics
+///
+/// Panics if decryption fails.
+impl PartialEq for ObfuseStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes().ct_eq(other.as_bytes()).into()
+    }
+}
+
+impl Eq for ObfuseStr {}
+
+#[cfg(not(feature = "redaction-policy"))]
+impl fmt::Debug for ObfuseStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObfuseStr")
+            .field("value", &"[REDACTED]")
+            .field("decrypted", &self.is_decrypted())
+            .finish()
+    }
+}
+
+/// Formats with the policy set by
+/// [`set_redaction_policy`](crate::set_redaction_policy)
+/// (`redaction-policy` feature).
+#[cfg(feature = "redaction-policy")]
+impl fmt::Debug for ObfuseStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        crate::redaction::current().fmt_debug(self, f)
+    }
+}
+
+/// Logs the algorithm and cache state, never the plaintext.
+#[cfg(feature = "defmt")]
+impl defmt::Format for ObfuseStr {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "ObfuseStr {{ algorithm: {=str}, value: [REDACTED], decrypted: {=bool} }}",
+            crate::ALGORITHM,
+            self.is_decrypted()
+        );
+    }
+}
+
+/// Writes the plaintext, decrypting on first use.
+///
+/// # Panics
+///
+/// Panics if decryption fails.
+#[cfg(not(feature = "transport-safe"))]
+impl fmt::Display for ObfuseStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Writes `[REDACTED]` without decrypting (`transport-safe` feature).
+///
+/// Formatting, `to_string()` and anything built on them never see the
+/// plaintext; use [`reveal`](ObfuseStr::reveal) or
+/// [`as_str`](ObfuseStr::as_str) explicitly.
+#[cfg(feature = "transport-safe")]
+impl fmt::Display for ObfuseStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+/// Serializes as the string `"[REDACTED]"` without decrypting
+/// (`serialize-redacted` feature, implied by `transport-safe`).
+///
+/// Lets structs holding an `ObfuseStr` derive `Serialize` for IPC, logging
+/// or telemetry without a path that leaks the plaintext. Fields that must
+/// round-trip opt in with
+/// [`#[serde(with = "obfuse::serde_ciphertext")]`](crate::serde_ciphertext);
+/// serialize an explicit [`reveal`](ObfuseStr::reveal) where the plaintext
+/// itself must be sent.
+#[cfg(feature = "serialize-redacted")]
+impl serde::Serialize for ObfuseStr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str("[REDACTED]")
+    }
+}
+
+impl Drop for ObfuseStr {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+/// Lets `ObfuseStr` sit inside `#[derive(Zeroize)]` containers.
+impl Zeroize for ObfuseStr {
+    fn zeroize(&mut self) {
+        Self::zeroize(self);
+    }
+}
+
+/// `ObfuseStr` wipes itself on drop, so `#[derive(ZeroizeOnDrop)]`
+/// containers need no `#[zeroize(skip)]` on it.
+impl ZeroizeOnDrop for ObfuseStr {}
+
+// Note: ObfuseStr is Send + Sync because:
+// - Cow<'static, [u8]> is Send + Sync
+// - [u8; N] arrays are Send + Sync
+// - OnceLock<Box<[u8]>> and AtomicU64 are Send + Sync
+// The derive is automatic since all fields are Send + Sync; tests/thread_safety.rs
+// fails to compile if a new field breaks this.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_limit_boundary() {
+        // The limits exceed `usize` on 32-bit targets, where every slice fits
+        let Ok(max) = usize::try_from(CIPHERTEXT_LIMIT) else {
+            return;
+        };
+        assert!(check_size_limit(0).is_ok());
+        assert!(check_size_limit(max).is_ok());
+        if let Some(over) = max.checked_add(1) {
+            assert!(matches!(
+                check_size_limit(over),
+                Err(ObfuseError::SizeLimitExceeded)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_stored_key_is_masked() {
+        let key = [0x42; KEY_SIZE];
+        let secret = ObfuseStr::new_from_owned(Vec::new(), key, [9; NONCE_SIZE]);
+        assert_ne!(secret.key, key);
+    }
+
+    #[test]
+    fn test_peel_rejects_truncated_layer() {
+        let short = [0u8; KEY_SIZE + NONCE_SIZE - 1];
+        assert!(matches!(peel(&short), Err(ObfuseError::TruncatedLayer)));
+    }
 
-#[cfg(test)]
-mod tests {
     #[test]
     fn test_debug_redacts_value() {
         // This test requires the macro, so we just test the debug format structure
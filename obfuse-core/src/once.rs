@@ -0,0 +1,51 @@
+//! The `ObfuseOnceStr` type - a one-time secret consumed by its only read.
+
+use std::fmt;
+
+use crate::{ObfuseError, ObfuseStr};
+
+/// An obfuscated string that can be read exactly once, created by `obfuse_once!`.
+///
+/// For one-time secrets such as setup or enrollment tokens. There is no way
+/// to borrow the plaintext: [`take`] consumes the value, so the compiler
+/// rejects any second read, and the ciphertext and key are wiped as it
+/// returns. The type is deliberately neither `Clone` nor `Copy`.
+///
+/// [`take`]: Self::take
+#[must_use = "a one-time secret does nothing until it is taken"]
+pub struct ObfuseOnceStr {
+    inner: ObfuseStr,
+}
+
+impl ObfuseOnceStr {
+    /// Wraps an `ObfuseStr` for one-time use.
+    ///
+    /// This is called by the `obfuse_once!` macro and should not be used
+    /// directly.
+    #[doc(hidden)]
+    pub const fn new(inner: ObfuseStr) -> Self {
+        Self { inner }
+    }
+
+    /// Decrypts the secret and consumes it, returning the owned plaintext.
+    ///
+    /// The plaintext is decrypted straight into the returned `String`
+    /// without being cached, and the encrypted state is wiped on return
+    /// whether or not decryption succeeds. Wiping the returned `String` is
+    /// up to the caller, e.g. with `zeroize::Zeroizing::new(once.take()?)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails or the plaintext is not valid UTF-8.
+    pub fn take(self) -> Result<String, ObfuseError> {
+        self.inner.decrypt_string()
+    }
+}
+
+impl fmt::Debug for ObfuseOnceStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObfuseOnceStr")
+            .field("value", &"[REDACTED]")
+            .finish()
+    }
+}
@@ -0,0 +1,99 @@
+//! Junk-byte padding for the `ciphertext-padding` feature.
+//!
+//! The stored ciphertext is wrapped in a junk prefix and suffix of up to
+//! [`MAX_PADDING`] - 1 bytes each, so strings of equal length do not embed
+//! blobs of equal length and a scanner cannot anchor on where one starts.
+//! Both lengths and the junk come from the string's nonce (through
+//! [`nonce_rng`](crate::mask::nonce_rng)), so no offsets are stored. With an
+//! AEAD the nonce is authenticated, so tampering with it fails decryption
+//! rather than shifting the padding; with `xor` or `polymorphic` nothing is
+//! authenticated, and a tampered nonce silently strips the wrong bytes. The
+//! macro crate carries an identical copy of [`layout`]; the two must stay in
+//! sync.
+
+use crate::mask::nonce_rng;
+use crate::{NONCE_SIZE, ObfuseError};
+
+/// Exclusive upper bound on the junk bytes added on each side.
+pub const MAX_PADDING: usize = 32;
+
+/// Domain separator keeping the seed independent of the key mask and the
+/// permutation.
+const DOMAIN: u64 = 0x7061_6464_696e_6721;
+
+/// Returns the prefix length, suffix length and a generator for the junk
+/// bytes selected by `nonce`.
+fn layout(nonce: &[u8; NONCE_SIZE]) -> (usize, usize, impl FnMut() -> u8) {
+    let mut next = nonce_rng(nonce, DOMAIN);
+
+    // Only the low bits of the random values matter
+    #[allow(clippy::cast_possible_truncation)]
+    let (prefix, suffix) = (next() as usize % MAX_PADDING, next() as usize % MAX_PADDING);
+    #[allow(clippy::cast_possible_truncation)]
+    let junk = move || next() as u8;
+    (prefix, suffix, junk)
+}
+
+/// Wraps ciphertext in the junk prefix and suffix selected by `nonce`.
+///
+/// Ciphertext passed to [`ObfuseStr::new_from_owned`](crate::ObfuseStr::new_from_owned)
+/// in a `ciphertext-padding` build must be padded with this first (and then
+/// shuffled with `permute_ciphertext`, if that feature is also enabled).
+#[must_use]
+pub fn pad_ciphertext(ciphertext: &[u8], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    let (prefix, suffix, mut junk) = layout(nonce);
+
+    let mut stored = Vec::with_capacity(prefix + ciphertext.len() + suffix);
+    stored.extend((0..prefix).map(|_| junk()));
+    stored.extend_from_slice(ciphertext);
+    stored.extend((0..suffix).map(|_| junk()));
+    stored
+}
+
+/// Returns the ciphertext inside padded stored bytes.
+pub fn strip<'a>(stored: &'a [u8], nonce: &[u8; NONCE_SIZE]) -> Result<&'a [u8], ObfuseError> {
+    let (prefix, suffix, _) = layout(nonce);
+
+    let len = stored
+        .len()
+        .checked_sub(prefix + suffix)
+        .ok_or(ObfuseError::TruncatedLayer)?;
+    Ok(&stored[prefix..prefix + len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_inverts_pad() {
+        let ciphertext = b"ciphertext bytes";
+        for seed in 0..32u8 {
+            let nonce = [seed; NONCE_SIZE];
+            let stored = pad_ciphertext(ciphertext, &nonce);
+            assert_eq!(strip(&stored, &nonce).unwrap(), ciphertext);
+        }
+    }
+
+    #[test]
+    fn test_lengths_vary_with_nonce() {
+        let lengths: std::collections::HashSet<usize> = (0..32u8)
+            .map(|seed| pad_ciphertext(&[], &[seed; NONCE_SIZE]).len())
+            .collect();
+        assert!(lengths.len() > 1);
+        assert!(lengths.iter().all(|&len| len <= 2 * (MAX_PADDING - 1)));
+    }
+
+    #[test]
+    fn test_strip_rejects_short_input() {
+        let nonce = (0..=u8::MAX)
+            .map(|seed| [seed; NONCE_SIZE])
+            .find(|nonce| layout(nonce).0 > 0)
+            .unwrap();
+        let stored = pad_ciphertext(&[], &nonce);
+        assert!(matches!(
+            strip(&stored[1..], &nonce),
+            Err(ObfuseError::TruncatedLayer)
+        ));
+    }
+}
@@ -0,0 +1,73 @@
+//! Ciphertext permutation for the `permute-ciphertext` feature.
+//!
+//! The embedded ciphertext is stored shuffled, so carving a contiguous run of
+//! bytes out of the binary does not yield the AEAD blob. The permutation is a
+//! Fisher-Yates shuffle driven by [`nonce_rng`](crate::mask::nonce_rng), so no
+//! table (and no extra seed bytes) is stored. The macro crate carries an
+//! identical copy of [`permutation`]; the two must stay in sync.
+
+use crate::NONCE_SIZE;
+use crate::mask::nonce_rng;
+
+/// Domain separator keeping the seed independent of the key mask.
+const DOMAIN: u64 = 0x7065_726d_7574_6521;
+
+/// Returns the permutation of `0..len` selected by `nonce`.
+///
+/// Stored byte `i` holds canonical byte `permutation[i]`.
+pub fn permutation(len: usize, nonce: &[u8; NONCE_SIZE]) -> Vec<usize> {
+    let mut next = nonce_rng(nonce, DOMAIN);
+
+    let mut indices: Vec<usize> = (0..len).collect();
+    for i in (1..len).rev() {
+        // Only the low bits of the random value matter
+        #[allow(clippy::cast_possible_truncation)]
+        let j = (next() as usize) % (i + 1);
+        indices.swap(i, j);
+    }
+    indices
+}
+
+/// Shuffles canonical ciphertext into its stored order.
+///
+/// Ciphertext passed to [`ObfuseStr::new_from_owned`](crate::ObfuseStr::new_from_owned)
+/// in a `permute-ciphertext` build must be shuffled with this first.
+#[must_use]
+pub fn permute_ciphertext(ciphertext: &[u8], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    permutation(ciphertext.len(), nonce)
+        .into_iter()
+        .map(|source| ciphertext[source])
+        .collect()
+}
+
+/// Restores the canonical order of stored ciphertext.
+pub fn unpermute(stored: &[u8], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    let mut canonical = vec![0u8; stored.len()];
+    for (&byte, target) in stored.iter().zip(permutation(stored.len(), nonce)) {
+        canonical[target] = byte;
+    }
+    canonical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permutation_is_bijection() {
+        for len in [0, 1, 2, 16, 17, 255, 1024] {
+            let mut indices = permutation(len, &[3; NONCE_SIZE]);
+            indices.sort_unstable();
+            assert_eq!(indices, (0..len).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_unpermute_inverts_permute() {
+        let canonical: Vec<u8> = (0..=255).collect();
+        let stored = permute_ciphertext(&canonical, &[5; NONCE_SIZE]);
+
+        assert_ne!(stored, canonical);
+        assert_eq!(unpermute(&stored, &[5; NONCE_SIZE]), canonical);
+    }
+}
@@ -0,0 +1,32 @@
+//! Helpers for the `polymorphic` byte program, shared by the XOR decryptor
+//! and the decoders that `obfuse_inline!` unrolls into each call site.
+
+/// Returns the multiplicative inverse of an odd byte modulo 256.
+///
+/// A `const fn`, so `obfuse_inline!` expansions resolve their multipliers
+/// at compile time with this same function.
+#[doc(hidden)]
+#[must_use]
+pub const fn mul_inverse(odd: u8) -> u8 {
+    // Newton's iteration doubles the correct low bits each step; every odd
+    // number is its own inverse modulo 8, so two steps reach 8 bits
+    let mut inverse = odd;
+    let mut step = 0;
+    while step < 2 {
+        inverse = inverse.wrapping_mul(2u8.wrapping_sub(odd.wrapping_mul(inverse)));
+        step += 1;
+    }
+    inverse
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_inverse() {
+        for odd in (1..=255u8).step_by(2) {
+            assert_eq!(odd.wrapping_mul(mul_inverse(odd)), 1, "odd = {odd}");
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! PII masking for `ObfuseStr::reveal_redacted_for_log`.
+
+use std::borrow::Cow;
+
+use regex::{Captures, Regex, Replacer};
+use zeroize::Zeroizing;
+
+/// Matches most email addresses, including the `user@host` part of URLs.
+const EMAIL: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}";
+
+/// Matches 13-19 digit card numbers, optionally grouped by spaces or dashes.
+///
+/// Timestamps and IDs of the same length match too, so matches are only
+/// masked if they also pass [`luhn`].
+const CARD_NUMBER: &str = r"\b(?:\d[ -]?){12,18}\d\b";
+
+/// Patterns masked by [`ObfuseStr::reveal_redacted_for_log`].
+///
+/// [`Default`] masks email addresses and payment card numbers (digit runs
+/// passing the Luhn check) with `[REDACTED]`; [`empty`](Self::empty) starts with no patterns. Patterns
+/// are applied in the order they were added, each to the output of the
+/// previous one.
+///
+/// [`ObfuseStr::reveal_redacted_for_log`]: crate::ObfuseStr::reveal_redacted_for_log
+#[derive(Debug, Clone)]
+pub struct RedactConfig {
+    patterns: Vec<Pattern>,
+    replacement: String,
+}
+
+/// A regex plus a check every match must pass to be masked.
+#[derive(Debug, Clone)]
+struct Pattern {
+    regex: Regex,
+    check: fn(&str) -> bool,
+}
+
+impl RedactConfig {
+    /// Creates a config with no patterns, which masks nothing.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            patterns: Vec::new(),
+            replacement: "[REDACTED]".to_owned(),
+        }
+    }
+
+    /// Adds a regex whose every match is masked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pattern` is not a valid regex.
+    pub fn with_pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.patterns.push(Pattern {
+            regex: Regex::new(pattern)?,
+            check: |_| true,
+        });
+        Ok(self)
+    }
+
+    /// Sets the text each match is replaced with (default `[REDACTED]`).
+    /// `$` has no special meaning in it.
+    #[must_use]
+    pub fn with_replacement(mut self, replacement: impl Into<String>) -> Self {
+        self.replacement = replacement.into();
+        self
+    }
+
+    /// Masks every match in `text`, wiping each intermediate copy.
+    pub(crate) fn apply(&self, text: &str) -> String {
+        let mut masked = Zeroizing::new(text.to_owned());
+        for pattern in &self.patterns {
+            let mask = Mask {
+                check: pattern.check,
+                replacement: &self.replacement,
+            };
+            let replaced = match pattern.regex.replace_all(&masked, mask) {
+                Cow::Owned(replaced) => replaced,
+                Cow::Borrowed(_) => continue,
+            };
+            masked = Zeroizing::new(replaced);
+        }
+        std::mem::take(&mut *masked)
+    }
+}
+
+impl Default for RedactConfig {
+    fn default() -> Self {
+        let mut config = Self::empty()
+            .with_pattern(EMAIL)
+            .expect("built-in patterns are valid");
+        config.patterns.push(Pattern {
+            regex: Regex::new(CARD_NUMBER).expect("built-in patterns are valid"),
+            check: luhn,
+        });
+        config
+    }
+}
+
+/// Replaces matches passing `check` with `replacement`, literally, and keeps
+/// the others without copying them into a temporary.
+struct Mask<'a> {
+    check: fn(&str) -> bool,
+    replacement: &'a str,
+}
+
+impl Replacer for Mask<'_> {
+    fn replace_append(&mut self, caps: &Captures<'_>, dst: &mut String) {
+        let matched = &caps[0];
+        dst.push_str(if (self.check)(matched) {
+            self.replacement
+        } else {
+            matched
+        });
+    }
+}
+
+/// Returns whether the digits in `text` pass the Luhn checksum, which every
+/// payment card number does.
+fn luhn(text: &str) -> bool {
+    let sum: u32 = text
+        .bytes()
+        .rev()
+        .filter(u8::is_ascii_digit)
+        .map(|b| u32::from(b - b'0'))
+        .enumerate()
+        .map(|(i, d)| match (i % 2, d * 2) {
+            (0, _) => d,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_luhn() {
+        assert!(luhn("4111 1111 1111 1111"));
+        assert!(luhn("5500-0000-0000-0004"));
+        assert!(!luhn("4111 1111 1111 1112"));
+        assert!(!luhn("1700000000123"));
+    }
+}
@@ -0,0 +1,95 @@
+//! Configurable `Debug` output for `ObfuseStr` (`redaction-policy` feature).
+
+use std::fmt;
+use std::sync::{PoisonError, RwLock};
+
+use crate::ObfuseStr;
+
+/// Decides how an [`ObfuseStr`] appears in `Debug` output.
+///
+/// Implementations must not decrypt: `Debug` output ends up in logs and
+/// panic messages. The built-in policies are the variants of [`Redaction`].
+pub trait RedactionPolicy: Sync {
+    /// Writes the `Debug` representation of `secret`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `f` fails.
+    fn fmt_debug(&self, secret: &ObfuseStr, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+/// The built-in redaction policies. [`Full`](Self::Full) is the default;
+/// [`Length`](Self::Length) and [`Algorithm`](Self::Algorithm) each show one
+/// more detail, and [`Nothing`](Self::Nothing) shows less. None of them
+/// decrypts or shows plaintext.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Redaction {
+    /// `ObfuseStr { value: "[REDACTED]", decrypted: false }`, the default.
+    #[default]
+    Full,
+
+    /// Adds the stored ciphertext length, which bounds the plaintext length.
+    Length,
+
+    /// Adds the name of the algorithm protecting the value.
+    Algorithm,
+
+    /// `ObfuseStr { .. }`, hiding even whether the value was decrypted.
+    Nothing,
+}
+
+impl RedactionPolicy for Redaction {
+    fn fmt_debug(&self, secret: &ObfuseStr, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("ObfuseStr");
+        match self {
+            Self::Full => {}
+            Self::Length => {
+                debug.field("ciphertext_len", &secret.ciphertext().len());
+            }
+            Self::Algorithm => {
+                debug.field("algorithm", &secret.algorithm());
+            }
+            Self::Nothing => return debug.finish_non_exhaustive(),
+        }
+        debug
+            .field("value", &"[REDACTED]")
+            .field("decrypted", &secret.is_decrypted())
+            .finish()
+    }
+}
+
+static POLICY: RwLock<&'static dyn RedactionPolicy> = RwLock::new(&Redaction::Full);
+
+/// Sets the policy `ObfuseStr`'s `Debug` impl uses process-wide, replacing
+/// the previous one.
+///
+/// Defaults to [`Redaction::Full`]. A policy passed to
+/// [`ObfuseStr::debug_with`] takes precedence at that site, so a containing
+/// type can show more or less detail than the global setting.
+///
+/// # Examples
+///
+/// ```ignore
+/// obfuse::set_redaction_policy(&obfuse::Redaction::Algorithm);
+/// ```
+pub fn set_redaction_policy(policy: &'static dyn RedactionPolicy) {
+    *POLICY.write().unwrap_or_else(PoisonError::into_inner) = policy;
+}
+
+/// Returns the policy set by [`set_redaction_policy`].
+pub(crate) fn current() -> &'static dyn RedactionPolicy {
+    *POLICY.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// `Debug` adapter returned by [`ObfuseStr::debug_with`].
+pub(crate) struct DebugWith<'a> {
+    pub(crate) secret: &'a ObfuseStr,
+    pub(crate) policy: &'a dyn RedactionPolicy,
+}
+
+impl fmt::Debug for DebugWith<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.policy.fmt_debug(self.secret, f)
+    }
+}
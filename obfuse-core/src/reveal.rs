@@ -0,0 +1,230 @@
+//! The `Revealed`, `SecretBytesGuard` and `AlignedBytesGuard` guards -
+//! short-lived decrypted copies wiped on drop.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use zeroize::Zeroize;
+
+use crate::ObfuseError;
+
+/// A freshly decrypted copy of an `ObfuseStr`, returned by [`ObfuseStr::reveal`].
+///
+/// Unlike [`ObfuseStr::as_str`], the plaintext is not cached inside the
+/// `ObfuseStr`; it lives only as long as this guard and is zeroed on drop.
+/// The guard is `#[must_use]`, so revealing a secret without using it
+/// triggers a compiler warning.
+///
+/// [`ObfuseStr::reveal`]: crate::ObfuseStr::reveal
+/// [`ObfuseStr::as_str`]: crate::ObfuseStr::as_str
+#[must_use = "revealed plaintext is wiped immediately if unused"]
+pub struct Revealed {
+    plaintext: String,
+}
+
+impl Revealed {
+    pub(crate) fn new(plaintext: String) -> Self {
+        Self { plaintext }
+    }
+
+    /// Returns the revealed string.
+    #[inline]
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.plaintext
+    }
+
+    /// Returns the revealed bytes.
+    #[inline]
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.plaintext.as_bytes()
+    }
+}
+
+impl Deref for Revealed {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Revealed {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for Revealed {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl fmt::Debug for Revealed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Revealed")
+            .field("value", &"[REDACTED]")
+            .finish()
+    }
+}
+
+impl Drop for Revealed {
+    fn drop(&mut self) {
+        self.plaintext.zeroize();
+    }
+}
+
+/// A freshly decrypted byte copy of an `ObfuseStr`, returned by
+/// [`ObfuseStr::reveal_bytes`].
+///
+/// The bytes-oriented counterpart of [`Revealed`] for binary secrets: no UTF-8
+/// validation is performed, nothing is cached in the `ObfuseStr`, and the copy
+/// is zeroed on drop. The guard borrows the `ObfuseStr` it came from.
+///
+/// [`ObfuseStr::reveal_bytes`]: crate::ObfuseStr::reveal_bytes
+#[must_use = "revealed plaintext is wiped immediately if unused"]
+pub struct SecretBytesGuard<'a> {
+    plaintext: Box<[u8]>,
+    _source: PhantomData<&'a crate::ObfuseStr>,
+}
+
+impl SecretBytesGuard<'_> {
+    pub(crate) fn new(plaintext: Box<[u8]>) -> Self {
+        Self {
+            plaintext,
+            _source: PhantomData,
+        }
+    }
+
+    /// Returns the revealed bytes.
+    #[inline]
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.plaintext
+    }
+}
+
+impl Deref for SecretBytesGuard<'_> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<[u8]> for SecretBytesGuard<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl fmt::Debug for SecretBytesGuard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretBytesGuard")
+            .field("value", &"[REDACTED]")
+            .finish()
+    }
+}
+
+impl Drop for SecretBytesGuard<'_> {
+    fn drop(&mut self) {
+        self.plaintext.zeroize();
+    }
+}
+
+/// A freshly decrypted byte copy whose first byte is aligned to `A` bytes,
+/// returned by [`ObfuseStr::decrypt_aligned`].
+///
+/// For SIMD routines that need aligned input. The buffer is over-allocated
+/// by `A - 1` bytes and the plaintext placed at the first aligned offset, so
+/// no `unsafe` allocator is involved; the whole allocation, padding
+/// included, is zeroed on drop. Like [`SecretBytesGuard`], nothing is cached
+/// in the `ObfuseStr`.
+///
+/// [`ObfuseStr::decrypt_aligned`]: crate::ObfuseStr::decrypt_aligned
+#[must_use = "revealed plaintext is wiped immediately if unused"]
+pub struct AlignedBytesGuard<'a, const A: usize> {
+    buffer: Vec<u8>,
+    offset: usize,
+    _source: PhantomData<&'a crate::ObfuseStr>,
+}
+
+impl<const A: usize> AlignedBytesGuard<'_, A> {
+    /// Copies `plaintext` into an aligned buffer and wipes the original.
+    pub(crate) fn new(mut plaintext: Box<[u8]>) -> Result<Self, ObfuseError> {
+        const { assert!(A.is_power_of_two(), "alignment must be a power of two") };
+
+        let result = Self::copy_aligned(&plaintext);
+        plaintext.zeroize();
+        result
+    }
+
+    fn copy_aligned(plaintext: &[u8]) -> Result<Self, ObfuseError> {
+        let capacity = plaintext
+            .len()
+            .checked_add(A - 1)
+            .ok_or(ObfuseError::AllocationFailed)?;
+        let mut buffer: Vec<u8> = Vec::new();
+        buffer
+            .try_reserve_exact(capacity)
+            .map_err(|_| ObfuseError::AllocationFailed)?;
+
+        // The reservation covers the padding, so these never reallocate
+        let address = buffer.as_ptr().addr();
+        let offset = address.next_multiple_of(A) - address;
+        buffer.resize(offset, 0);
+        buffer.extend_from_slice(plaintext);
+
+        Ok(Self {
+            buffer,
+            offset,
+            _source: PhantomData,
+        })
+    }
+
+    /// Returns the revealed bytes, starting at an `A`-byte boundary.
+    #[inline]
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[self.offset..]
+    }
+}
+
+impl<const A: usize> Deref for AlignedBytesGuard<'_, A> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_bytes()
+    }
+}
+
+impl<const A: usize> AsRef<[u8]> for AlignedBytesGuard<'_, A> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const A: usize> fmt::Debug for AlignedBytesGuard<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlignedBytesGuard")
+            .field("value", &"[REDACTED]")
+            .field("align", &A)
+            .finish()
+    }
+}
+
+impl<const A: usize> Drop for AlignedBytesGuard<'_, A> {
+    fn drop(&mut self) {
+        self.buffer.zeroize();
+    }
+}
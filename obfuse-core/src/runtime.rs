@@ -0,0 +1,248 @@
+//! Runtime encryption for secrets that only arrive at runtime.
+//!
+//! The macros encrypt literals at compile time; this module covers secrets
+//! read from stdin, a pipe or a file, encrypting them under a fresh random
+//! key so the plaintext does not linger in memory.
+
+use std::io::{self, Read};
+
+use zeroize::Zeroizing;
+
+use crate::cipher::{Cipher, decrypt_with};
+use crate::{KEY_SIZE, MAX_PLAINTEXT_LEN, NONCE_SIZE, ObfuseStr};
+
+#[cfg(any(
+    feature = "aes-256-gcm",
+    all(feature = "aes-128-gcm", not(feature = "aes-256-gcm"))
+))]
+use crate::aes::encrypt;
+
+#[cfg(all(
+    feature = "aes-256-ccm",
+    not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
+))]
+use crate::aes_ccm::encrypt;
+
+#[cfg(all(
+    feature = "chacha20-poly1305",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm"
+    ))
+))]
+use crate::chacha::encrypt;
+
+#[cfg(all(
+    feature = "xor",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
+        feature = "chacha20-poly1305"
+    ))
+))]
+use crate::xor::encrypt;
+
+/// Size of the stack buffer each `read` call fills.
+const CHUNK_SIZE: usize = 1024;
+
+impl ObfuseStr {
+    /// Reads a secret from `reader` and encrypts it under a fresh random key.
+    ///
+    /// Reads until end of input. Every buffer that held plaintext, including
+    /// ones outgrown while reading, is zeroed before it is freed, so only the
+    /// ciphertext outlives the call. The key and nonce come from the
+    /// operating system's random number generator.
+    ///
+    /// With `multi-aes`, the secret is encrypted with AES-256-GCM.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `reader` other than [`io::ErrorKind::Interrupted`],
+    /// an [`io::ErrorKind::InvalidInput`] error if the secret is longer than
+    /// [`MAX_PLAINTEXT_LEN`], or the random number generator's error.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let token = ObfuseStr::from_reader(&mut std::io::stdin().lock())?;
+    /// ```
+    pub fn from_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let plaintext = read_zeroizing(reader)?;
+        Self::encrypt_runtime(&plaintext)
+    }
+
+    /// Replaces the secret with one read from `reader`, encrypted under a
+    /// fresh random key, for rotating credentials without rebuilding the
+    /// structs that hold them.
+    ///
+    /// The input is read and wiped as in [`from_reader`](Self::from_reader).
+    /// The old ciphertext, key and nonce are zeroed and the cached plaintext
+    /// is dropped; access counts and honeytoken state are kept. Only strings
+    /// that own their ciphertext can be replaced: a literal embedded by the
+    /// `obfuse!` macro is immutable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::ErrorKind::PermissionDenied`] error if the string was
+    /// created by the macros, or any error [`from_reader`](Self::from_reader)
+    /// returns. `self` is unchanged on error.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// config.token.replace_from_reader(&mut File::open("/run/secrets/token")?)?;
+    /// ```
+    pub fn replace_from_reader<R: Read>(&mut self, reader: &mut R) -> io::Result<()> {
+        if self.is_embedded() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "compile-time secrets are immutable",
+            ));
+        }
+
+        self.replace_with(Self::from_reader(reader)?);
+        Ok(())
+    }
+
+    /// Encrypts `plaintext` with a user-supplied [`Cipher`] under a fresh
+    /// random key and nonce.
+    ///
+    /// For runtime secrets that must be protected with an algorithm the
+    /// crate features do not offer. The string remembers `C` and decrypts
+    /// with it from then on; every accessor works as usual. Padding and
+    /// permutation features are applied to the ciphertext as for built-in
+    /// strings. The string reports [`CUSTOM_ALGORITHM`](crate::CUSTOM_ALGORITHM)
+    /// as its algorithm, and [`to_base64`](Self::to_base64) refuses to export
+    /// it, since the export can only name the build's algorithm.
+    ///
+    /// Fails to compile if `C::KEY_SIZE` or `C::NONCE_SIZE` exceed the
+    /// build's [`KEY_SIZE`] or [`NONCE_SIZE`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the random number generator's error.
+    pub fn encrypt_with<C: Cipher>(plaintext: &[u8]) -> io::Result<Self> {
+        const {
+            assert!(
+                C::KEY_SIZE <= KEY_SIZE,
+                "cipher key does not fit the key storage"
+            );
+            assert!(
+                C::NONCE_SIZE <= NONCE_SIZE,
+                "cipher nonce does not fit the nonce storage"
+            );
+        }
+
+        let secret = Self::seal_random(|key, nonce| {
+            C::encrypt(plaintext, &key[..C::KEY_SIZE], &nonce[..C::NONCE_SIZE])
+        })?;
+        Ok(secret.with_cipher(decrypt_with::<C>))
+    }
+
+    /// Encrypts `plaintext` under a fresh random key and nonce.
+    fn encrypt_runtime(plaintext: &[u8]) -> io::Result<Self> {
+        // Never true for XOR, which has no limit
+        #[allow(clippy::absurd_extreme_comparisons)]
+        if u64::try_from(plaintext.len()).map_or(true, |len| len > MAX_PLAINTEXT_LEN) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "secret exceeds the algorithm's size limit",
+            ));
+        }
+
+        Self::seal_random(|key, nonce| encrypt(plaintext, key, nonce))
+    }
+
+    /// Encrypts with `seal` under a fresh random key and nonce, laying the
+    /// ciphertext out as the macros do.
+    fn seal_random(
+        seal: impl FnOnce(&[u8; KEY_SIZE], &[u8; NONCE_SIZE]) -> Vec<u8>,
+    ) -> io::Result<Self> {
+        let mut key = Zeroizing::new([0u8; KEY_SIZE]);
+        let mut nonce = [0u8; NONCE_SIZE];
+        getrandom::fill(key.as_mut_slice())?;
+        getrandom::fill(&mut nonce)?;
+
+        let ciphertext = seal(&key, &nonce);
+        #[cfg(feature = "ciphertext-padding")]
+        let ciphertext = crate::pad_ciphertext(&ciphertext, &nonce);
+        #[cfg(feature = "permute-ciphertext")]
+        let ciphertext = crate::permute_ciphertext(&ciphertext, &nonce);
+
+        Ok(Self::new_from_owned(ciphertext, *key, nonce))
+    }
+}
+
+/// Deserializes a plaintext string, encrypting it under a fresh random key
+/// (`serde-config` feature).
+///
+/// For config structs whose secret fields should be obfuscated as soon as
+/// the file is parsed. An owned intermediate `String` or byte buffer handed
+/// over by the deserializer is wiped; borrowed input (e.g. the document
+/// passed to `serde_json::from_str`) remains the caller's to wipe.
+#[cfg(feature = "serde-config")]
+impl<'de> serde::Deserialize<'de> for ObfuseStr {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_string(PlaintextVisitor)
+    }
+}
+
+#[cfg(feature = "serde-config")]
+struct PlaintextVisitor;
+
+#[cfg(feature = "serde-config")]
+impl serde::de::Visitor<'_> for PlaintextVisitor {
+    type Value = ObfuseStr;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a plaintext secret string")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<ObfuseStr, E> {
+        ObfuseStr::encrypt_runtime(value.as_bytes()).map_err(E::custom)
+    }
+
+    fn visit_string<E: serde::de::Error>(self, value: String) -> Result<ObfuseStr, E> {
+        let value = Zeroizing::new(value);
+        self.visit_str(&value)
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, value: &[u8]) -> Result<ObfuseStr, E> {
+        let value = std::str::from_utf8(value)
+            .map_err(|_| E::invalid_value(serde::de::Unexpected::Bytes(value), &self))?;
+        self.visit_str(value)
+    }
+
+    fn visit_byte_buf<E: serde::de::Error>(self, value: Vec<u8>) -> Result<ObfuseStr, E> {
+        let value = Zeroizing::new(value);
+        self.visit_bytes(&value)
+    }
+}
+
+/// Reads `reader` to the end without leaving copies of the data behind.
+///
+/// `Read::read_to_end` would free outgrown buffers without wiping them, so
+/// growth copies into a new zeroizing buffer instead.
+fn read_zeroizing<R: Read>(reader: &mut R) -> io::Result<Zeroizing<Vec<u8>>> {
+    let mut buffer = Zeroizing::new(Vec::new());
+    let mut chunk = Zeroizing::new([0u8; CHUNK_SIZE]);
+
+    loop {
+        let read = match reader.read(chunk.as_mut_slice()) {
+            Ok(0) => return Ok(buffer),
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        let needed = buffer.len() + read;
+        if needed > buffer.capacity() {
+            let mut grown = Zeroizing::new(Vec::with_capacity(needed.max(buffer.capacity() * 2)));
+            grown.extend_from_slice(&buffer);
+            buffer = grown;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+}
@@ -0,0 +1,39 @@
+//! The `Secret` trait - an object-safe view of obfuscated values for
+//! heterogeneous stores such as `Vec<Box<dyn Secret>>`.
+
+use std::fmt;
+
+use crate::{ObfuseError, ObfuseStr};
+
+/// An obfuscated value whose plaintext can be borrowed for the length of a
+/// callback.
+///
+/// The trait is object-safe, so secrets of different concrete types can be
+/// stored together as `Box<dyn Secret>`. `Debug` is a supertrait and
+/// implementations must redact the plaintext, so a store can be logged with
+/// `{:?}` safely.
+pub trait Secret: fmt::Debug {
+    /// Decrypts a fresh copy of the plaintext and passes it to `f`, wiping
+    /// the copy afterwards. Nothing is cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decryption fails; `f` is not called then.
+    fn reveal_scoped(&self, f: &mut dyn FnMut(&[u8])) -> Result<(), ObfuseError>;
+
+    /// Name of the algorithm protecting the value, e.g. `"AES-256-GCM"`, or
+    /// `"custom"` for strings encrypted with a user-supplied `Cipher`.
+    fn algorithm(&self) -> &'static str;
+}
+
+impl Secret for ObfuseStr {
+    fn reveal_scoped(&self, f: &mut dyn FnMut(&[u8])) -> Result<(), ObfuseError> {
+        f(&self.reveal_bytes()?);
+        Ok(())
+    }
+
+    #[inline]
+    fn algorithm(&self) -> &'static str {
+        ObfuseStr::algorithm(self)
+    }
+}
@@ -0,0 +1,178 @@
+//! The `SecretVec` buffer - a reusable plaintext buffer that wipes every
+//! allocation it leaves behind.
+
+use std::fmt;
+use std::ops::Deref;
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::ObfuseError;
+
+/// A growable byte buffer for plaintext that is decrypted into repeatedly.
+///
+/// A plain `Vec<u8>` frees its old allocation on growth without wiping it,
+/// leaving a stale copy of the plaintext on the heap. `SecretVec` instead
+/// grows by copying into a new allocation and wiping the old one first,
+/// wipes its contents on [`clear`](Self::clear), and wipes its whole
+/// capacity on drop. Fill it with [`ObfuseStr::decrypt_into_secret_vec`].
+///
+/// [`ObfuseStr::decrypt_into_secret_vec`]: crate::ObfuseStr::decrypt_into_secret_vec
+#[derive(Default)]
+pub struct SecretVec {
+    bytes: Vec<u8>,
+}
+
+impl SecretVec {
+    /// Creates an empty buffer without allocating.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { bytes: Vec::new() }
+    }
+
+    /// Creates an empty buffer with room for `capacity` bytes, so later
+    /// decryptions up to that size never reallocate.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            bytes: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of bytes held.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the buffer holds no bytes.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the number of bytes the buffer can hold without reallocating.
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Returns the bytes held.
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Wipes and removes the contents, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.bytes.zeroize();
+    }
+
+    /// Ensures room for at least `additional` more bytes, moving to a new
+    /// allocation (and wiping the old one) if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ObfuseError::AllocationFailed`] if the allocation fails; the
+    /// buffer is left unchanged.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), ObfuseError> {
+        grow_wiping(&mut self.bytes, additional)
+    }
+
+    /// Appends `bytes`, growing as [`try_reserve`](Self::try_reserve) does.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ObfuseError::AllocationFailed`] if the buffer cannot grow;
+    /// it is left unchanged.
+    pub fn try_extend_from_slice(&mut self, bytes: &[u8]) -> Result<(), ObfuseError> {
+        self.try_reserve(bytes.len())?;
+        self.bytes.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Moves the contents into an allocation of exactly their size, wiping
+    /// the old one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ObfuseError::AllocationFailed`] if the allocation fails; the
+    /// buffer is left unchanged.
+    pub fn try_shrink_to_fit(&mut self) -> Result<(), ObfuseError> {
+        if self.bytes.capacity() > self.bytes.len() {
+            let shrunk = copy_exact(&self.bytes, self.bytes.len())?;
+            self.bytes.zeroize();
+            self.bytes = shrunk;
+        }
+        Ok(())
+    }
+}
+
+/// Grows `bytes` by at least `additional` spare bytes, copying into a fresh
+/// allocation and wiping the old one, since `Vec`'s own reallocation frees
+/// the old block without wiping it.
+pub(crate) fn grow_wiping(bytes: &mut Vec<u8>, additional: usize) -> Result<(), ObfuseError> {
+    if bytes.capacity() - bytes.len() >= additional {
+        return Ok(());
+    }
+
+    let needed = bytes
+        .len()
+        .checked_add(additional)
+        .ok_or(ObfuseError::AllocationFailed)?;
+    let grown = copy_exact(bytes, needed.max(bytes.capacity().saturating_mul(2)))?;
+    bytes.zeroize();
+    *bytes = grown;
+    Ok(())
+}
+
+/// Copies `bytes` into a new allocation of exactly `capacity` bytes.
+fn copy_exact(bytes: &[u8], capacity: usize) -> Result<Vec<u8>, ObfuseError> {
+    let mut copy = Vec::new();
+    copy.try_reserve_exact(capacity)
+        .map_err(|_| ObfuseError::AllocationFailed)?;
+    copy.extend_from_slice(bytes);
+    Ok(copy)
+}
+
+impl Deref for SecretVec {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for SecretVec {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl fmt::Debug for SecretVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretVec")
+            .field("len", &self.len())
+            .field("value", &"[REDACTED]")
+            .finish()
+    }
+}
+
+impl Zeroize for SecretVec {
+    fn zeroize(&mut self) {
+        self.clear();
+    }
+}
+
+impl Drop for SecretVec {
+    fn drop(&mut self) {
+        self.bytes.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretVec {}
@@ -0,0 +1,45 @@
+//! Opt-in ciphertext serialization for `ObfuseStr` fields
+//! (`serialize-redacted` feature).
+//!
+//! With `serialize-redacted`, an `ObfuseStr` serializes as `"[REDACTED]"`.
+//! A field that must survive a round trip, e.g. through a cache file, opts
+//! into the [`ObfuseStr::to_base64`] export instead:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Cached {
+//!     #[serde(with = "obfuse::serde_ciphertext")]
+//!     token: ObfuseStr,
+//! }
+//! ```
+//!
+//! The export carries the key next to the ciphertext, so the serialized
+//! form keeps the plaintext out of the output but is exactly as secret as
+//! the plaintext itself.
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+use crate::ObfuseStr;
+
+/// Serializes `value` as its [`ObfuseStr::to_base64`] export, without
+/// decrypting.
+///
+/// # Errors
+///
+/// Returns the serializer's error, or a custom error if `value` was
+/// encrypted with a user-supplied cipher and cannot be exported.
+pub fn serialize<S: Serializer>(value: &ObfuseStr, serializer: S) -> Result<S::Ok, S::Error> {
+    let export = value.to_base64().map_err(serde::ser::Error::custom)?;
+    serializer.serialize_str(&export)
+}
+
+/// Deserializes an [`ObfuseStr::to_base64`] export, without decrypting.
+///
+/// # Errors
+///
+/// Returns the deserializer's error, or a custom error if the export is
+/// malformed or was produced with another algorithm.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ObfuseStr, D::Error> {
+    let export = String::deserialize(deserializer)?;
+    ObfuseStr::from_base64(&export).map_err(serde::de::Error::custom)
+}
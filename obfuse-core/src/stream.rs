@@ -0,0 +1,263 @@
+//! Chunked AEAD (STREAM construction) for the `streaming` feature.
+//!
+//! The plaintext is split into [`CHUNK_SIZE`] chunks, each sealed with its
+//! own tag under `aead::stream`'s big-endian 32-bit counter. The stream nonce
+//! is the first 7 bytes of the embedded 12-byte nonce; the remaining 5 bytes
+//! hold the counter and last-chunk flag. The final chunk is always sealed as
+//! "last" (an empty one for an empty plaintext), so truncating a stream at a
+//! chunk boundary fails authentication.
+//!
+//! The layout is duplicated in the macro crate and must stay in sync.
+
+use std::cell::Cell;
+use std::time::Instant;
+
+use zeroize::Zeroize;
+
+#[cfg(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))]
+use aes_gcm::aead;
+
+#[cfg(all(
+    feature = "aes-256-ccm",
+    not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
+))]
+use ccm::aead;
+
+#[cfg(all(
+    feature = "chacha20-poly1305",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm"
+    ))
+))]
+use chacha20poly1305::aead;
+
+use aead::generic_array::typenum::{U5, Unsigned};
+use aead::generic_array::{ArrayLength, GenericArray};
+use aead::stream::DecryptorBE32;
+#[cfg(any(test, feature = "runtime-encrypt"))]
+use aead::stream::EncryptorBE32;
+use aead::{AeadInPlace, KeyInit};
+use core::ops::Sub;
+
+/// Plaintext bytes sealed per chunk (64 KiB).
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Authentication tag appended to every chunk.
+const TAG_SIZE: usize = 16;
+
+/// Bytes of the embedded nonce used as the stream nonce prefix.
+const NONCE_PREFIX_SIZE: usize = 7;
+
+thread_local! {
+    /// Deadline checked before each chunk, set by [`with_deadline`].
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+
+    /// Whether the last failed [`decrypt`] stopped at the deadline rather
+    /// than on a bad tag.
+    static EXPIRED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with `deadline` checked between chunks of every [`decrypt`] on
+/// this thread, restoring the previous deadline afterwards.
+pub fn with_deadline<R>(deadline: Instant, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Instant>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            DEADLINE.set(self.0);
+            EXPIRED.set(false);
+        }
+    }
+
+    let _restore = Restore(DEADLINE.replace(Some(deadline)));
+    f()
+}
+
+/// Returns whether the last failed [`decrypt`] ran out of time, clearing
+/// the flag.
+pub fn take_expired() -> bool {
+    EXPIRED.take()
+}
+
+/// Fails the current chunk if the deadline set by [`with_deadline`] passed.
+fn check_deadline() -> Result<(), aead::Error> {
+    match DEADLINE.get() {
+        Some(deadline) if Instant::now() >= deadline => {
+            EXPIRED.set(true);
+            Err(aead::Error)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Largest well-formed streamed ciphertext, given the one-shot limit of the
+/// selected algorithm: every additional chunk adds one more tag.
+pub const fn max_ciphertext_len(one_shot: u64) -> u64 {
+    one_shot + (crate::MAX_PLAINTEXT_LEN / CHUNK_SIZE as u64) * TAG_SIZE as u64
+}
+
+/// Decrypts a chunked ciphertext produced by [`encrypt`] or the macros.
+///
+/// Stops before the next chunk once a deadline set by [`with_deadline`]
+/// passes, wiping what was decrypted so far.
+pub fn decrypt<A>(cipher: A, nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, aead::Error>
+where
+    A: AeadInPlace + KeyInit,
+    A::NonceSize: Sub<U5>,
+    <A::NonceSize as Sub<U5>>::Output: ArrayLength<u8>,
+{
+    debug_assert_eq!(A::TagSize::USIZE, TAG_SIZE);
+    let prefix = GenericArray::from_slice(&nonce[..NONCE_PREFIX_SIZE]);
+    let mut decryptor = DecryptorBE32::from_aead(cipher, prefix);
+
+    let mut chunks = ciphertext.chunks(CHUNK_SIZE + TAG_SIZE);
+    let last = chunks.next_back().unwrap_or_default();
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+
+    let result = (|| {
+        for chunk in chunks {
+            check_deadline()?;
+            let mut part = decryptor.decrypt_next(chunk)?;
+            plaintext.extend_from_slice(&part);
+            part.zeroize();
+        }
+        check_deadline()?;
+        let mut part = decryptor.decrypt_last(last)?;
+        plaintext.extend_from_slice(&part);
+        part.zeroize();
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => Ok(plaintext),
+        Err(err) => {
+            plaintext.zeroize();
+            Err(err)
+        }
+    }
+}
+
+/// Encrypts `plaintext` as a chunked stream, one tag per chunk.
+///
+/// # Panics
+///
+/// Panics if the plaintext needs more chunks than the 32-bit counter allows.
+#[cfg(any(test, feature = "runtime-encrypt"))]
+pub fn encrypt<A>(cipher: A, nonce: &[u8], plaintext: &[u8]) -> Vec<u8>
+where
+    A: AeadInPlace + KeyInit,
+    A::NonceSize: Sub<U5>,
+    <A::NonceSize as Sub<U5>>::Output: ArrayLength<u8>,
+{
+    let prefix = GenericArray::from_slice(&nonce[..NONCE_PREFIX_SIZE]);
+    let mut encryptor = EncryptorBE32::from_aead(cipher, prefix);
+
+    let mut chunks = plaintext.chunks(CHUNK_SIZE);
+    let last = chunks.next_back().unwrap_or_default();
+    let mut ciphertext = Vec::with_capacity(plaintext.len() + TAG_SIZE);
+
+    for chunk in chunks {
+        let sealed = encryptor
+            .encrypt_next(chunk)
+            .expect("chunk counter overflow");
+        ciphertext.extend_from_slice(&sealed);
+    }
+    let sealed = encryptor
+        .encrypt_last(last)
+        .expect("chunk counter overflow");
+    ciphertext.extend_from_slice(&sealed);
+    ciphertext
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))]
+    fn cipher() -> impl AeadInPlace<NonceSize = aead::consts::U12> + KeyInit {
+        aes_gcm::Aes128Gcm::new_from_slice(&[7; 16]).unwrap()
+    }
+
+    #[cfg(all(
+        feature = "aes-256-ccm",
+        not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
+    ))]
+    fn cipher() -> impl AeadInPlace<NonceSize = aead::consts::U12> + KeyInit {
+        ccm::Ccm::<aes::Aes256, aead::consts::U16, aead::consts::U12>::new_from_slice(&[7; 32])
+            .unwrap()
+    }
+
+    #[cfg(all(
+        feature = "chacha20-poly1305",
+        not(any(
+            feature = "aes-256-gcm",
+            feature = "aes-128-gcm",
+            feature = "aes-256-ccm"
+        ))
+    ))]
+    fn cipher() -> impl AeadInPlace<NonceSize = aead::consts::U12> + KeyInit {
+        chacha20poly1305::ChaCha20Poly1305::new_from_slice(&[7; 32]).unwrap()
+    }
+
+    const NONCE: [u8; 12] = [9; 12];
+
+    #[test]
+    fn test_round_trip_across_chunk_boundaries() {
+        for len in [
+            0,
+            1,
+            CHUNK_SIZE - 1,
+            CHUNK_SIZE,
+            CHUNK_SIZE + 1,
+            3 * CHUNK_SIZE + 5,
+        ] {
+            #[allow(clippy::cast_possible_truncation)]
+            let plaintext: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let ciphertext = encrypt(cipher(), &NONCE, &plaintext);
+
+            let chunks = len / CHUNK_SIZE + usize::from(len % CHUNK_SIZE != 0 || len == 0);
+            assert_eq!(ciphertext.len(), len + chunks * TAG_SIZE, "len {len}");
+            assert_eq!(decrypt(cipher(), &NONCE, &ciphertext).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_truncated_stream_is_rejected() {
+        let plaintext = vec![0x5a; 2 * CHUNK_SIZE + 1];
+        let ciphertext = encrypt(cipher(), &NONCE, &plaintext);
+
+        // Dropping the final chunk leaves a stream whose last chunk was not
+        // sealed as "last"
+        let truncated = &ciphertext[..2 * (CHUNK_SIZE + TAG_SIZE)];
+        assert!(decrypt(cipher(), &NONCE, truncated).is_err());
+        assert!(decrypt(cipher(), &NONCE, &[]).is_err());
+    }
+
+    #[test]
+    fn test_reordered_chunks_are_rejected() {
+        let plaintext = vec![0xa5; 2 * CHUNK_SIZE + 1];
+        let mut ciphertext = encrypt(cipher(), &NONCE, &plaintext);
+        let (first, rest) = ciphertext.split_at_mut(CHUNK_SIZE + TAG_SIZE);
+        first.swap_with_slice(&mut rest[..CHUNK_SIZE + TAG_SIZE]);
+
+        assert!(decrypt(cipher(), &NONCE, &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_deadline_stops_before_next_chunk() {
+        let plaintext = vec![0x3c; 2 * CHUNK_SIZE];
+        let ciphertext = encrypt(cipher(), &NONCE, &plaintext);
+
+        let result = with_deadline(Instant::now(), || {
+            let result = decrypt(cipher(), &NONCE, &ciphertext);
+            (result.is_err(), take_expired())
+        });
+        assert_eq!(result, (true, true));
+
+        // The deadline does not outlive the scope
+        assert_eq!(decrypt(cipher(), &NONCE, &ciphertext).unwrap(), plaintext);
+        assert!(!take_expired());
+    }
+}
@@ -2,15 +2,52 @@
 //!
 //! This is a simple obfuscation method, NOT cryptographically secure.
 //! Use only when performance is critical and strong security is not required.
+//!
+//! With the `polymorphic` feature, the otherwise unused nonce is read as a
+//! per-string program of six `(opcode, operand)` byte pairs that the macro
+//! applied after the key XOR. Every byte value is a valid instruction:
+//!
+//! | `opcode % 4` | Encrypt                 | Decrypt                       |
+//! |--------------|-------------------------|-------------------------------|
+//! | 0            | `b ^ operand`           | `b ^ operand`                 |
+//! | 1            | `b + operand`           | `b - operand`                 |
+//! | 2            | `b.rotate_left(op % 8)` | `b.rotate_right(op % 8)`      |
+//! | 3            | `b * (operand \| 1)`    | `b * (operand \| 1)^-1`       |
+//!
+//! Arithmetic wraps modulo 256; odd multipliers are always invertible.
 
 use crate::ObfuseError;
 
+/// Human-readable algorithm name.
+pub const ALGORITHM: &str = "XOR";
+
+/// Relative decryption work per ciphertext byte, the baseline for the other
+/// algorithms.
+#[cfg(not(feature = "polymorphic"))]
+pub const DECRYPT_COST_PER_BYTE: usize = 1;
+
+/// Relative decryption work per ciphertext byte (plain XOR is 1); undoing the
+/// transform program roughly doubles it.
+#[cfg(feature = "polymorphic")]
+pub const DECRYPT_COST_PER_BYTE: usize = 2;
+
 /// Key size for XOR cipher (32 bytes for consistency).
 pub const KEY_SIZE: usize = 32;
 
-/// Nonce size for XOR cipher (not used, but kept for API consistency).
+/// Nonce size for XOR cipher (unused unless `polymorphic` is enabled, but kept
+/// for API consistency).
 pub const NONCE_SIZE: usize = 12;
 
+/// XOR has no keystream or counter to exhaust, so any length is accepted.
+pub const MAX_PLAINTEXT_LEN: u64 = u64::MAX;
+
+/// XOR ciphertext is exactly as long as the plaintext.
+pub const MAX_CIPHERTEXT_LEN: u64 = MAX_PLAINTEXT_LEN;
+
+// No cipher to check against; these must match the macro crate's values
+const _: () = assert!(KEY_SIZE == 32);
+const _: () = assert!(NONCE_SIZE == 12);
+
 /// Decrypts ciphertext using XOR cipher.
 ///
 /// # Arguments
@@ -23,16 +60,203 @@ pub const NONCE_SIZE: usize = 12;
 ///
 /// # Security Warning
 /// XOR cipher provides NO authentication. Use AEAD ciphers for real security.
+#[cfg(not(feature = "polymorphic"))]
+// Infallible, but shares its signature with the AEAD backends
+#[allow(clippy::unnecessary_wraps)]
+#[cfg_attr(feature = "decrypt-noinline", inline(never), cold)]
 pub fn decrypt(
     ciphertext: &[u8],
     key: &[u8; KEY_SIZE],
     _nonce: &[u8; NONCE_SIZE],
 ) -> Result<Box<[u8]>, ObfuseError> {
-    let plaintext: Vec<u8> = ciphertext
+    let mut plaintext = ciphertext.to_vec();
+    xor_key(&mut plaintext, key);
+
+    Ok(plaintext.into_boxed_slice())
+}
+
+/// Decrypts ciphertext using XOR cipher and the per-string program in `nonce`.
+///
+/// # Arguments
+/// * `ciphertext` - The XOR-encrypted, transformed data
+/// * `key` - Encryption key (bytes are cycled if shorter than ciphertext)
+/// * `nonce` - The per-string program (see the module docs)
+///
+/// # Returns
+/// Decrypted plaintext bytes.
+///
+/// # Security Warning
+/// XOR cipher provides NO authentication. Use AEAD ciphers for real security.
+#[cfg(feature = "polymorphic")]
+// Infallible, but shares its signature with the AEAD backends
+#[allow(clippy::unnecessary_wraps)]
+#[cfg_attr(feature = "decrypt-noinline", inline(never), cold)]
+pub fn decrypt(
+    ciphertext: &[u8],
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+) -> Result<Box<[u8]>, ObfuseError> {
+    let program = inverse_program(nonce);
+
+    let mut plaintext: Vec<u8> = ciphertext
         .iter()
-        .enumerate()
-        .map(|(i, &byte)| byte ^ key[i % KEY_SIZE])
+        .map(|&byte| undo(&program, byte))
         .collect();
+    xor_key(&mut plaintext, key);
 
     Ok(plaintext.into_boxed_slice())
 }
+
+/// Encrypts plaintext using XOR cipher.
+///
+/// The inverse of [`decrypt`]; `_nonce` is unused.
+#[cfg(all(feature = "runtime-encrypt", not(feature = "polymorphic")))]
+pub fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], _nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    let mut ciphertext = plaintext.to_vec();
+    xor_key(&mut ciphertext, key);
+    ciphertext
+}
+
+/// Encrypts plaintext using XOR cipher, then runs the program in `nonce`.
+///
+/// The inverse of [`decrypt`].
+#[cfg(all(feature = "runtime-encrypt", feature = "polymorphic"))]
+pub fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    let mut ciphertext = plaintext.to_vec();
+    xor_key(&mut ciphertext, key);
+    for byte in &mut ciphertext {
+        *byte = nonce.chunks_exact(2).fold(*byte, |b, op| match op[0] % 4 {
+            0 => b ^ op[1],
+            1 => b.wrapping_add(op[1]),
+            2 => b.rotate_left(u32::from(op[1] % 8)),
+            _ => b.wrapping_mul(op[1] | 1),
+        });
+    }
+    ciphertext
+}
+
+/// XORs `data` in place with the key repeated.
+///
+/// Works a key-sized block at a time, zipping each block with the key, so
+/// the loop has no per-byte index arithmetic or bounds checks and the
+/// compiler can vectorize it.
+fn xor_key(data: &mut [u8], key: &[u8; KEY_SIZE]) {
+    let mut blocks = data.chunks_exact_mut(KEY_SIZE);
+    for block in &mut blocks {
+        for (byte, k) in block.iter_mut().zip(key) {
+            *byte ^= k;
+        }
+    }
+    for (byte, k) in blocks.into_remainder().iter_mut().zip(key) {
+        *byte ^= k;
+    }
+}
+
+/// Decodes the program, resolving multipliers to their inverses up front.
+#[cfg(feature = "polymorphic")]
+fn inverse_program(nonce: &[u8; NONCE_SIZE]) -> [(u8, u8); NONCE_SIZE / 2] {
+    let mut program = [(0, 0); NONCE_SIZE / 2];
+    for (slot, op) in program.iter_mut().zip(nonce.chunks_exact(2)) {
+        let opcode = op[0] % 4;
+        let operand = match opcode {
+            2 => op[1] % 8,
+            3 => crate::program::mul_inverse(op[1] | 1),
+            _ => op[1],
+        };
+        *slot = (opcode, operand);
+    }
+    program
+}
+
+/// Undoes the program for one byte, last instruction first.
+#[cfg(feature = "polymorphic")]
+fn undo(program: &[(u8, u8)], byte: u8) -> u8 {
+    program
+        .iter()
+        .rev()
+        .fold(byte, |b, &(opcode, operand)| match opcode {
+            0 => b ^ operand,
+            1 => b.wrapping_sub(operand),
+            2 => b.rotate_right(u32::from(operand)),
+            _ => b.wrapping_mul(operand),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The original per-byte loop, kept as the reference for `xor_key`.
+    fn xor_key_reference(data: &mut [u8], key: &[u8; KEY_SIZE]) {
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte ^= key[i % KEY_SIZE];
+        }
+    }
+
+    fn test_key() -> [u8; KEY_SIZE] {
+        std::array::from_fn(|i| u8::try_from(i * 37 % 256).unwrap())
+    }
+
+    #[test]
+    fn test_xor_key_matches_reference() {
+        let key = test_key();
+        for len in (0..4 * KEY_SIZE + 3).chain([1000, 4096 + 7]) {
+            let data: Vec<u8> = (0..len).map(|i| u8::try_from(i % 251).unwrap()).collect();
+            let mut fast = data.clone();
+            xor_key(&mut fast, &key);
+            let mut reference = data;
+            xor_key_reference(&mut reference, &key);
+            assert_eq!(fast, reference, "len {len}");
+        }
+    }
+
+    /// Compares `xor_key` with the per-byte reference on 16 MiB. Run with
+    /// `cargo test --release -p obfuse-core --no-default-features --features xor
+    /// -- --ignored --nocapture bench_xor_key`.
+    #[test]
+    #[ignore = "benchmark; run in release mode"]
+    fn bench_xor_key() {
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        let key = test_key();
+        let mut data = vec![0x5a; 16 << 20];
+
+        let start = Instant::now();
+        for _ in 0..8 {
+            xor_key_reference(black_box(&mut data), &key);
+        }
+        let reference = start.elapsed();
+
+        let start = Instant::now();
+        for _ in 0..8 {
+            xor_key(black_box(&mut data), &key);
+        }
+        let blocked = start.elapsed();
+
+        println!("per-byte modulo: {reference:?}, key blocks: {blocked:?}");
+    }
+
+    #[cfg(feature = "polymorphic")]
+    #[test]
+    fn test_every_opcode_is_invertible() {
+        for opcode in 0..4 {
+            for operand in 0..=255u8 {
+                let mut nonce = [0u8; NONCE_SIZE];
+                nonce[0] = opcode;
+                nonce[1] = operand;
+                let program = inverse_program(&nonce);
+
+                for byte in 0..=255u8 {
+                    let encrypted = match opcode {
+                        0 => byte ^ operand,
+                        1 => byte.wrapping_add(operand),
+                        2 => byte.rotate_left(u32::from(operand % 8)),
+                        _ => byte.wrapping_mul(operand | 1),
+                    };
+                    assert_eq!(undo(&program[..1], encrypted), byte);
+                }
+            }
+        }
+    }
+}
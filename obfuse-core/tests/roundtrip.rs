@@ -0,0 +1,137 @@
+//! Property tests: arbitrary bytes round-trip through the selected backend.
+//!
+//! Ciphertext is produced here with the same primitives the macro uses at
+//! compile time, then decrypted through `ObfuseStr`. `streaming` builds chunk
+//! the ciphertext instead; see the unit tests in `stream.rs`.
+
+#![cfg(not(feature = "streaming"))]
+
+use obfuse_core::{KEY_SIZE, NONCE_SIZE, ObfuseStr};
+use proptest::prelude::*;
+
+#[cfg(all(feature = "aes-256-gcm", not(feature = "multi-aes")))]
+fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    use aes_gcm::{Aes256Gcm, KeyInit, Nonce, aead::Aead};
+
+    let cipher = Aes256Gcm::new_from_slice(key).unwrap();
+    cipher.encrypt(Nonce::from_slice(nonce), plaintext).unwrap()
+}
+
+#[cfg(feature = "multi-aes")]
+fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    use aes_gcm::{Aes256Gcm, KeyInit, Nonce, aead::Aead};
+
+    let cipher = Aes256Gcm::new_from_slice(key).unwrap();
+    let ciphertext = cipher.encrypt(Nonce::from_slice(nonce), plaintext).unwrap();
+    std::iter::once(32).chain(ciphertext).collect()
+}
+
+#[cfg(all(feature = "aes-128-gcm", not(feature = "aes-256-gcm")))]
+fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    use aes_gcm::{Aes128Gcm, KeyInit, Nonce, aead::Aead};
+
+    let cipher = Aes128Gcm::new_from_slice(key).unwrap();
+    cipher.encrypt(Nonce::from_slice(nonce), plaintext).unwrap()
+}
+
+#[cfg(all(
+    feature = "aes-256-ccm",
+    not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
+))]
+fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    use ccm::consts::{U12, U16};
+    use ccm::{Ccm, KeyInit, Nonce, aead::Aead};
+
+    let cipher = Ccm::<aes::Aes256, U16, U12>::new_from_slice(key).unwrap();
+    cipher.encrypt(Nonce::from_slice(nonce), plaintext).unwrap()
+}
+
+#[cfg(all(
+    feature = "chacha20-poly1305",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm"
+    ))
+))]
+fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce, aead::Aead};
+
+    let cipher = ChaCha20Poly1305::new_from_slice(key).unwrap();
+    cipher.encrypt(Nonce::from_slice(nonce), plaintext).unwrap()
+}
+
+#[cfg(all(
+    feature = "xor",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
+        feature = "chacha20-poly1305"
+    ))
+))]
+fn encrypt(plaintext: &[u8], key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    // Apply the per-string program the macro uses with `polymorphic`
+    let transform = |byte: u8| {
+        if cfg!(not(feature = "polymorphic")) {
+            return byte;
+        }
+        nonce.chunks_exact(2).fold(byte, |b, op| match op[0] % 4 {
+            0 => b ^ op[1],
+            1 => b.wrapping_add(op[1]),
+            2 => b.rotate_left(u32::from(op[1] % 8)),
+            _ => b.wrapping_mul(op[1] | 1),
+        })
+    };
+
+    plaintext
+        .iter()
+        .enumerate()
+        .map(|(i, &byte)| transform(byte ^ key[i % KEY_SIZE]))
+        .collect()
+}
+
+/// Encrypts `plaintext` and asserts that `ObfuseStr` decrypts it unchanged.
+fn assert_round_trip(plaintext: &[u8], key: [u8; KEY_SIZE], nonce: [u8; NONCE_SIZE]) {
+    let ciphertext = encrypt(plaintext, &key, &nonce);
+    #[cfg(feature = "ciphertext-padding")]
+    let ciphertext = obfuse_core::pad_ciphertext(&ciphertext, &nonce);
+    #[cfg(feature = "permute-ciphertext")]
+    let ciphertext = obfuse_core::permute_ciphertext(&ciphertext, &nonce);
+
+    // Takes the raw key; `new` expects the masked key the macro emits
+    let secret = ObfuseStr::new_from_owned(ciphertext, key, nonce);
+
+    assert_eq!(secret.try_as_bytes().unwrap(), plaintext);
+}
+
+#[test]
+fn test_all_byte_values() {
+    let plaintext: Vec<u8> = (0..=255).collect();
+    assert_round_trip(&plaintext, [0x42; KEY_SIZE], [0x24; NONCE_SIZE]);
+}
+
+#[test]
+fn test_edge_lengths() {
+    // Empty, single byte, around the 16-byte tag/AES block, the 32-byte XOR
+    // key period and the 64-byte ChaCha block, and large inputs
+    let lengths = [0, 1, 15, 16, 17, 31, 32, 33, 63, 64, 65, 4095, 4096, 4097];
+
+    for len in lengths {
+        // Truncation is intentional - we want every byte value to appear
+        #[allow(clippy::cast_possible_truncation)]
+        let plaintext: Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+        assert_round_trip(&plaintext, [0x5a; KEY_SIZE], [0xa5; NONCE_SIZE]);
+    }
+}
+
+proptest! {
+    #[test]
+    fn prop_round_trip(
+        plaintext in proptest::collection::vec(any::<u8>(), 0..4096),
+        key in any::<[u8; KEY_SIZE]>(),
+        nonce in any::<[u8; NONCE_SIZE]>(),
+    ) {
+        assert_round_trip(&plaintext, key, nonce);
+    }
+}
@@ -0,0 +1,17 @@
+//! Compile-time checks of the thread-safety guarantees.
+//!
+//! `ObfuseStr` values are stored in statics and shared across threads, so a
+//! field that is not `Send + Sync` (an `Rc`, a `Cell`, ...) must fail the build
+//! rather than surface in downstream crates. Likewise, a panic during
+//! decryption must not leave a value that is unsound to use afterwards.
+
+use std::panic::{RefUnwindSafe, UnwindSafe};
+
+use obfuse_core::{ObfuseError, ObfuseStr, ParseSecretError, Revealed};
+use static_assertions::assert_impl_all;
+
+assert_impl_all!(ObfuseStr: Send, Sync);
+assert_impl_all!(ObfuseStr: UnwindSafe, RefUnwindSafe);
+assert_impl_all!(ObfuseError: Send, Sync, std::error::Error);
+assert_impl_all!(ParseSecretError<std::num::ParseIntError>: Send, Sync);
+assert_impl_all!(Revealed: Send, Sync);
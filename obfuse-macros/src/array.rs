@@ -0,0 +1,64 @@
+//! Input parsing for the `obfuse_array!` macro.
+
+use syn::{LitStr, Token, parse::Parse, parse::ParseStream};
+
+use crate::ObfuseInput;
+use crate::input::Literal;
+
+/// Input to `obfuse_array!`: one or more string literals followed by the
+/// usual `obfuse!` options, all encrypted as a single payload.
+pub struct ArrayInput {
+    pub value: ObfuseInput,
+    /// Number of elements.
+    pub len: usize,
+}
+
+impl Parse for ArrayInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if !input.peek(LitStr) {
+            return Err(input.error("`obfuse_array!` requires at least one string literal"));
+        }
+
+        let mut elements: Vec<LitStr> = vec![input.parse()?];
+        while input.peek(Token![,]) && input.peek2(LitStr) {
+            input.parse::<Token![,]>()?;
+            elements.push(input.parse()?);
+        }
+
+        if let Some(long) = elements
+            .iter()
+            .find(|element| u32::try_from(element.value().len()).is_err())
+        {
+            return Err(syn::Error::new(
+                long.span(),
+                "array elements must be shorter than 4 GiB",
+            ));
+        }
+
+        let len = elements.len();
+        let value = ObfuseInput::parse_with_literal(Literal::Array(elements), input)?;
+        Ok(Self { value, len })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elements_and_options() {
+        let input: ArrayInput = syn::parse_quote!("a", "bc", "", seed = "s");
+        assert_eq!(input.len, 3);
+        assert_eq!(input.value.seed().as_deref(), Some("s"));
+
+        let trailing: ArrayInput = syn::parse_quote!("a", "b",);
+        assert_eq!(trailing.len, 2);
+    }
+
+    #[test]
+    fn test_requires_an_element() {
+        let result = syn::parse_str::<ArrayInput>(r#"seed = "s""#);
+        let error = result.err().expect("no elements");
+        assert!(error.to_string().contains("at least one"));
+    }
+}
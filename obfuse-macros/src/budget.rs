@@ -0,0 +1,181 @@
+//! Best-effort size budget for embedded ciphertext.
+//!
+//! Every expansion adds its ciphertext length to a process-wide counter. When
+//! `OBFUSE_SIZE_BUDGET` is set and the running total first exceeds it, that
+//! expansion emits a warning. A proc macro cannot tell which expansion is the
+//! last one in a crate, so the warning points at the string that crossed the
+//! budget, and anything expanded afterwards is counted but not reported again.
+//!
+//! The counter lives as long as the proc-macro library stays loaded: one
+//! `rustc` invocation for a normal build, which is one crate. Long-running
+//! hosts such as rust-analyzer keep it loaded and see inflated totals.
+//!
+//! Independently of the budget, every string over `OBFUSE_LARGE_LITERAL`
+//! bytes (64 KiB by default) gets its own warning: obfuscation is meant for
+//! secrets, and a literal that size is more likely an embedded template or
+//! asset that slows the build and bloats the binary.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+const BUDGET_ENV: &str = "OBFUSE_SIZE_BUDGET";
+
+const LARGE_LITERAL_ENV: &str = "OBFUSE_LARGE_LITERAL";
+
+/// Per-string size above which a literal is reported, unless overridden.
+const DEFAULT_LARGE_LITERAL: usize = 64 * 1024;
+
+static BUDGET: Budget = Budget::new();
+
+/// Running total of embedded ciphertext bytes.
+struct Budget {
+    embedded: AtomicUsize,
+    warned: AtomicBool,
+}
+
+impl Budget {
+    const fn new() -> Self {
+        Self {
+            embedded: AtomicUsize::new(0),
+            warned: AtomicBool::new(false),
+        }
+    }
+
+    /// Adds `bytes` to the total, returning a warning the first time the total
+    /// exceeds `limit`.
+    fn record(&self, bytes: usize, limit: Option<usize>) -> Option<TokenStream2> {
+        let total = self.embedded.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let limit = limit.filter(|&limit| total > limit)?;
+
+        if self.warned.swap(true, Ordering::Relaxed) {
+            return None;
+        }
+        Some(warning_tokens(total, limit))
+    }
+}
+
+/// Records `bytes` of embedded ciphertext, returning warning tokens if this
+/// pushes the crate over `OBFUSE_SIZE_BUDGET`.
+pub fn track(bytes: usize) -> Option<TokenStream2> {
+    BUDGET.record(bytes, size_budget_from(std::env::var(BUDGET_ENV).ok()))
+}
+
+/// Parses the budget in bytes; empty or malformed values disable it.
+fn size_budget_from(value: Option<String>) -> Option<usize> {
+    value?.trim().parse().ok()
+}
+
+/// Returns warning tokens if one string embeds more than
+/// `OBFUSE_LARGE_LITERAL` bytes of ciphertext.
+pub fn check_literal(bytes: usize) -> Option<TokenStream2> {
+    let limit = large_literal_from(std::env::var(LARGE_LITERAL_ENV).ok())?;
+    (bytes > limit).then(|| large_literal_tokens(bytes, limit))
+}
+
+/// Parses the per-string threshold in bytes: unset, empty or malformed
+/// values keep the default, and `0` disables the check.
+fn large_literal_from(value: Option<String>) -> Option<usize> {
+    match value.and_then(|value| value.trim().parse().ok()) {
+        Some(0) => None,
+        Some(limit) => Some(limit),
+        None => Some(DEFAULT_LARGE_LITERAL),
+    }
+}
+
+/// Generates an item whose only effect is a compiler warning.
+///
+/// Stable proc macros cannot emit warnings directly, so this references a
+/// `#[deprecated]` constant and lets the deprecation lint carry the message.
+fn warning_tokens(total: usize, limit: usize) -> TokenStream2 {
+    let note = format!(
+        "obfuscated strings embed {total} bytes of ciphertext, \
+         over the {BUDGET_ENV} of {limit} bytes"
+    );
+
+    quote! {
+        const _: () = {
+            #[deprecated(note = #note)]
+            #[allow(non_upper_case_globals)]
+            const obfuse_size_budget_exceeded: () = ();
+            obfuse_size_budget_exceeded
+        };
+    }
+}
+
+/// Generates the deprecation-lint warning for one oversized string.
+fn large_literal_tokens(bytes: usize, limit: usize) -> TokenStream2 {
+    let note = format!(
+        "this obfuscated string embeds {bytes} bytes of ciphertext, over the \
+         {LARGE_LITERAL_ENV} threshold of {limit} bytes; large non-secret data \
+         such as templates belongs in `include_str!` or a compressed asset"
+    );
+
+    quote! {
+        const _: () = {
+            #[deprecated(note = #note)]
+            #[allow(non_upper_case_globals)]
+            const obfuse_large_literal: () = ();
+            obfuse_large_literal
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_budget_from() {
+        assert_eq!(size_budget_from(None), None);
+        assert_eq!(size_budget_from(Some(String::new())), None);
+        assert_eq!(size_budget_from(Some("lots".to_owned())), None);
+        assert_eq!(size_budget_from(Some(" 4096 ".to_owned())), Some(4096));
+    }
+
+    #[test]
+    fn test_warns_once_over_budget() {
+        let budget = Budget::new();
+
+        assert!(budget.record(20, Some(64)).is_none());
+        assert!(budget.record(44, Some(64)).is_none());
+
+        let warning = budget.record(1, Some(64)).expect("over budget");
+        let warning = warning.to_string();
+        assert!(warning.contains("deprecated"));
+        assert!(warning.contains("65 bytes"));
+        assert!(warning.contains("OBFUSE_SIZE_BUDGET of 64 bytes"));
+
+        // Counted, but reported only once
+        assert!(budget.record(100, Some(64)).is_none());
+        assert_eq!(budget.embedded.load(Ordering::Relaxed), 165);
+    }
+
+    #[test]
+    fn test_large_literal_from() {
+        assert_eq!(large_literal_from(None), Some(DEFAULT_LARGE_LITERAL));
+        assert_eq!(
+            large_literal_from(Some("huge".to_owned())),
+            Some(DEFAULT_LARGE_LITERAL)
+        );
+        assert_eq!(large_literal_from(Some(" 1024 ".to_owned())), Some(1024));
+        assert_eq!(large_literal_from(Some("0".to_owned())), None);
+    }
+
+    #[test]
+    fn test_large_literal_warns() {
+        let warning = large_literal_tokens(500 * 1024, DEFAULT_LARGE_LITERAL).to_string();
+        assert!(warning.contains("deprecated"));
+        assert!(warning.contains("512000 bytes"));
+        assert!(warning.contains("OBFUSE_LARGE_LITERAL threshold of 65536 bytes"));
+        assert!(warning.contains("include_str!"));
+    }
+
+    #[test]
+    fn test_no_budget_never_warns() {
+        let budget = Budget::new();
+        assert!(budget.record(usize::MAX / 2, None).is_none());
+        assert!(!budget.warned.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,435 @@
+//! Named obfuscated string declarations for the `obfuse_const!` macro.
+
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{ToTokens, format_ident, quote};
+use syn::{Attribute, Ident, LitStr, Token, Visibility, parse::Parse, parse::ParseStream};
+
+use crate::budget;
+#[cfg(feature = "shuffle-layout")]
+use crate::encrypt::layout_order;
+use crate::encrypt::{NONCE_SIZE, env_seed, manifest_hmac, manifest_key, symbol_entropy};
+use crate::{
+    ObfuseInput, byte_array_tokens, fixed_byte_array_tokens, fuzzing_select, key_tokens, new_tokens,
+};
+
+/// Input to the `obfuse_const!` macro: one or more declarations of the form
+/// `#[attrs] vis static NAME = <obfuse! input>;`, plus at most one
+/// `#[attrs] vis manifest NAME [, seed = "..."];`
+pub struct ObfuseConstInput {
+    items: Vec<ObfuseConstItem>,
+    manifest: Option<ManifestItem>,
+}
+
+struct ObfuseConstItem {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    static_token: Token![static],
+    name: Ident,
+    value: ObfuseInput,
+}
+
+struct ManifestItem {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    seed: Option<LitStr>,
+}
+
+impl Parse for ObfuseConstInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut items = Vec::new();
+        let mut manifest: Option<ManifestItem> = None;
+
+        while !input.is_empty() {
+            let attrs = input.call(Attribute::parse_outer)?;
+            let vis: Visibility = input.parse()?;
+
+            if input.peek(Ident) && !input.peek(Token![static]) {
+                let item = parse_manifest(input, attrs, vis)?;
+                if manifest.is_some() {
+                    return Err(syn::Error::new(
+                        item.name.span(),
+                        "only one `manifest` may be declared per `obfuse_const!`",
+                    ));
+                }
+                manifest = Some(item);
+            } else {
+                items.push(parse_item(input, attrs, vis)?);
+            }
+        }
+
+        Ok(Self { items, manifest })
+    }
+}
+
+fn parse_item(
+    input: ParseStream,
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+) -> syn::Result<ObfuseConstItem> {
+    let static_token = input.parse()?;
+    let name: Ident = input.parse()?;
+    input.parse::<Token![=]>()?;
+    let value: ObfuseInput = input.parse()?;
+    input.parse::<Token![;]>()?;
+
+    Ok(ObfuseConstItem {
+        attrs,
+        vis,
+        static_token,
+        name,
+        value,
+    })
+}
+
+/// Parses `manifest NAME [, seed = "..."];`
+fn parse_manifest(
+    input: ParseStream,
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+) -> syn::Result<ManifestItem> {
+    let keyword: Ident = input.parse()?;
+    if keyword != "manifest" {
+        return Err(syn::Error::new(
+            keyword.span(),
+            format!("expected `static` or `manifest`, found `{keyword}`"),
+        ));
+    }
+
+    let name: Ident = input.parse()?;
+
+    let seed = if input.peek(Token![,]) {
+        input.parse::<Token![,]>()?;
+        let ident: Ident = input.parse()?;
+        if ident != "seed" {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!("expected `seed`, found `{ident}`"),
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        Some(input.parse::<LitStr>()?)
+    } else {
+        None
+    };
+    input.parse::<Token![;]>()?;
+
+    Ok(ManifestItem {
+        attrs,
+        vis,
+        name,
+        seed,
+    })
+}
+
+pub fn obfuse_const_impl(input: &ObfuseConstInput) -> TokenStream2 {
+    let encrypted: Vec<_> = input
+        .items
+        .iter()
+        .map(|item| item.value.encrypt())
+        .collect();
+    let fuzzing: Vec<_> = input
+        .items
+        .iter()
+        .map(|item| item.value.encrypt_for_fuzzing())
+        .collect();
+
+    // Identical ciphertext (seeded or pinned duplicates) shares one backing
+    // static, as long as the linker section and fuzz-build ciphertext match too
+    let mut backings: HashMap<_, Ident> = HashMap::new();
+    #[cfg_attr(not(feature = "shuffle-layout"), allow(unused_mut))]
+    let mut items: Vec<_> = input
+        .items
+        .iter()
+        .zip(&encrypted)
+        .zip(&fuzzing)
+        .map(|((item, (ciphertext, key, nonce)), fuzzing)| {
+            let section = item.value.section_attrs();
+            let dedup_key = (
+                ciphertext.as_slice(),
+                fuzzing
+                    .as_ref()
+                    .map(|(ciphertext, _, _)| ciphertext.as_slice()),
+                section.to_token_stream().to_string(),
+            );
+
+            let (backing, backing_tokens) = match backings.get(&dedup_key) {
+                Some(shared) => (shared.clone(), None),
+                None => {
+                    let backing = backing_ident(item.value.seed().as_deref(), &item.name);
+                    backings.insert(dedup_key, backing.clone());
+                    let len = ciphertext.len();
+                    let warning = budget::check_literal(len);
+                    let budget_warning = budget::track(len);
+                    let ciphertext_tokens = fuzzing_select(
+                        byte_array_tokens(ciphertext),
+                        fuzzing
+                            .as_ref()
+                            .map(|(ciphertext, _, _)| byte_array_tokens(ciphertext)),
+                    );
+                    let tokens = quote! {
+                        #warning
+                        #budget_warning
+                        #section
+                        #[doc(hidden)]
+                        #[allow(non_upper_case_globals)]
+                        static #backing: [u8; #len] = #ciphertext_tokens;
+                    };
+                    (backing, Some(tokens))
+                }
+            };
+
+            let key_tokens = fuzzing_select(
+                key_tokens(key, nonce),
+                fuzzing
+                    .as_ref()
+                    .map(|(_, key, nonce)| key_tokens(key, nonce)),
+            );
+            let nonce_tokens = fuzzing_select(
+                fixed_byte_array_tokens::<NONCE_SIZE>(nonce),
+                fuzzing
+                    .as_ref()
+                    .map(|(_, _, nonce)| fixed_byte_array_tokens::<NONCE_SIZE>(nonce)),
+            );
+            let item = item_tokens(item, &backing, &key_tokens, &nonce_tokens);
+            quote! {
+                #backing_tokens
+                #item
+            }
+        })
+        .collect();
+
+    #[cfg(feature = "shuffle-layout")]
+    shuffle_layout(&mut items, &input.items);
+
+    let manifest = input.manifest.as_ref().map(|manifest| {
+        let ciphertexts: Vec<_> = encrypted
+            .iter()
+            .map(|(ciphertext, _, _)| ciphertext.as_slice())
+            .collect();
+        let fuzzing_ciphertexts = fuzzing.iter().any(Option::is_some).then(|| {
+            fuzzing
+                .iter()
+                .zip(&ciphertexts)
+                .map(|(fuzzing, ciphertext)| {
+                    fuzzing
+                        .as_ref()
+                        .map_or(*ciphertext, |(ciphertext, _, _)| ciphertext.as_slice())
+                })
+                .collect::<Vec<_>>()
+        });
+        manifest_tokens(
+            manifest,
+            &input.items,
+            &ciphertexts,
+            fuzzing_ciphertexts.as_deref(),
+        )
+    });
+
+    quote! {
+        #(#items)*
+        #manifest
+    }
+}
+
+/// Reorders the emitted statics so their layout in the data segment does
+/// not follow source order; item order has no meaning in Rust, so behavior
+/// is unchanged. Fully seeded blocks get a reproducible order.
+#[cfg(feature = "shuffle-layout")]
+fn shuffle_layout(tokens: &mut Vec<TokenStream2>, items: &[ObfuseConstItem]) {
+    let seed = items
+        .iter()
+        .map(|item| Some(format!("{}:{}", item.value.seed()?, item.name)))
+        .collect::<Option<Vec<_>>>()
+        .map(|seeds| seeds.join("|"));
+
+    let mut slots: Vec<_> = tokens.drain(..).map(Some).collect();
+    tokens.extend(
+        layout_order(slots.len(), seed.as_deref())
+            .into_iter()
+            .filter_map(|i| slots[i].take()),
+    );
+}
+
+fn item_tokens(
+    item: &ObfuseConstItem,
+    backing: &Ident,
+    key_tokens: &TokenStream2,
+    nonce_tokens: &TokenStream2,
+) -> TokenStream2 {
+    let ObfuseConstItem {
+        attrs,
+        vis,
+        static_token,
+        name,
+        ..
+    } = item;
+
+    let constructor = new_tokens(&item.value, &quote! { &#backing }, key_tokens, nonce_tokens);
+
+    quote! {
+        #(#attrs)*
+        #vis #static_token #name: ::obfuse::ObfuseStr = #constructor;
+    }
+}
+
+fn manifest_tokens(
+    manifest: &ManifestItem,
+    items: &[ObfuseConstItem],
+    ciphertexts: &[&[u8]],
+    fuzzing_ciphertexts: Option<&[&[u8]]>,
+) -> TokenStream2 {
+    let ManifestItem {
+        attrs,
+        vis,
+        name,
+        seed,
+    } = manifest;
+
+    let seed = seed.as_ref().map(LitStr::value).or_else(env_seed);
+    let key = manifest_key(seed.as_deref());
+    let expected = manifest_hmac(&key, ciphertexts.iter().copied());
+    let fuzzing_expected =
+        fuzzing_ciphertexts.map(|ciphertexts| manifest_hmac(&key, ciphertexts.iter().copied()));
+
+    let names = items.iter().map(|item| &item.name);
+    let key_tokens = fixed_byte_array_tokens(&key);
+    let expected_tokens = fuzzing_select(
+        fixed_byte_array_tokens(&expected),
+        fuzzing_expected.map(|expected| fixed_byte_array_tokens(&expected)),
+    );
+
+    quote! {
+        #(#attrs)*
+        #vis static #name: ::obfuse::Manifest = ::obfuse::Manifest::new(
+            &[#(&#names),*],
+            #key_tokens,
+            #expected_tokens,
+        );
+    }
+}
+
+/// Generates the name of a hidden static.
+///
+/// The user-facing name is mixed in so seeded declarations sharing a seed
+/// still get distinct backing statics.
+pub fn backing_ident(seed: Option<&str>, name: impl std::fmt::Display) -> Ident {
+    let entropy = match seed {
+        Some(seed) => symbol_entropy(Some(&format!("{seed}:{name}"))),
+        None => symbol_entropy(None),
+    };
+    format_ident!("_{:016x}", entropy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backing_name(tokens: &TokenStream2) -> String {
+        let mut iter = tokens.clone().into_iter().map(|t| t.to_string());
+        iter.find(|t| t == "static");
+        iter.next().expect("backing static name")
+    }
+
+    #[test]
+    fn test_backing_names_are_random() {
+        let input: ObfuseConstInput = syn::parse_quote! {
+            static SECRET = "value";
+        };
+
+        let first = obfuse_const_impl(&input);
+        let second = obfuse_const_impl(&input);
+
+        let first_name = backing_name(&first);
+        assert!(first_name.starts_with('_'));
+        assert!(!first_name.to_uppercase().contains("OBFUSE"));
+        assert_ne!(first_name, backing_name(&second));
+    }
+
+    #[test]
+    fn test_backing_names_follow_seed() {
+        let input: ObfuseConstInput = syn::parse_quote! {
+            static A = "value", seed = "s";
+            static B = "value", seed = "s";
+        };
+
+        let first = obfuse_const_impl(&input).to_string();
+        let second = obfuse_const_impl(&input).to_string();
+        assert_eq!(first, second);
+
+        let a: ObfuseConstInput = syn::parse_quote! { static A = "value", seed = "s"; };
+        let b: ObfuseConstInput = syn::parse_quote! { static B = "value", seed = "s"; };
+        assert_ne!(
+            backing_name(&obfuse_const_impl(&a)),
+            backing_name(&obfuse_const_impl(&b))
+        );
+    }
+
+    #[test]
+    fn test_identical_ciphertext_shares_backing() {
+        let input: ObfuseConstInput = syn::parse_quote! {
+            static A = "same", seed = "s";
+            static B = "same", seed = "s";
+            static C = "other", seed = "s";
+            static D = "same";
+        };
+
+        let tokens = obfuse_const_impl(&input).to_string();
+        // A and B share one backing static; C and D (random key) get their own
+        assert_eq!(tokens.matches("# [doc (hidden)]").count(), 3);
+    }
+
+    #[test]
+    fn test_fuzzing_variant() {
+        let random: ObfuseConstInput = syn::parse_quote! { static A = "value"; };
+        let tokens = obfuse_const_impl(&random).to_string();
+        assert!(tokens.contains("# [cfg (fuzzing)]"));
+        assert!(tokens.contains("# [cfg (not (fuzzing))]"));
+        assert_eq!(tokens.matches("# [doc (hidden)]").count(), 1);
+
+        // Seeded blocks are already reproducible
+        let seeded: ObfuseConstInput = syn::parse_quote! { static A = "value", seed = "s"; };
+        assert!(!obfuse_const_impl(&seeded).to_string().contains("fuzzing"));
+    }
+
+    #[test]
+    fn test_single_manifest() {
+        let result =
+            syn::parse_str::<ObfuseConstInput>(r#"static A = "a"; manifest M1; manifest M2;"#);
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "shuffle-layout")]
+    #[test]
+    fn test_shuffle_layout_order() {
+        fn declared_order(tokens: &TokenStream2) -> Vec<String> {
+            let tokens: Vec<_> = tokens.clone().into_iter().map(|t| t.to_string()).collect();
+            tokens
+                .windows(2)
+                .filter(|pair| pair[0] == "static" && !pair[1].starts_with('_'))
+                .map(|pair| pair[1].clone())
+                .collect()
+        }
+
+        let random: ObfuseConstInput = syn::parse_quote! {
+            static A = "a"; static B = "b"; static C = "c"; static D = "d";
+            static E = "e"; static F = "f"; static G = "g"; static H = "h";
+        };
+        let first = declared_order(&obfuse_const_impl(&random));
+        let mut sorted = first.clone();
+        sorted.sort();
+        assert_eq!(sorted, ["A", "B", "C", "D", "E", "F", "G", "H"]);
+        assert_ne!(first, declared_order(&obfuse_const_impl(&random)));
+
+        let seeded: ObfuseConstInput = syn::parse_quote! {
+            static A = "a", seed = "s"; static B = "b", seed = "s";
+            static C = "c", seed = "s"; static D = "d", seed = "s";
+        };
+        assert_eq!(
+            obfuse_const_impl(&seeded).to_string(),
+            obfuse_const_impl(&seeded).to_string()
+        );
+    }
+}
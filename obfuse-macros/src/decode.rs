@@ -0,0 +1,129 @@
+//! Decoding of pre-encrypted payloads given as hex or base64 literals.
+//!
+//! Used by the `obfuse!(hex = "...", ...)` and `obfuse!(base64 = "...", ...)`
+//! forms, where the ciphertext, key and nonce were produced out-of-band.
+
+/// Text encoding of a pre-encrypted payload.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Hexadecimal, two digits per byte (case-insensitive).
+    Hex,
+    /// Standard base64 alphabet with optional `=` padding.
+    Base64,
+}
+
+impl Encoding {
+    /// Returns the encoding named by a macro argument, if any.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "hex" => Some(Self::Hex),
+            "base64" => Some(Self::Base64),
+            _ => None,
+        }
+    }
+
+    /// Decodes `input` into raw bytes.
+    ///
+    /// Whitespace is ignored so long payloads can be split across lines.
+    pub fn decode(self, input: &str) -> Result<Vec<u8>, String> {
+        let digits: Vec<u8> = input.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+
+        match self {
+            Self::Hex => decode_hex(&digits),
+            Self::Base64 => decode_base64(&digits),
+        }
+    }
+}
+
+/// Decodes a hex string into bytes.
+fn decode_hex(digits: &[u8]) -> Result<Vec<u8>, String> {
+    if digits.len() % 2 != 0 {
+        return Err(format!(
+            "hex input has odd length {}; expected two digits per byte",
+            digits.len()
+        ));
+    }
+
+    digits
+        .chunks_exact(2)
+        .map(|pair| Ok((hex_value(pair[0])? << 4) | hex_value(pair[1])?))
+        .collect()
+}
+
+fn hex_value(digit: u8) -> Result<u8, String> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(format!("invalid hex digit `{}`", char::from(digit))),
+    }
+}
+
+/// Decodes a standard (RFC 4648) base64 string into bytes.
+fn decode_base64(digits: &[u8]) -> Result<Vec<u8>, String> {
+    let unpadded = digits
+        .strip_suffix(b"==")
+        .or_else(|| digits.strip_suffix(b"="));
+    let data = unpadded.unwrap_or(digits);
+
+    if unpadded.is_some() && digits.len() % 4 != 0 {
+        return Err("base64 input has invalid padding".to_string());
+    }
+    if data.len() % 4 == 1 {
+        return Err(format!("base64 input has invalid length {}", data.len()));
+    }
+
+    let mut output = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &digit in data {
+        buffer = (buffer << 6) | u32::from(base64_value(digit)?);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            // Truncation is intentional - we extract the next full byte
+            #[allow(clippy::cast_possible_truncation)]
+            output.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(output)
+}
+
+fn base64_value(digit: u8) -> Result<u8, String> {
+    match digit {
+        b'A'..=b'Z' => Ok(digit - b'A'),
+        b'a'..=b'z' => Ok(digit - b'a' + 26),
+        b'0'..=b'9' => Ok(digit - b'0' + 52),
+        b'+' => Ok(62),
+        b'/' => Ok(63),
+        _ => Err(format!("invalid base64 character `{}`", char::from(digit))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_round_trip() {
+        let decoded = Encoding::Hex.decode("00ff10Ab").unwrap();
+        assert_eq!(decoded, [0x00, 0xff, 0x10, 0xab]);
+    }
+
+    #[test]
+    fn test_hex_rejects_bad_input() {
+        assert!(Encoding::Hex.decode("abc").is_err());
+        assert!(Encoding::Hex.decode("zz").is_err());
+    }
+
+    #[test]
+    fn test_base64_decoding() {
+        assert_eq!(Encoding::Base64.decode("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(Encoding::Base64.decode("aGk=").unwrap(), b"hi");
+        assert_eq!(Encoding::Base64.decode("aGV5").unwrap(), b"hey");
+        assert!(Encoding::Base64.decode("aGV5!").is_err());
+        assert!(Encoding::Base64.decode("a").is_err());
+    }
+}
@@ -5,7 +5,7 @@
 //! deterministic key generation (using seeded RNG).
 
 use rand::{RngCore, SeedableRng};
-use rand_chacha::ChaCha20Rng;
+use rand_chacha::{ChaCha8Rng, ChaCha12Rng, ChaCha20Rng};
 
 // Algorithm-specific constants
 #[cfg(feature = "aes-256-gcm")]
@@ -19,21 +19,41 @@ pub const KEY_SIZE: usize = 16;
 pub const NONCE_SIZE: usize = 12;
 
 #[cfg(all(
-    feature = "chacha20-poly1305",
+    feature = "aes-256-ccm",
     not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
 ))]
 pub const KEY_SIZE: usize = 32;
 #[cfg(all(
-    feature = "chacha20-poly1305",
+    feature = "aes-256-ccm",
     not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
 ))]
 pub const NONCE_SIZE: usize = 12;
 
+#[cfg(all(
+    feature = "chacha20-poly1305",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm"
+    ))
+))]
+pub const KEY_SIZE: usize = 32;
+#[cfg(all(
+    feature = "chacha20-poly1305",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm"
+    ))
+))]
+pub const NONCE_SIZE: usize = 12;
+
 #[cfg(all(
     feature = "xor",
     not(any(
         feature = "aes-256-gcm",
         feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
         feature = "chacha20-poly1305"
     ))
 ))]
@@ -43,6 +63,7 @@ pub const KEY_SIZE: usize = 32;
     not(any(
         feature = "aes-256-gcm",
         feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
         feature = "chacha20-poly1305"
     ))
 ))]
@@ -52,6 +73,7 @@ pub const NONCE_SIZE: usize = 12;
 #[cfg(not(any(
     feature = "aes-256-gcm",
     feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
     feature = "chacha20-poly1305",
     feature = "xor"
 )))]
@@ -59,32 +81,377 @@ pub const KEY_SIZE: usize = 32;
 #[cfg(not(any(
     feature = "aes-256-gcm",
     feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
     feature = "chacha20-poly1305",
     feature = "xor"
 )))]
 pub const NONCE_SIZE: usize = 12;
 
+/// Size of the authentication tag appended to the ciphertext.
+#[cfg(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+))]
+pub const TAG_SIZE: usize = 16;
+
+/// XOR produces no authentication tag.
+#[cfg(not(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+)))]
+pub const TAG_SIZE: usize = 0;
+
+/// Largest plaintext one AES-GCM encryption may cover (NIST SP 800-38D).
+/// Must stay in sync with `obfuse_core::MAX_PLAINTEXT_LEN`.
+#[cfg(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))]
+pub const MAX_PLAINTEXT_LEN: u64 = (1 << 36) - 32;
+
+/// Largest plaintext the 3-byte length field of AES-CCM with a 12-byte nonce
+/// can describe (NIST SP 800-38C).
+#[cfg(all(
+    feature = "aes-256-ccm",
+    not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
+))]
+pub const MAX_PLAINTEXT_LEN: u64 = (1 << 24) - 1;
+
+/// Largest plaintext one ChaCha20-Poly1305 encryption may cover (RFC 8439).
+#[cfg(all(
+    feature = "chacha20-poly1305",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm"
+    ))
+))]
+pub const MAX_PLAINTEXT_LEN: u64 = (1 << 38) - 64;
+
+/// XOR accepts plaintext of any length.
+#[cfg(not(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+)))]
+pub const MAX_PLAINTEXT_LEN: u64 = u64::MAX;
+
+// The sizes above are duplicated in obfuse-core; pin both copies to the
+// cipher crates so a desync fails the build instead of every decryption
+#[cfg(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+))]
+mod size_checks {
+    use super::{KEY_SIZE, NONCE_SIZE, TAG_SIZE};
+    use aead::generic_array::typenum::Unsigned;
+    use aead::{AeadCore, KeySizeUser};
+
+    #[cfg(feature = "aes-256-gcm")]
+    type Cipher = aes_gcm::Aes256Gcm;
+    #[cfg(all(feature = "aes-128-gcm", not(feature = "aes-256-gcm")))]
+    type Cipher = aes_gcm::Aes128Gcm;
+    #[cfg(all(
+        feature = "aes-256-ccm",
+        not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
+    ))]
+    type Cipher = ccm::Ccm<aes::Aes256, ccm::consts::U16, ccm::consts::U12>;
+    #[cfg(all(
+        feature = "chacha20-poly1305",
+        not(any(
+            feature = "aes-256-gcm",
+            feature = "aes-128-gcm",
+            feature = "aes-256-ccm"
+        ))
+    ))]
+    type Cipher = chacha20poly1305::ChaCha20Poly1305;
+
+    const _: () = assert!(KEY_SIZE == <Cipher as KeySizeUser>::KeySize::USIZE);
+    const _: () = assert!(NONCE_SIZE == <Cipher as AeadCore>::NonceSize::USIZE);
+    const _: () = assert!(TAG_SIZE == <Cipher as AeadCore>::TagSize::USIZE);
+}
+
+// XOR has no cipher crate; these must match obfuse-core's values
+#[cfg(not(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+)))]
+const _: () = assert!(KEY_SIZE == 32 && NONCE_SIZE == 12 && TAG_SIZE == 0);
+
+/// Maximum number of encryption layers accepted by `layers = N`.
+pub const MAX_LAYERS: u8 = 8;
+
+/// Key and nonce supplied verbatim by the caller instead of being generated.
+#[derive(Default)]
+pub struct Pinned {
+    pub key: Option<[u8; KEY_SIZE]>,
+    pub nonce: Option<[u8; NONCE_SIZE]>,
+}
+
+/// Deterministic RNG that expands a seed into the key and nonce.
+///
+/// The derivation is part of the stable output format: the seed string is
+/// mixed into 32 bytes by `create_seed_bytes`, passed to `SeedableRng::from_seed`,
+/// and the key is drawn first, then the nonce.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SeedRng {
+    /// `rand_chacha::ChaCha8Rng`.
+    ChaCha8,
+    /// `rand_chacha::ChaCha12Rng`.
+    ChaCha12,
+    /// `rand_chacha::ChaCha20Rng` (the default).
+    #[default]
+    ChaCha20,
+}
+
+impl SeedRng {
+    /// Names accepted by the `rng = "..."` option.
+    pub const NAMES: [&str; 3] = ["chacha8", "chacha12", "chacha20"];
+
+    /// Parses an `rng = "..."` option value.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "chacha8" => Some(Self::ChaCha8),
+            "chacha12" => Some(Self::ChaCha12),
+            "chacha20" => Some(Self::ChaCha20),
+            _ => None,
+        }
+    }
+
+    fn seeded(self, seed: [u8; 32]) -> Box<dyn RngCore> {
+        match self {
+            Self::ChaCha8 => Box::new(ChaCha8Rng::from_seed(seed)),
+            Self::ChaCha12 => Box::new(ChaCha12Rng::from_seed(seed)),
+            Self::ChaCha20 => Box::new(ChaCha20Rng::from_seed(seed)),
+        }
+    }
+}
+
+/// Function turning a seed string into the 32-byte RNG seed.
+///
+/// Pinned with `seed_kdf = "..."` so a crate upgrade that changes the
+/// default cannot silently change the keys of existing seeded builds. Each
+/// version's output is frozen once released.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SeedKdf {
+    /// The original byte-mixing function, `create_seed_bytes` (the default).
+    #[default]
+    V1,
+    /// SHA-256 over a versioned domain tag and the seed.
+    V2,
+}
+
+impl SeedKdf {
+    /// Names accepted by the `seed_kdf = "..."` option.
+    pub const NAMES: [&str; 2] = ["v1", "v2"];
+
+    /// Parses a `seed_kdf = "..."` option value.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "v1" => Some(Self::V1),
+            "v2" => Some(Self::V2),
+            _ => None,
+        }
+    }
+
+    fn derive(self, seed: &str) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+
+        match self {
+            Self::V1 => create_seed_bytes(seed),
+            Self::V2 => Sha256::new()
+                .chain_update(b"obfuse-seed-kdf-v2\0")
+                .chain_update(seed.as_bytes())
+                .finalize()
+                .into(),
+        }
+    }
+}
+
+/// A seed for deterministic key generation, the KDF that condenses it and
+/// the RNG that expands it.
+pub struct Seed {
+    pub value: String,
+    pub rng: SeedRng,
+    pub kdf: SeedKdf,
+}
+
 /// Encrypts plaintext at compile time.
 ///
 /// # Arguments
 /// * `plaintext` - The string bytes to encrypt
 /// * `seed` - Optional seed for deterministic key generation
+/// * `pinned` - Key and/or nonce to use instead of generated ones
 ///
 /// # Returns
 /// Tuple of (ciphertext, key, nonce)
 pub fn encrypt(
     plaintext: &[u8],
-    seed: Option<String>,
+    seed: Option<Seed>,
+    pinned: &Pinned,
 ) -> (Vec<u8>, [u8; KEY_SIZE], [u8; NONCE_SIZE]) {
-    let (key, nonce) = generate_key_nonce(seed);
+    let (key, nonce) = generate_key_nonce(seed, pinned);
     let ciphertext = encrypt_with_algorithm(plaintext, &key, &nonce);
     (ciphertext, key, nonce)
 }
 
-/// Generates key and nonce, either randomly or from seed.
-fn generate_key_nonce(seed: Option<String>) -> ([u8; KEY_SIZE], [u8; NONCE_SIZE]) {
-    seed.map_or_else(generate_random, |seed_str| {
-        generate_deterministic(&seed_str)
+/// Masks a key for embedding: `key XOR expand(nonce)`, stored in the byte
+/// order given by [`key_order`].
+///
+/// `obfuse-core` removes the mask before decrypting; the expansion must stay
+/// identical to `obfuse_core::mask::expand_nonce`.
+pub fn mask_key(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> [u8; KEY_SIZE] {
+    let masked = xor_mask(key, nonce);
+    let order = key_order(nonce);
+    std::array::from_fn(|i| masked[order[i]])
+}
+
+/// Returns a `SplitMix64` generator seeded from `nonce` under `domain`,
+/// through 64-bit FNV-1a; must stay identical to
+/// `obfuse_core::mask::nonce_rng`.
+fn nonce_rng(nonce: &[u8; NONCE_SIZE], domain: u64) -> impl FnMut() -> u64 {
+    let mut state = nonce
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64 ^ domain, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    move || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Returns the order in which key bytes are stored for `nonce`.
+///
+/// Fisher-Yates driven by [`nonce_rng`] under its own domain; must stay
+/// identical to `obfuse_core::mask::key_order`.
+fn key_order(nonce: &[u8; NONCE_SIZE]) -> [usize; KEY_SIZE] {
+    const DOMAIN: u64 = 0x6b65_795f_6f72_6465;
+
+    let mut next = nonce_rng(nonce, DOMAIN);
+
+    let mut order: [usize; KEY_SIZE] = std::array::from_fn(|i| i);
+    for i in (1..KEY_SIZE).rev() {
+        #[allow(clippy::cast_possible_truncation)]
+        let j = (next() as usize) % (i + 1);
+        order.swap(i, j);
+    }
+    order
+}
+
+/// XORs a key with the expanded nonce.
+fn xor_mask(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> [u8; KEY_SIZE] {
+    let mut next = nonce_rng(nonce, 0);
+
+    let mut masked = *key;
+    for chunk in masked.chunks_mut(8) {
+        for (byte, mask) in chunk.iter_mut().zip(next().to_le_bytes()) {
+            *byte ^= mask;
+        }
+    }
+    masked
+}
+
+/// Wraps ciphertext in the nonce-derived junk prefix and suffix stored by
+/// `ciphertext-padding` builds, each shorter than `MAX_PADDING` bytes.
+///
+/// [`nonce_rng`] picks both lengths, then the junk; must stay identical to
+/// `obfuse_core::padding::layout`.
+#[cfg(feature = "ciphertext-padding")]
+pub fn pad_ciphertext(ciphertext: &[u8], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    const DOMAIN: u64 = 0x7061_6464_696e_6721;
+    const MAX_PADDING: usize = 32;
+
+    let mut next = nonce_rng(nonce, DOMAIN);
+
+    #[allow(clippy::cast_possible_truncation)]
+    let (prefix, suffix) = (next() as usize % MAX_PADDING, next() as usize % MAX_PADDING);
+
+    let mut stored = Vec::with_capacity(prefix + ciphertext.len() + suffix);
+    #[allow(clippy::cast_possible_truncation)]
+    stored.extend((0..prefix).map(|_| next() as u8));
+    stored.extend_from_slice(ciphertext);
+    #[allow(clippy::cast_possible_truncation)]
+    stored.extend((0..suffix).map(|_| next() as u8));
+    stored
+}
+
+/// Shuffles ciphertext into the order stored by `permute-ciphertext` builds.
+///
+/// Fisher-Yates driven by [`nonce_rng`]; must stay identical to
+/// `obfuse_core::permute::permutation`.
+#[cfg(feature = "permute-ciphertext")]
+pub fn permute_ciphertext(ciphertext: &[u8], nonce: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    const DOMAIN: u64 = 0x7065_726d_7574_6521;
+
+    let mut next = nonce_rng(nonce, DOMAIN);
+
+    let mut indices: Vec<usize> = (0..ciphertext.len()).collect();
+    for i in (1..ciphertext.len()).rev() {
+        #[allow(clippy::cast_possible_truncation)]
+        let j = (next() as usize) % (i + 1);
+        indices.swap(i, j);
+    }
+    indices
+        .into_iter()
+        .map(|source| ciphertext[source])
+        .collect()
+}
+
+/// Serializes an encrypted layer as the plaintext of the next one.
+///
+/// The format is `key || nonce || ciphertext`, which core peels in reverse.
+pub fn layer_plaintext(
+    ciphertext: &[u8],
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+) -> Vec<u8> {
+    [key.as_slice(), nonce.as_slice(), ciphertext].concat()
+}
+
+/// Generates key and nonce, either randomly or from seed, keeping pinned values.
+pub fn generate_key_nonce(
+    seed: Option<Seed>,
+    pinned: &Pinned,
+) -> ([u8; KEY_SIZE], [u8; NONCE_SIZE]) {
+    let (key, nonce) = seed.map_or_else(generate_random, |seed| {
+        generate_deterministic(&seed.value, seed.rng, seed.kdf)
+    });
+    (pinned.key.unwrap_or(key), pinned.nonce.unwrap_or(nonce))
+}
+
+/// Environment variables that make unseeded expansions deterministic, in
+/// order of precedence.
+const DETERMINISTIC_ENV: [&str; 3] = [
+    "OBFUSE_CRATE_SEED",
+    "OBFUSE_DETERMINISTIC",
+    "SOURCE_DATE_EPOCH",
+];
+
+/// Returns the build-wide seed requested through the environment, if any.
+///
+/// Reproducible-build pipelines set `SOURCE_DATE_EPOCH`; `OBFUSE_DETERMINISTIC`
+/// opts in explicitly and takes precedence. `OBFUSE_CRATE_SEED` is meant to be
+/// set per crate from a build script (`cargo:rustc-env`) and wins over both.
+/// Empty values are ignored.
+pub fn env_seed() -> Option<String> {
+    env_seed_from(|name| std::env::var(name).ok())
+}
+
+fn env_seed_from(lookup: impl Fn(&str) -> Option<String>) -> Option<String> {
+    DETERMINISTIC_ENV.iter().find_map(|&name| {
+        lookup(name)
+            .filter(|value| !value.is_empty())
+            .map(|value| format!("{name}={value}"))
     })
 }
 
@@ -99,11 +466,56 @@ fn generate_random() -> ([u8; KEY_SIZE], [u8; NONCE_SIZE]) {
     (key, nonce)
 }
 
+/// Environment variable holding the build-host pepper for `host-pepper`.
+#[cfg(feature = "host-pepper")]
+const PEPPER_ENV: &str = "OBFUSE_PEPPER";
+
+/// Returns the pepper mixed into seeded keys, if the `host-pepper` feature is on.
+///
+/// # Panics
+///
+/// Panics (failing the expansion) if the feature is on but `OBFUSE_PEPPER`
+/// is unset or empty, since silently building unpeppered keys would defeat it.
+#[cfg(feature = "host-pepper")]
+fn pepper() -> Option<String> {
+    let pepper = std::env::var(PEPPER_ENV)
+        .ok()
+        .filter(|value| !value.is_empty());
+    assert!(
+        pepper.is_some(),
+        "the `host-pepper` feature requires `{PEPPER_ENV}` to be set at build time"
+    );
+    pepper
+}
+
+#[cfg(not(feature = "host-pepper"))]
+fn pepper() -> Option<String> {
+    None
+}
+
 /// Generates deterministic key and nonce from a seed string.
-fn generate_deterministic(seed: &str) -> ([u8; KEY_SIZE], [u8; NONCE_SIZE]) {
-    // Create a 32-byte seed for ChaCha20 from the string
-    let seed_bytes = create_seed_bytes(seed);
-    let mut rng = ChaCha20Rng::from_seed(seed_bytes);
+fn generate_deterministic(
+    seed: &str,
+    rng: SeedRng,
+    kdf: SeedKdf,
+) -> ([u8; KEY_SIZE], [u8; NONCE_SIZE]) {
+    generate_peppered(seed, rng, kdf, pepper().as_deref())
+}
+
+/// Generates deterministic key and nonce from a seed string and an optional
+/// pepper that is never part of the source.
+fn generate_peppered(
+    seed: &str,
+    rng: SeedRng,
+    kdf: SeedKdf,
+    pepper: Option<&str>,
+) -> ([u8; KEY_SIZE], [u8; NONCE_SIZE]) {
+    // Create a 32-byte seed for the RNG from the string
+    let seed_bytes = match pepper {
+        Some(pepper) => kdf.derive(&format!("pepper:{pepper}:{seed}")),
+        None => kdf.derive(seed),
+    };
+    let mut rng = rng.seeded(seed_bytes);
 
     let mut key = [0u8; KEY_SIZE];
     let mut nonce = [0u8; NONCE_SIZE];
@@ -114,6 +526,68 @@ fn generate_deterministic(seed: &str) -> ([u8; KEY_SIZE], [u8; NONCE_SIZE]) {
     (key, nonce)
 }
 
+/// Generates entropy for naming generated items.
+///
+/// Random per build by default. With a seed, the value is derived from the
+/// seed in a separate domain from the key, so names stay reproducible without
+/// revealing anything about the key material.
+pub fn symbol_entropy(seed: Option<&str>) -> u64 {
+    let mut bytes = [0u8; 8];
+    fill_entropy(&mut bytes, "symbol", seed);
+    u64::from_le_bytes(bytes)
+}
+
+/// Returns a permutation of `0..len` for the emission order of an
+/// `obfuse_const!` block (`shuffle-layout` feature).
+///
+/// Random per build by default; with a seed, derived from it in its own
+/// domain so seeded builds keep a reproducible layout.
+#[cfg(any(test, feature = "shuffle-layout"))]
+pub fn layout_order(len: usize, seed: Option<&str>) -> Vec<usize> {
+    use rand::seq::SliceRandom;
+
+    let mut rng_seed = [0u8; 32];
+    fill_entropy(&mut rng_seed, "layout", seed);
+    let mut order: Vec<usize> = (0..len).collect();
+    order.shuffle(&mut ChaCha20Rng::from_seed(rng_seed));
+    order
+}
+
+/// Generates the HMAC key for a ciphertext manifest.
+pub fn manifest_key(seed: Option<&str>) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    fill_entropy(&mut key, "manifest", seed);
+    key
+}
+
+/// Computes HMAC-SHA256 over length-prefixed ciphertexts.
+///
+/// Must match `obfuse_core::manifest_hmac`.
+pub fn manifest_hmac<'a>(
+    key: &[u8; 32],
+    ciphertexts: impl IntoIterator<Item = &'a [u8]>,
+) -> [u8; 32] {
+    use hmac::{Hmac, Mac};
+
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    for ciphertext in ciphertexts {
+        mac.update(&(ciphertext.len() as u64).to_le_bytes());
+        mac.update(ciphertext);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+/// Fills `bytes` randomly, or deterministically from `seed` within `domain`.
+fn fill_entropy(bytes: &mut [u8], domain: &str, seed: Option<&str>) {
+    match seed {
+        Some(seed) => {
+            let mut rng = ChaCha20Rng::from_seed(create_seed_bytes(&format!("{domain}:{seed}")));
+            rng.fill_bytes(bytes);
+        }
+        None => getrandom::fill(bytes).expect("Failed to generate random bytes"),
+    }
+}
+
 /// Creates a 32-byte seed from a string using simple hashing.
 fn create_seed_bytes(seed: &str) -> [u8; 32] {
     let mut result = [0u8; 32];
@@ -141,19 +615,115 @@ fn create_seed_bytes(seed: &str) -> [u8; 32] {
     result
 }
 
+/// Plaintext bytes sealed per chunk when `streaming` is enabled.
+/// Must stay in sync with `obfuse_core`'s `stream::CHUNK_SIZE`.
+#[cfg(feature = "streaming")]
+pub const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Bytes of the nonce used as the `aead::stream` nonce prefix; the rest of
+/// the nonce is replaced by the 32-bit chunk counter and last-chunk flag.
+#[cfg(feature = "streaming")]
+const STREAM_NONCE_PREFIX_SIZE: usize = 7;
+
+/// Seals `plaintext` with an AEAD, appending the authentication tag.
+///
+/// With `streaming`, the plaintext is instead split into
+/// [`STREAM_CHUNK_SIZE`] chunks sealed under `aead::stream`'s big-endian
+/// 32-bit counter, one tag per chunk, the final chunk marked as last.
+#[cfg(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+))]
+fn seal<A>(cipher: A, nonce: &[u8; NONCE_SIZE], plaintext: &[u8]) -> Vec<u8>
+where
+    A: aead::AeadInPlace + aead::KeyInit,
+    A::NonceSize: std::ops::Sub<aead::consts::U5>,
+    <A::NonceSize as std::ops::Sub<aead::consts::U5>>::Output: aead::generic_array::ArrayLength<u8>,
+{
+    use aead::generic_array::GenericArray;
+
+    #[cfg(not(feature = "streaming"))]
+    let ciphertext = {
+        use aead::Aead;
+
+        cipher
+            .encrypt(GenericArray::from_slice(nonce), plaintext)
+            .expect("Encryption failed")
+    };
+
+    #[cfg(feature = "streaming")]
+    let ciphertext = {
+        use aead::stream::EncryptorBE32;
+
+        let prefix = GenericArray::from_slice(&nonce[..STREAM_NONCE_PREFIX_SIZE]);
+        let mut encryptor = EncryptorBE32::from_aead(cipher, prefix);
+
+        let mut chunks = plaintext.chunks(STREAM_CHUNK_SIZE);
+        let last = chunks.next_back().unwrap_or_default();
+        let mut ciphertext = Vec::with_capacity(plaintext.len() + TAG_SIZE);
+        for chunk in chunks {
+            ciphertext.extend(encryptor.encrypt_next(chunk).expect("Encryption failed"));
+        }
+        ciphertext.extend(encryptor.encrypt_last(last).expect("Encryption failed"));
+        ciphertext
+    };
+
+    ciphertext
+}
+
 /// Encrypts plaintext using the selected algorithm.
-#[cfg(feature = "aes-256-gcm")]
+#[cfg(all(feature = "aes-256-gcm", not(feature = "multi-aes")))]
 fn encrypt_with_algorithm(
     plaintext: &[u8],
     key: &[u8; KEY_SIZE],
     nonce: &[u8; NONCE_SIZE],
 ) -> Vec<u8> {
-    use aes_gcm::{Aes256Gcm, KeyInit, Nonce, aead::Aead};
+    use aes_gcm::{Aes256Gcm, KeyInit};
 
     let cipher = Aes256Gcm::new_from_slice(key).expect("Invalid key size");
-    let nonce = Nonce::from_slice(nonce);
+    seal(cipher, nonce, plaintext)
+}
+
+/// Encrypts plaintext with AES-256-GCM, tagged for `multi-aes` dispatch.
+#[cfg(feature = "multi-aes")]
+fn encrypt_with_algorithm(
+    plaintext: &[u8],
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+) -> Vec<u8> {
+    use aes_gcm::{Aes256Gcm, KeyInit};
+
+    let cipher = Aes256Gcm::new_from_slice(key).expect("Invalid key size");
+    tag_key_size(32, seal(cipher, nonce, plaintext))
+}
+
+/// Encrypts plaintext with AES-128-GCM in a `multi-aes` build.
+///
+/// The key is stored in the shared 32-byte array with the upper 16 bytes zeroed.
+#[cfg(feature = "multi-aes")]
+pub fn encrypt_aes128(
+    plaintext: &[u8],
+    seed: Option<Seed>,
+    pinned: &Pinned,
+) -> (Vec<u8>, [u8; KEY_SIZE], [u8; NONCE_SIZE]) {
+    use aes_gcm::{Aes128Gcm, KeyInit};
+
+    let (mut key, nonce) = generate_key_nonce(seed, pinned);
+    key[16..].fill(0);
 
-    cipher.encrypt(nonce, plaintext).expect("Encryption failed")
+    let cipher = Aes128Gcm::new_from_slice(&key[..16]).expect("Invalid key size");
+    let ciphertext = seal(cipher, &nonce, plaintext);
+
+    (tag_key_size(16, ciphertext), key, nonce)
+}
+
+/// Prepends the key-size tag that `multi-aes` builds use to dispatch.
+#[cfg(feature = "multi-aes")]
+pub fn tag_key_size(key_len: usize, ciphertext: Vec<u8>) -> Vec<u8> {
+    let tag = u8::try_from(key_len).expect("key size fits in a byte");
+    std::iter::once(tag).chain(ciphertext).collect()
 }
 
 #[cfg(all(feature = "aes-128-gcm", not(feature = "aes-256-gcm")))]
@@ -162,29 +732,45 @@ fn encrypt_with_algorithm(
     key: &[u8; KEY_SIZE],
     nonce: &[u8; NONCE_SIZE],
 ) -> Vec<u8> {
-    use aes_gcm::{Aes128Gcm, KeyInit, Nonce, aead::Aead};
+    use aes_gcm::{Aes128Gcm, KeyInit};
 
     let cipher = Aes128Gcm::new_from_slice(key).expect("Invalid key size");
-    let nonce = Nonce::from_slice(nonce);
+    seal(cipher, nonce, plaintext)
+}
 
-    cipher.encrypt(nonce, plaintext).expect("Encryption failed")
+#[cfg(all(
+    feature = "aes-256-ccm",
+    not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
+))]
+fn encrypt_with_algorithm(
+    plaintext: &[u8],
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+) -> Vec<u8> {
+    use ccm::consts::{U12, U16};
+    use ccm::{Ccm, KeyInit};
+
+    let cipher = Ccm::<aes::Aes256, U16, U12>::new_from_slice(key).expect("Invalid key size");
+    seal(cipher, nonce, plaintext)
 }
 
 #[cfg(all(
     feature = "chacha20-poly1305",
-    not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm"
+    ))
 ))]
 fn encrypt_with_algorithm(
     plaintext: &[u8],
     key: &[u8; KEY_SIZE],
     nonce: &[u8; NONCE_SIZE],
 ) -> Vec<u8> {
-    use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce, aead::Aead};
+    use chacha20poly1305::{ChaCha20Poly1305, KeyInit};
 
     let cipher = ChaCha20Poly1305::new_from_slice(key).expect("Invalid key size");
-    let nonce = Nonce::from_slice(nonce);
-
-    cipher.encrypt(nonce, plaintext).expect("Encryption failed")
+    seal(cipher, nonce, plaintext)
 }
 
 #[cfg(all(
@@ -192,25 +778,56 @@ fn encrypt_with_algorithm(
     not(any(
         feature = "aes-256-gcm",
         feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
         feature = "chacha20-poly1305"
     ))
 ))]
 fn encrypt_with_algorithm(
     plaintext: &[u8],
     key: &[u8; KEY_SIZE],
-    _nonce: &[u8; NONCE_SIZE],
+    nonce: &[u8; NONCE_SIZE],
 ) -> Vec<u8> {
     plaintext
         .iter()
         .enumerate()
-        .map(|(i, &byte)| byte ^ key[i % KEY_SIZE])
+        .map(|(i, &byte)| transform(byte ^ key[i % KEY_SIZE], nonce))
         .collect()
 }
 
+/// Applies the per-string program encoded in the nonce.
+///
+/// Must be the inverse of `undo` in `obfuse_core::xor`. Also the encoder of
+/// `obfuse_inline!`, which is available with any algorithm.
+#[cfg(feature = "polymorphic")]
+pub fn transform(byte: u8, program: &[u8; NONCE_SIZE]) -> u8 {
+    program.chunks_exact(2).fold(byte, |b, op| match op[0] % 4 {
+        0 => b ^ op[1],
+        1 => b.wrapping_add(op[1]),
+        2 => b.rotate_left(u32::from(op[1] % 8)),
+        _ => b.wrapping_mul(op[1] | 1),
+    })
+}
+
+/// Plain XOR ignores the nonce.
+#[cfg(all(
+    feature = "xor",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
+        feature = "chacha20-poly1305"
+    )),
+    not(feature = "polymorphic")
+))]
+fn transform(byte: u8, _nonce: &[u8; NONCE_SIZE]) -> u8 {
+    byte
+}
+
 // Fallback when no feature is enabled
 #[cfg(not(any(
     feature = "aes-256-gcm",
     feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
     feature = "chacha20-poly1305",
     feature = "xor"
 )))]
@@ -226,10 +843,22 @@ fn encrypt_with_algorithm(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_nonce_rng_matches_core() {
+        // Same known answer as `obfuse_core::mask`'s test
+        let mut next = nonce_rng(&[7; NONCE_SIZE], 0x6b65_795f_6f72_6465);
+        assert_eq!(
+            [next(), next()],
+            [0xd9a8_8f92_7417_a6aa, 0xc3ea_8af6_ac3e_9238]
+        );
+    }
+
     #[test]
     fn test_deterministic_same_seed() {
-        let (key1, nonce1) = generate_deterministic("test_seed");
-        let (key2, nonce2) = generate_deterministic("test_seed");
+        let (key1, nonce1) =
+            generate_deterministic("test_seed", SeedRng::default(), SeedKdf::default());
+        let (key2, nonce2) =
+            generate_deterministic("test_seed", SeedRng::default(), SeedKdf::default());
 
         assert_eq!(key1, key2);
         assert_eq!(nonce1, nonce2);
@@ -237,12 +866,254 @@ mod tests {
 
     #[test]
     fn test_deterministic_different_seeds() {
-        let (key1, _) = generate_deterministic("seed_a");
-        let (key2, _) = generate_deterministic("seed_b");
+        let (key1, _) = generate_deterministic("seed_a", SeedRng::default(), SeedKdf::default());
+        let (key2, _) = generate_deterministic("seed_b", SeedRng::default(), SeedKdf::default());
 
         assert_ne!(key1, key2);
     }
 
+    #[test]
+    fn test_layout_order() {
+        let mut order = layout_order(16, None);
+        assert_ne!(order, layout_order(16, None));
+        order.sort_unstable();
+        assert_eq!(order, (0..16).collect::<Vec<_>>());
+
+        // Seeded builds keep their layout
+        assert_eq!(layout_order(16, Some("s")), layout_order(16, Some("s")));
+        assert_ne!(layout_order(16, Some("s")), layout_order(16, Some("t")));
+        assert!(layout_order(0, None).is_empty());
+    }
+
+    #[test]
+    fn test_pepper_changes_seeded_keys() {
+        let plain = generate_peppered("test_seed", SeedRng::default(), SeedKdf::default(), None);
+        let peppered = generate_peppered(
+            "test_seed",
+            SeedRng::default(),
+            SeedKdf::default(),
+            Some("host"),
+        );
+
+        // Same seed, so only the pepper can tell the two builds apart
+        assert_ne!(plain.0, peppered.0);
+        assert_ne!(plain.1, peppered.1);
+        assert_eq!(
+            peppered,
+            generate_peppered(
+                "test_seed",
+                SeedRng::default(),
+                SeedKdf::default(),
+                Some("host")
+            )
+        );
+        assert_ne!(
+            peppered,
+            generate_peppered(
+                "test_seed",
+                SeedRng::default(),
+                SeedKdf::default(),
+                Some("other")
+            )
+        );
+    }
+
+    #[test]
+    fn test_seed_rngs_reproducible_and_distinct() {
+        let outputs: Vec<_> = SeedRng::NAMES
+            .iter()
+            .map(|name| {
+                let rng = SeedRng::from_name(name).unwrap();
+                let first = generate_deterministic("rng_seed", rng, SeedKdf::default());
+                assert_eq!(
+                    first,
+                    generate_deterministic("rng_seed", rng, SeedKdf::default()),
+                    "{name}"
+                );
+                first
+            })
+            .collect();
+
+        assert_ne!(outputs[0], outputs[1]);
+        assert_ne!(outputs[1], outputs[2]);
+        assert_ne!(outputs[0], outputs[2]);
+    }
+
+    #[test]
+    fn test_default_seed_rng_is_chacha20() {
+        // Existing seeded builds must keep producing the same keys
+        let mut expected = ([0u8; KEY_SIZE], [0u8; NONCE_SIZE]);
+        let mut rng = ChaCha20Rng::from_seed(create_seed_bytes("test_seed"));
+        rng.fill_bytes(&mut expected.0);
+        rng.fill_bytes(&mut expected.1);
+
+        assert_eq!(
+            generate_peppered("test_seed", SeedRng::default(), SeedKdf::default(), None),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_seed_kdf_versions_frozen() {
+        // Released outputs; changing either breaks pinned golden builds
+        assert_eq!(
+            SeedKdf::V1.derive("test_seed"),
+            [
+                0xde, 0x2f, 0x7e, 0x14, 0xec, 0xd7, 0x80, 0x94, 0x90, 0xc5, 0x20, 0xe0, 0xae, 0x7c,
+                0x5c, 0x3f, 0x04, 0x14, 0xb6, 0x16, 0x71, 0x3e, 0x2c, 0x58, 0x87, 0x44, 0xc0, 0x82,
+                0x93, 0x94, 0x40, 0x0a,
+            ]
+        );
+        // SHA-256("obfuse-seed-kdf-v2\0test_seed")
+        assert_eq!(
+            SeedKdf::V2.derive("test_seed"),
+            [
+                0xa1, 0x4d, 0x0e, 0xb0, 0x79, 0xb2, 0xec, 0xbf, 0xf5, 0x96, 0x1f, 0xd8, 0x9f, 0x7a,
+                0xb4, 0x45, 0x36, 0x78, 0xba, 0x7c, 0x28, 0x5c, 0xe9, 0xf1, 0x14, 0x88, 0x21, 0xb0,
+                0x97, 0xd9, 0xaa, 0xa5,
+            ]
+        );
+
+        // v1 stays the default, so unpinned seeded builds keep their keys
+        assert_eq!(SeedKdf::default(), SeedKdf::V1);
+        assert_ne!(
+            generate_peppered("test_seed", SeedRng::default(), SeedKdf::V1, None),
+            generate_peppered("test_seed", SeedRng::default(), SeedKdf::V2, None)
+        );
+        assert_eq!(
+            SeedKdf::NAMES.map(SeedKdf::from_name),
+            [Some(SeedKdf::V1), Some(SeedKdf::V2)]
+        );
+        assert_eq!(SeedKdf::from_name("v3"), None);
+    }
+
+    #[test]
+    fn test_mask_key_hides_key() {
+        let (key, nonce) = generate_deterministic("mask", SeedRng::default(), SeedKdf::default());
+        let masked = mask_key(&key, &nonce);
+        assert_ne!(masked, key);
+
+        // A shuffle of the plain XOR mask, not the XOR mask itself
+        let xored = xor_mask(&key, &nonce);
+        assert_eq!(xor_mask(&xored, &nonce), key);
+        assert_ne!(masked, xored);
+        let (mut sorted_masked, mut sorted_xored) = (masked, xored);
+        sorted_masked.sort_unstable();
+        sorted_xored.sort_unstable();
+        assert_eq!(sorted_masked, sorted_xored);
+    }
+
+    #[test]
+    fn test_symbol_entropy() {
+        assert_eq!(symbol_entropy(Some("seed")), symbol_entropy(Some("seed")));
+        assert_ne!(symbol_entropy(None), symbol_entropy(None));
+    }
+
+    #[test]
+    fn test_env_seed_precedence() {
+        let env = |vars: &'static [(&str, &str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| (*value).to_owned())
+            }
+        };
+
+        assert_eq!(env_seed_from(env(&[])), None);
+        assert_eq!(env_seed_from(env(&[("SOURCE_DATE_EPOCH", "")])), None);
+        assert_eq!(
+            env_seed_from(env(&[("SOURCE_DATE_EPOCH", "1700000000")])).as_deref(),
+            Some("SOURCE_DATE_EPOCH=1700000000")
+        );
+        assert_eq!(
+            env_seed_from(env(&[
+                ("SOURCE_DATE_EPOCH", "1700000000"),
+                ("OBFUSE_DETERMINISTIC", "ci"),
+            ]))
+            .as_deref(),
+            Some("OBFUSE_DETERMINISTIC=ci")
+        );
+        assert_eq!(
+            env_seed_from(env(&[
+                ("OBFUSE_DETERMINISTIC", "ci"),
+                ("OBFUSE_CRATE_SEED", "my-crate"),
+            ]))
+            .as_deref(),
+            Some("OBFUSE_CRATE_SEED=my-crate")
+        );
+    }
+
+    #[cfg(all(
+        feature = "polymorphic",
+        not(any(
+            feature = "aes-256-gcm",
+            feature = "aes-128-gcm",
+            feature = "aes-256-ccm",
+            feature = "chacha20-poly1305"
+        ))
+    ))]
+    #[test]
+    fn test_polymorphic_programs_differ() {
+        // Same plaintext and key; only the generated program varies
+        let pinned = Pinned {
+            key: Some([0x42; KEY_SIZE]),
+            nonce: None,
+        };
+        let (first, _, first_program) = encrypt(b"same plaintext", None, &pinned);
+        let (second, _, second_program) = encrypt(b"same plaintext", None, &pinned);
+
+        assert_ne!(first_program, second_program);
+        assert_ne!(first, second);
+    }
+
+    #[cfg(feature = "streaming")]
+    #[test]
+    fn test_streaming_adds_one_tag_per_chunk() {
+        let overhead = usize::from(cfg!(feature = "multi-aes"));
+        for (len, chunks) in [
+            (0, 1),
+            (STREAM_CHUNK_SIZE, 1),
+            (STREAM_CHUNK_SIZE + 1, 2),
+            (3 * STREAM_CHUNK_SIZE + 5, 4),
+        ] {
+            let (ciphertext, _, _) = encrypt(&vec![b'x'; len], None, &Pinned::default());
+            assert_eq!(
+                ciphertext.len(),
+                overhead + len + chunks * TAG_SIZE,
+                "len {len}"
+            );
+        }
+    }
+
+    #[cfg(all(
+        feature = "streaming",
+        feature = "aes-256-gcm",
+        not(feature = "multi-aes")
+    ))]
+    #[test]
+    fn test_streaming_round_trips_through_aead_stream() {
+        use aead::generic_array::GenericArray;
+        use aead::stream::DecryptorBE32;
+        use aes_gcm::{Aes256Gcm, KeyInit};
+
+        #[allow(clippy::cast_possible_truncation)]
+        let plaintext: Vec<u8> = (0..2 * STREAM_CHUNK_SIZE + 7).map(|i| i as u8).collect();
+        let (ciphertext, key, nonce) = encrypt(&plaintext, None, &Pinned::default());
+
+        let cipher = Aes256Gcm::new_from_slice(&key).unwrap();
+        let prefix = GenericArray::from_slice(&nonce[..STREAM_NONCE_PREFIX_SIZE]);
+        let mut decryptor = DecryptorBE32::from_aead(cipher, prefix);
+        let mut chunks = ciphertext.chunks(STREAM_CHUNK_SIZE + TAG_SIZE);
+        let last = chunks.next_back().unwrap();
+
+        let mut decrypted = Vec::new();
+        for chunk in chunks {
+            decrypted.extend(decryptor.decrypt_next(chunk).unwrap());
+        }
+        decrypted.extend(decryptor.decrypt_last(last).unwrap());
+        assert_eq!(decrypted, plaintext);
+    }
+
     #[test]
     fn test_random_is_different() {
         let (key1, _) = generate_random();
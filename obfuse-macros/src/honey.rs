@@ -0,0 +1,68 @@
+//! Input parsing for the `obfuse_honey!` macro.
+
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use syn::{Path, parse::Parse, parse::ParseStream};
+
+use crate::ObfuseInput;
+
+/// Input to `obfuse_honey!`: the usual `obfuse!` input plus
+/// `on_access = path::to::fn` among its options.
+pub struct HoneyInput {
+    pub value: ObfuseInput,
+    pub on_access: Path,
+}
+
+impl Parse for HoneyInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let tokens: Vec<TokenTree> = input.parse::<TokenStream2>()?.into_iter().collect();
+
+        // `on_access` is not an `obfuse!` option, so cut `, on_access = <path>`
+        // out of the top-level tokens and parse the rest as usual
+        let Some(start) = tokens.windows(3).position(|window| {
+            matches!(window, [TokenTree::Punct(comma), TokenTree::Ident(ident), TokenTree::Punct(eq)]
+                if comma.as_char() == ',' && ident == "on_access" && eq.as_char() == '=')
+        }) else {
+            return Err(input.error("`obfuse_honey!` requires `on_access = path::to::fn`"));
+        };
+
+        let path_start = start + 3;
+        let path_end = tokens[path_start..]
+            .iter()
+            .position(|token| matches!(token, TokenTree::Punct(p) if p.as_char() == ','))
+            .map_or(tokens.len(), |offset| path_start + offset);
+
+        let on_access = syn::parse2(tokens[path_start..path_end].iter().cloned().collect())?;
+        let value = syn::parse2(
+            tokens[..start]
+                .iter()
+                .chain(&tokens[path_end..])
+                .cloned()
+                .collect(),
+        )?;
+
+        Ok(Self { value, on_access })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_access_anywhere_in_options() {
+        let last: HoneyInput = syn::parse_quote!("fake", seed = "s", on_access = alerts::fire);
+        assert_eq!(last.on_access.segments.len(), 2);
+        assert_eq!(last.value.seed().as_deref(), Some("s"));
+
+        let first: HoneyInput = syn::parse_quote!("fake", on_access = fire, seed = "s");
+        assert!(first.on_access.is_ident("fire"));
+        assert_eq!(first.value.seed().as_deref(), Some("s"));
+    }
+
+    #[test]
+    fn test_on_access_required() {
+        let result = syn::parse_str::<HoneyInput>(r#""fake", seed = "s""#);
+        let error = result.err().expect("missing callback");
+        assert!(error.to_string().contains("on_access"));
+    }
+}
@@ -0,0 +1,126 @@
+//! Expansion of the `obfuse_inline!` macro.
+//!
+//! The literal is XORed with a key, then run through a program of
+//! `(opcode, operand)` pairs taken from the nonce, using the instruction set
+//! of the `polymorphic` engine (see `obfuse_core::xor`). Instead of handing
+//! the program to the shared `decrypt` routine, the inverse instructions are
+//! unrolled into the expansion, so every call site carries its own decoder.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse::Parse, parse::ParseStream};
+
+use crate::encrypt::{KEY_SIZE, NONCE_SIZE, transform};
+use crate::input::{Literal, ObfuseInput, Payload};
+
+/// Input to `obfuse_inline!`: a plain string literal with the `obfuse!`
+/// key-generation options (`seed`, `rng`, `seed_kdf`, `key`, `nonce`).
+pub struct InlineInput {
+    plaintext: String,
+    value: ObfuseInput,
+}
+
+impl Parse for InlineInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let value: ObfuseInput = input.parse()?;
+
+        let plaintext = match &value.payload {
+            Payload::Plaintext {
+                literal: Literal::Str(literal),
+                ..
+            } => literal.value(),
+            _ => {
+                return Err(input.error("`obfuse_inline!` requires a string literal"));
+            }
+        };
+        if value.layers() != 1 || value.options.section.is_some() {
+            return Err(input.error("`obfuse_inline!` does not support `layers` or `section`"));
+        }
+
+        Ok(Self { plaintext, value })
+    }
+}
+
+impl InlineInput {
+    /// Generates a block that decodes the literal into a `Zeroizing<String>`.
+    pub fn expand(&self) -> TokenStream2 {
+        let (key, program) = self.value.key_nonce();
+        let encoded = encode(self.plaintext.as_bytes(), &key, &program);
+
+        let len = encoded.len();
+        let undo = program
+            .chunks_exact(2)
+            .rev()
+            .map(|op| undo_tokens(op[0], op[1]));
+
+        quote! {
+            {
+                let encoded: [u8; #len] = ::std::hint::black_box([#(#encoded),*]);
+                let key: [u8; #KEY_SIZE] = ::std::hint::black_box([#(#key),*]);
+                let mut bytes = encoded.to_vec();
+                for block in bytes.chunks_mut(#KEY_SIZE) {
+                    for (byte, k) in block.iter_mut().zip(&key) {
+                        let mut b = *byte;
+                        #(#undo)*
+                        *byte = b ^ k;
+                    }
+                }
+                ::obfuse::__private::Zeroizing::new(
+                    ::std::string::String::from_utf8(bytes)
+                        .expect("`obfuse_inline!` decodes to its UTF-8 literal"),
+                )
+            }
+        }
+    }
+}
+
+/// XORs `plaintext` with `key`, then runs each byte through `program`, as
+/// the `polymorphic` engine encrypts.
+fn encode(plaintext: &[u8], key: &[u8; KEY_SIZE], program: &[u8; NONCE_SIZE]) -> Vec<u8> {
+    plaintext
+        .chunks(KEY_SIZE)
+        .flat_map(|block| block.iter().zip(key))
+        .map(|(&byte, &k)| transform(byte ^ k, program))
+        .collect()
+}
+
+/// Generates the statement undoing one instruction on `b`.
+fn undo_tokens(opcode: u8, operand: u8) -> TokenStream2 {
+    match opcode % 4 {
+        0 => quote! { b ^= #operand; },
+        1 => quote! { b = b.wrapping_sub(#operand); },
+        2 => {
+            let shift = u32::from(operand % 8);
+            quote! { b = b.rotate_right(#shift); }
+        }
+        _ => {
+            // Resolved at compile time by the decoder's own inverse
+            let multiplier = operand | 1;
+            quote! {
+                b = b.wrapping_mul(const { ::obfuse::__private::mul_inverse(#multiplier) });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_sites_share_a_decoder() {
+        let first: InlineInput = syn::parse_quote!("x", seed = "s");
+        let second: InlineInput = syn::parse_quote!("x", seed = "s");
+        assert_eq!(first.expand().to_string(), second.expand().to_string());
+
+        let unseeded: InlineInput = syn::parse_quote!("x");
+        assert_ne!(unseeded.expand().to_string(), first.expand().to_string());
+    }
+
+    #[test]
+    fn test_rejects_unsupported_input() {
+        for input in [r#"c"x""#, r#""x", layers = 2"#, r#""x", section"#] {
+            assert!(syn::parse_str::<InlineInput>(input).is_err(), "{input}");
+        }
+    }
+}
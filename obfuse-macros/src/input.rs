@@ -0,0 +1,842 @@
+//! Parsing of the shared `obfuse!` input syntax.
+//!
+//! `obfuse!`, `obfuse_static!` and each `obfuse_const!` declaration accept the
+//! same input: a payload (plaintext literal or pre-encrypted data) followed by
+//! comma-separated options.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{LitCStr, LitStr, Token, parse::Parse, parse::ParseStream};
+
+use crate::decode::Encoding;
+#[cfg(feature = "polymorphic")]
+use crate::encrypt::generate_key_nonce;
+#[cfg(feature = "ciphertext-padding")]
+use crate::encrypt::pad_ciphertext;
+#[cfg(feature = "permute-ciphertext")]
+use crate::encrypt::permute_ciphertext;
+use crate::encrypt::{
+    KEY_SIZE, MAX_LAYERS, MAX_PLAINTEXT_LEN, NONCE_SIZE, Pinned, Seed, SeedKdf, SeedRng, TAG_SIZE,
+    encrypt, env_seed, layer_plaintext,
+};
+#[cfg(feature = "multi-aes")]
+use crate::encrypt::{encrypt_aes128, tag_key_size};
+
+/// Input to the `obfuse!` macro.
+///
+/// Supports these forms:
+/// - `obfuse!("string")` - random key each compile
+/// - `obfuse!(c"string")` - C string literal, embedded with its NUL terminator
+/// - `obfuse!("string", seed = "seed_value")` - deterministic key from seed
+/// - `obfuse!("string", seed = "...", rng = "chacha8")` - seed expansion RNG
+/// - `obfuse!("string", seed = "...", seed_kdf = "v2")` - pinned seed KDF version
+/// - `obfuse!("string", aes = 128)` - per-string AES key size (`multi-aes` only)
+/// - `obfuse!("string", key = "...", nonce = "...")` - pinned hex key and/or
+///   nonce, used verbatim instead of being generated
+/// - `obfuse!("string", layers = 3)` - re-encrypt the ciphertext under fresh
+///   keys, up to [`MAX_LAYERS`] times in total
+/// - `obfuse!(hex = "...", key = "...", nonce = "...")` - pre-encrypted payload
+///   (`base64 = "..."` is accepted in place of `hex`)
+///
+/// Every form also accepts the options in [`Options`].
+pub struct ObfuseInput {
+    pub payload: Payload,
+    pub options: Options,
+}
+
+/// The data to embed.
+pub enum Payload {
+    /// A plaintext literal encrypted during expansion.
+    Plaintext {
+        literal: Literal,
+        seed: Option<LitStr>,
+        /// RNG expanding the seed (explicit or from the environment).
+        rng: SeedRng,
+        /// KDF condensing the seed (explicit or from the environment).
+        kdf: SeedKdf,
+        /// Use AES-128-GCM instead of AES-256-GCM (`multi-aes` only).
+        #[cfg_attr(not(feature = "multi-aes"), allow(dead_code))]
+        aes128: bool,
+        /// Key and/or nonce of the innermost layer.
+        pinned: Pinned,
+        /// Number of encryption layers, `1..=MAX_LAYERS`.
+        layers: u8,
+    },
+
+    /// Ciphertext, key and nonce produced out-of-band.
+    Encrypted {
+        ciphertext: Vec<u8>,
+        key: [u8; KEY_SIZE],
+        nonce: [u8; NONCE_SIZE],
+    },
+}
+
+/// A plaintext literal.
+pub enum Literal {
+    /// `"..."` or `r#"..."#`.
+    Str(LitStr),
+    /// `c"..."` or `cr#"..."#`, whose NUL terminator is part of the plaintext
+    /// so the result works with `ObfuseStr::as_c_str`.
+    CStr(LitCStr),
+    /// The elements of `obfuse_array!`, each prefixed with its length as a
+    /// little-endian `u32`; must match `obfuse_core::ObfuseStrArray`.
+    Array(Vec<LitStr>),
+}
+
+impl Literal {
+    /// Returns the plaintext bytes to encrypt.
+    fn bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Str(literal) => literal.value().into_bytes(),
+            Self::CStr(literal) => literal.value().into_bytes_with_nul(),
+            Self::Array(elements) => elements
+                .iter()
+                .flat_map(|element| {
+                    let value = element.value().into_bytes();
+                    let len = u32::try_from(value.len()).expect("checked when parsing");
+                    len.to_le_bytes().into_iter().chain(value)
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the text mixed into derived seeds.
+    ///
+    /// C strings may hold arbitrary bytes, so they are rendered as hex.
+    fn seed_text(&self) -> String {
+        match self {
+            Self::Str(literal) => literal.value(),
+            Self::CStr(literal) => format!("{:02x?}", literal.value().as_bytes_with_nul()),
+            Self::Array(elements) => format!(
+                "{:?}",
+                elements.iter().map(LitStr::value).collect::<Vec<_>>()
+            ),
+        }
+    }
+
+    /// Returns `true` if there is nothing before the (C string) terminator.
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Str(literal) => literal.value().is_empty(),
+            Self::CStr(literal) => literal.value().is_empty(),
+            Self::Array(elements) => elements.is_empty(),
+        }
+    }
+
+    fn span(&self) -> proc_macro2::Span {
+        match self {
+            Self::Str(literal) => literal.span(),
+            Self::CStr(literal) => literal.span(),
+            Self::Array(elements) => elements[0].span(),
+        }
+    }
+}
+
+impl Parse for Literal {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(LitCStr) {
+            input.parse().map(Self::CStr)
+        } else {
+            input.parse().map(Self::Str)
+        }
+    }
+}
+
+/// Options shared by every input form.
+#[derive(Default)]
+pub struct Options {
+    /// Linker section for the backing ciphertext static.
+    pub section: Option<LinkSection>,
+}
+
+/// Where to place the backing ciphertext static.
+pub enum LinkSection {
+    /// `section` - a platform-appropriate default name.
+    Default,
+    /// `section = "name"` - a user-supplied name, used verbatim.
+    Custom(LitStr),
+}
+
+impl Options {
+    /// Parses a shared option if `ident` names one.
+    ///
+    /// Returns `Ok(false)` (consuming nothing) for unknown options so the
+    /// caller can report them in context.
+    fn parse_option(&mut self, ident: &syn::Ident, input: ParseStream) -> syn::Result<bool> {
+        if ident != "section" {
+            return Ok(false);
+        }
+
+        self.section = Some(if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            LinkSection::Custom(input.parse()?)
+        } else {
+            LinkSection::Default
+        });
+        Ok(true)
+    }
+}
+
+impl Parse for ObfuseInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = Options::default();
+
+        let payload = if input.peek(LitStr) || input.peek(LitCStr) {
+            parse_plaintext(input.parse()?, input, &mut options)?
+        } else {
+            parse_encrypted(input, &mut options)?
+        };
+
+        Ok(Self { payload, options })
+    }
+}
+
+impl ObfuseInput {
+    /// Parses the options following an already parsed `literal`, as for a
+    /// plaintext `obfuse!` input.
+    pub fn parse_with_literal(literal: Literal, input: ParseStream) -> syn::Result<Self> {
+        let mut options = Options::default();
+        let payload = parse_plaintext(literal, input, &mut options)?;
+        Ok(Self { payload, options })
+    }
+}
+
+/// Options accepted after a plaintext literal, for error messages.
+const PLAINTEXT_OPTIONS: [&str; 8] = [
+    "seed", "rng", "seed_kdf", "aes", "key", "nonce", "layers", "section",
+];
+
+/// Parses `[, seed = "..."] [, rng = "..."] [, seed_kdf = "..."] [, aes = 128|256] [, key = "..."] [, nonce = "..."] [, layers = N]` after `literal`.
+fn parse_plaintext(
+    literal: Literal,
+    input: ParseStream,
+    options: &mut Options,
+) -> syn::Result<Payload> {
+    let mut seed = None;
+    let mut rng = SeedRng::default();
+    let mut kdf = SeedKdf::default();
+    let mut aes128 = false;
+    let mut key: Option<LitStr> = None;
+    let mut nonce: Option<LitStr> = None;
+    let mut layers = 1;
+
+    while input.peek(Token![,]) {
+        input.parse::<Token![,]>()?;
+        if !input.peek(syn::Ident) {
+            break;
+        }
+
+        // Parse `seed = "value"`, `rng = "name"`, `seed_kdf = "version"`, `aes = 128|256`, `key`/`nonce`,
+        // `layers = N` or a shared option
+        let ident: syn::Ident = input.parse()?;
+        if options.parse_option(&ident, input)? {
+            continue;
+        }
+        input.parse::<Token![=]>()?;
+
+        if ident == "seed" {
+            seed = Some(input.parse::<LitStr>()?);
+        } else if ident == "rng" {
+            let name: LitStr = input.parse()?;
+            rng = SeedRng::from_name(&name.value()).ok_or_else(|| {
+                syn::Error::new(
+                    name.span(),
+                    format!(
+                        "expected one of {}, found `{}`",
+                        SeedRng::NAMES.map(|n| format!("`{n}`")).join(", "),
+                        name.value()
+                    ),
+                )
+            })?;
+        } else if ident == "seed_kdf" {
+            let version: LitStr = input.parse()?;
+            kdf = SeedKdf::from_name(&version.value()).ok_or_else(|| {
+                syn::Error::new(
+                    version.span(),
+                    format!(
+                        "expected one of {}, found `{}`",
+                        SeedKdf::NAMES.map(|n| format!("`{n}`")).join(", "),
+                        version.value()
+                    ),
+                )
+            })?;
+        } else if ident == "aes" {
+            aes128 = parse_aes_key_size(&ident, input)?;
+        } else if ident == "layers" {
+            layers = parse_layers(input)?;
+        } else if ident == "key" || ident == "nonce" {
+            let slot = if ident == "key" { &mut key } else { &mut nonce };
+            if slot.is_some() {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("duplicate `{ident}` argument"),
+                ));
+            }
+            *slot = Some(input.parse()?);
+        } else {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "expected one of {}, found `{ident}`",
+                    PLAINTEXT_OPTIONS.map(|n| format!("`{n}`")).join(", "),
+                ),
+            ));
+        }
+    }
+
+    let pinned = Pinned {
+        key: key.map(|key| decode_pinned_key(&key, aes128)).transpose()?,
+        nonce: nonce
+            .map(|nonce| decode_fixed(Encoding::Hex, &nonce, "nonce"))
+            .transpose()?,
+    };
+
+    check_size_limit(literal.bytes().len(), layers, literal.span())?;
+
+    Ok(Payload::Plaintext {
+        literal,
+        seed,
+        rng,
+        kdf,
+        aes128,
+        pinned,
+        layers,
+    })
+}
+
+/// Rejects plaintext too long to encrypt safely under one key and nonce.
+///
+/// Every extra layer wraps the previous one in its key, nonce and tag, so the
+/// outermost layer is the one that has to fit.
+fn check_size_limit(len: usize, layers: u8, span: proc_macro2::Span) -> syn::Result<()> {
+    let overhead = (KEY_SIZE + NONCE_SIZE + TAG_SIZE) * usize::from(layers - 1);
+    let outer = u64::try_from(len.saturating_add(overhead)).unwrap_or(u64::MAX);
+
+    // Always false for XOR, which has no limit
+    #[allow(clippy::absurd_extreme_comparisons)]
+    if outer > MAX_PLAINTEXT_LEN {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "{len}-byte plaintext exceeds the {MAX_PLAINTEXT_LEN}-byte limit for one \
+                 encryption with the selected algorithm"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Parses the value of `layers = N`, which must be in `1..=MAX_LAYERS`.
+fn parse_layers(input: ParseStream) -> syn::Result<u8> {
+    let count: syn::LitInt = input.parse()?;
+
+    match count.base10_parse::<u8>() {
+        Ok(layers @ 1..=MAX_LAYERS) => Ok(layers),
+        _ => Err(syn::Error::new(
+            count.span(),
+            format!("expected a layer count from 1 to {MAX_LAYERS}, found `{count}`"),
+        )),
+    }
+}
+
+/// Decodes a pinned hex key, padding an AES-128 key into the shared array.
+fn decode_pinned_key(literal: &LitStr, aes128: bool) -> syn::Result<[u8; KEY_SIZE]> {
+    let len = if aes128 { 16 } else { KEY_SIZE };
+    let bytes = decode_literal(Encoding::Hex, literal)?;
+    if bytes.len() != len {
+        return Err(syn::Error::new(
+            literal.span(),
+            format!("key must be {len} bytes, found {}", bytes.len()),
+        ));
+    }
+
+    let mut key = [0u8; KEY_SIZE];
+    key[..len].copy_from_slice(&bytes);
+    Ok(key)
+}
+
+/// Parses the value of `aes = 128|256`, returning `true` for AES-128.
+fn parse_aes_key_size(ident: &syn::Ident, input: ParseStream) -> syn::Result<bool> {
+    let bits: syn::LitInt = input.parse()?;
+
+    if cfg!(not(feature = "multi-aes")) {
+        return Err(syn::Error::new(
+            ident.span(),
+            "the `aes` option requires the `multi-aes` feature",
+        ));
+    }
+
+    match bits.base10_parse::<u16>()? {
+        128 => Ok(true),
+        256 => Ok(false),
+        other => Err(syn::Error::new(
+            bits.span(),
+            format!("expected `128` or `256`, found `{other}`"),
+        )),
+    }
+}
+
+/// Parses `hex|base64 = "...", key = "...", nonce = "..."`.
+///
+/// The key and nonce use the same encoding as the payload.
+fn parse_encrypted(input: ParseStream, options: &mut Options) -> syn::Result<Payload> {
+    let format: syn::Ident = input.parse()?;
+    let encoding = Encoding::from_name(&format.to_string()).ok_or_else(|| {
+        syn::Error::new(
+            format.span(),
+            format!("expected a string literal, `hex` or `base64`, found `{format}`"),
+        )
+    })?;
+    input.parse::<Token![=]>()?;
+    let payload: LitStr = input.parse()?;
+
+    let mut key: Option<LitStr> = None;
+    let mut nonce: Option<LitStr> = None;
+
+    while input.peek(Token![,]) {
+        input.parse::<Token![,]>()?;
+        if !input.peek(syn::Ident) {
+            break;
+        }
+
+        let ident: syn::Ident = input.parse()?;
+        if options.parse_option(&ident, input)? {
+            continue;
+        }
+
+        let slot = if ident == "key" {
+            &mut key
+        } else if ident == "nonce" {
+            &mut nonce
+        } else {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!("expected `key`, `nonce` or `section`, found `{ident}`"),
+            ));
+        };
+        if slot.is_some() {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!("duplicate `{ident}` argument"),
+            ));
+        }
+
+        input.parse::<Token![=]>()?;
+        *slot = Some(input.parse()?);
+    }
+
+    let missing = |name: &str| {
+        syn::Error::new(
+            format.span(),
+            format!("pre-encrypted input requires a `{name} = \"...\"` argument"),
+        )
+    };
+    let key = key.ok_or_else(|| missing("key"))?;
+    let nonce = nonce.ok_or_else(|| missing("nonce"))?;
+
+    let ciphertext = decode_literal(encoding, &payload)?;
+    // Always false for XOR, which has no tag
+    #[allow(clippy::absurd_extreme_comparisons)]
+    if ciphertext.len() < TAG_SIZE {
+        return Err(syn::Error::new(
+            payload.span(),
+            format!(
+                "ciphertext is {} bytes, shorter than the {TAG_SIZE}-byte authentication tag",
+                ciphertext.len()
+            ),
+        ));
+    }
+
+    check_size_limit(ciphertext.len() - TAG_SIZE, 1, payload.span())?;
+
+    #[cfg(not(feature = "multi-aes"))]
+    let (ciphertext, key) = (ciphertext, decode_fixed(encoding, &key, "key")?);
+
+    // The key length selects AES-128 or AES-256; tag the ciphertext to match
+    #[cfg(feature = "multi-aes")]
+    let (ciphertext, key) = {
+        let bytes = decode_literal(encoding, &key)?;
+        let mut padded = [0u8; KEY_SIZE];
+        match bytes.len() {
+            16 | 32 => padded[..bytes.len()].copy_from_slice(&bytes),
+            len => {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("key must be 16 or 32 bytes, found {len}"),
+                ));
+            }
+        }
+        (tag_key_size(bytes.len(), ciphertext), padded)
+    };
+
+    Ok(Payload::Encrypted {
+        ciphertext,
+        key,
+        nonce: decode_fixed(encoding, &nonce, "nonce")?,
+    })
+}
+
+/// Decodes a literal, reporting decoding errors at the literal's span.
+fn decode_literal(encoding: Encoding, literal: &LitStr) -> syn::Result<Vec<u8>> {
+    encoding
+        .decode(&literal.value())
+        .map_err(|e| syn::Error::new(literal.span(), e))
+}
+
+/// Decodes a literal that must be exactly `N` bytes long.
+fn decode_fixed<const N: usize>(
+    encoding: Encoding,
+    literal: &LitStr,
+    name: &str,
+) -> syn::Result<[u8; N]> {
+    let bytes = decode_literal(encoding, literal)?;
+    <[u8; N]>::try_from(bytes.as_slice()).map_err(|_| {
+        syn::Error::new(
+            literal.span(),
+            format!("{name} must be {N} bytes, found {}", bytes.len()),
+        )
+    })
+}
+
+/// Encrypts one layer with the algorithm the payload selects.
+#[cfg_attr(not(feature = "multi-aes"), allow(unused_variables))]
+fn encrypt_layer(plaintext: &[u8], seed: Option<Seed>, pinned: &Pinned, aes128: bool) -> Encrypted {
+    #[cfg(feature = "multi-aes")]
+    if aes128 {
+        return encrypt_aes128(plaintext, seed, pinned);
+    }
+
+    encrypt(plaintext, seed, pinned)
+}
+
+/// Derives the seed for a layer; the innermost layer uses the seed as-is.
+fn layer_seed(seed: Option<&Seed>, layer: u8) -> Option<Seed> {
+    seed.map(|seed| Seed {
+        value: if layer == 1 {
+            seed.value.clone()
+        } else {
+            format!("{}:layer{layer}", seed.value)
+        },
+        rng: seed.rng,
+        kdf: seed.kdf,
+    })
+}
+
+/// Seed prefix for the fixed keys used in `cfg(fuzzing)` builds.
+const FUZZING_SEED: &str = "obfuse-fuzzing";
+
+/// (ciphertext, key, nonce) produced for one input.
+pub type Encrypted = (Vec<u8>, [u8; KEY_SIZE], [u8; NONCE_SIZE]);
+
+impl ObfuseInput {
+    /// Returns the (ciphertext, key, nonce) to embed, encrypting if needed.
+    pub fn encrypt(&self) -> Encrypted {
+        self.encrypt_seeded(self.seed())
+    }
+
+    /// Returns the innermost key and nonce without encrypting anything, for
+    /// expansions that embed their own decoding (`obfuse_inline!`).
+    #[cfg(feature = "polymorphic")]
+    pub fn key_nonce(&self) -> ([u8; KEY_SIZE], [u8; NONCE_SIZE]) {
+        match &self.payload {
+            Payload::Plaintext { pinned, .. } => {
+                generate_key_nonce(self.full_seed(self.seed()), pinned)
+            }
+            Payload::Encrypted { key, nonce, .. } => (*key, *nonce),
+        }
+    }
+
+    /// Pairs a seed value with the payload's RNG and KDF choice.
+    fn full_seed(&self, seed: Option<String>) -> Option<Seed> {
+        seed.map(|value| Seed {
+            value,
+            rng: match &self.payload {
+                Payload::Plaintext { rng, .. } => *rng,
+                Payload::Encrypted { .. } => SeedRng::default(),
+            },
+            kdf: match &self.payload {
+                Payload::Plaintext { kdf, .. } => *kdf,
+                Payload::Encrypted { .. } => SeedKdf::default(),
+            },
+        })
+    }
+
+    fn encrypt_seeded(&self, seed: Option<String>) -> Encrypted {
+        let seed = self.full_seed(seed);
+
+        let encrypted = match &self.payload {
+            Payload::Plaintext {
+                literal,
+                aes128,
+                pinned,
+                layers,
+                ..
+            } => {
+                // Encrypt at compile time
+                let mut encrypted = encrypt_layer(
+                    &literal.bytes(),
+                    layer_seed(seed.as_ref(), 1),
+                    pinned,
+                    *aes128,
+                );
+
+                // Each outer layer seals the previous layer's key and nonce
+                // under a fresh pair; the pinned values apply to the innermost
+                for layer in 2..=*layers {
+                    let (ciphertext, key, nonce) = &encrypted;
+                    encrypted = encrypt_layer(
+                        &layer_plaintext(ciphertext, key, nonce),
+                        layer_seed(seed.as_ref(), layer),
+                        &Pinned::default(),
+                        *aes128,
+                    );
+                }
+                encrypted
+            }
+            Payload::Encrypted {
+                ciphertext,
+                key,
+                nonce,
+            } => (ciphertext.clone(), *key, *nonce),
+        };
+
+        // Wrap the outermost ciphertext in its junk padding
+        #[cfg(feature = "ciphertext-padding")]
+        let encrypted = {
+            let (ciphertext, key, nonce) = encrypted;
+            (pad_ciphertext(&ciphertext, &nonce), key, nonce)
+        };
+
+        // Shuffle the outermost ciphertext into its stored order
+        #[cfg(feature = "permute-ciphertext")]
+        let encrypted = {
+            let (ciphertext, key, nonce) = encrypted;
+            (permute_ciphertext(&ciphertext, &nonce), key, nonce)
+        };
+
+        encrypted
+    }
+
+    /// Returns the (ciphertext, key, nonce) for `cfg(fuzzing)` builds.
+    ///
+    /// Only strings that would otherwise get a random key have a fuzzing
+    /// variant; it is derived from a fixed seed and the plaintext so fuzz
+    /// rebuilds embed identical data. Returns `None` when [`encrypt`] is
+    /// already deterministic (explicit or environment seed, pinned key and
+    /// nonce on a single-layer string, or pre-encrypted input). A pinned
+    /// pair only covers the innermost layer, so layered strings with one
+    /// still take the fuzzing variant for their outer layers.
+    ///
+    /// [`encrypt`]: Self::encrypt
+    pub fn encrypt_for_fuzzing(&self) -> Option<Encrypted> {
+        match &self.payload {
+            Payload::Plaintext {
+                literal,
+                pinned,
+                layers,
+                ..
+            } if self.seed().is_none()
+                && (pinned.key.is_none() || pinned.nonce.is_none() || *layers > 1) =>
+            {
+                Some(self.encrypt_seeded(Some(format!("{FUZZING_SEED}:{}", literal.seed_text()))))
+            }
+            _ => None,
+        }
+    }
+
+    /// Rejects input whose plaintext is empty, for `obfuse_nonempty!`.
+    ///
+    /// Pre-encrypted input is empty when nothing but the authentication tag
+    /// (and the `multi-aes` key-size prefix) remains.
+    pub fn require_nonempty(&self) -> syn::Result<()> {
+        let (empty, span) = match &self.payload {
+            Payload::Plaintext { literal, .. } => (literal.is_empty(), literal.span()),
+            Payload::Encrypted { ciphertext, .. } => {
+                let overhead = TAG_SIZE + usize::from(cfg!(feature = "multi-aes"));
+                (ciphertext.len() <= overhead, proc_macro2::Span::call_site())
+            }
+        };
+
+        if empty {
+            return Err(syn::Error::new(
+                span,
+                "`obfuse_nonempty!` requires a non-empty string",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the number of encryption layers (1 for pre-encrypted input).
+    pub fn layers(&self) -> u8 {
+        match &self.payload {
+            Payload::Plaintext { layers, .. } => *layers,
+            Payload::Encrypted { .. } => 1,
+        }
+    }
+
+    /// Returns the seed for key and name generation, if any.
+    ///
+    /// An explicit `seed` wins. Otherwise, when the environment requests a
+    /// deterministic build (see [`env_seed`]), the seed is derived from that
+    /// value and the payload, so every expansion is reproducible.
+    pub fn seed(&self) -> Option<String> {
+        self.seed_with(env_seed())
+    }
+
+    fn seed_with(&self, env_seed: Option<String>) -> Option<String> {
+        match &self.payload {
+            Payload::Plaintext {
+                seed: Some(seed), ..
+            } => Some(seed.value()),
+            Payload::Plaintext { literal, .. } => {
+                env_seed.map(|env| format!("{env}:{}", literal.seed_text()))
+            }
+            Payload::Encrypted { ciphertext, .. } => {
+                env_seed.map(|env| format!("{env}:{ciphertext:02x?}"))
+            }
+        }
+    }
+
+    /// Returns the `#[link_section]` attributes for the backing static, if requested.
+    ///
+    /// The default name is chosen per target: `__DATA,__obfuse` on Mach-O and
+    /// `.obfuse` on ELF and PE/COFF. Other targets (such as WebAssembly, where
+    /// custom sections are not loaded into memory) get no attribute.
+    pub fn section_attrs(&self) -> Option<TokenStream2> {
+        let section = self.options.section.as_ref()?;
+
+        Some(match section {
+            LinkSection::Custom(name) => quote! {
+                #[allow(unsafe_code)]
+                #[unsafe(link_section = #name)]
+            },
+            LinkSection::Default => quote! {
+                #[allow(unsafe_code)]
+                #[cfg_attr(target_vendor = "apple", unsafe(link_section = "__DATA,__obfuse"))]
+                #[cfg_attr(
+                    all(
+                        not(target_vendor = "apple"),
+                        any(target_family = "unix", target_os = "windows")
+                    ),
+                    unsafe(link_section = ".obfuse")
+                )]
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPOCH: &str = "SOURCE_DATE_EPOCH=1700000000";
+
+    #[test]
+    fn test_array_elements_length_prefixed() {
+        let literal = Literal::Array(vec![
+            syn::parse_quote!("ab"),
+            syn::parse_quote!(""),
+            syn::parse_quote!("c"),
+        ]);
+        assert_eq!(literal.bytes(), b"\x02\0\0\0ab\0\0\0\0\x01\0\0\0c");
+    }
+
+    #[test]
+    fn test_size_limit_boundary() {
+        // Nothing can exceed the AEAD limits on 32-bit hosts, or with XOR
+        let Ok(max) = usize::try_from(MAX_PLAINTEXT_LEN) else {
+            return;
+        };
+        let Some(over) = max.checked_add(1) else {
+            return;
+        };
+        let span = proc_macro2::Span::call_site();
+
+        assert!(check_size_limit(max, 1, span).is_ok());
+        assert!(check_size_limit(over, 1, span).is_err());
+        // Layer overhead counts against the limit
+        assert!(check_size_limit(max, 2, span).is_err());
+        let overhead = KEY_SIZE + NONCE_SIZE + TAG_SIZE;
+        assert!(check_size_limit(max - overhead, 2, span).is_ok());
+    }
+
+    #[test]
+    fn test_env_seed_is_deterministic() {
+        let input: ObfuseInput = syn::parse_quote!("reproducible");
+
+        let seed = input.seed_with(Some(EPOCH.to_owned()));
+        assert_eq!(
+            input.encrypt_seeded(seed.clone()),
+            input.encrypt_seeded(seed)
+        );
+
+        // Without the variable, keys are random again
+        let unset = input.seed_with(None);
+        assert_eq!(unset, None);
+        assert_ne!(input.encrypt_seeded(None), input.encrypt_seeded(None));
+    }
+
+    #[test]
+    fn test_env_seed_depends_on_plaintext() {
+        let a: ObfuseInput = syn::parse_quote!("a");
+        let b: ObfuseInput = syn::parse_quote!("b");
+
+        let env = || Some(EPOCH.to_owned());
+        assert_ne!(a.seed_with(env()), b.seed_with(env()));
+    }
+
+    #[test]
+    fn test_fuzzing_variant_is_reproducible() {
+        let input: ObfuseInput = syn::parse_quote!("fuzzed");
+        assert_eq!(input.encrypt_for_fuzzing(), input.encrypt_for_fuzzing());
+        assert!(input.encrypt_for_fuzzing().is_some());
+
+        // Already-deterministic inputs need no separate variant
+        let seeded: ObfuseInput = syn::parse_quote!("fuzzed", seed = "s");
+        assert!(seeded.encrypt_for_fuzzing().is_none());
+    }
+
+    #[test]
+    fn test_crate_seed_derives_per_string_keys() {
+        let crate_seed = Some("OBFUSE_CRATE_SEED=my-crate".to_owned());
+        let a: ObfuseInput = syn::parse_quote!("first");
+        let b: ObfuseInput = syn::parse_quote!("second");
+
+        let seed_a = a.seed_with(crate_seed.clone());
+        assert_eq!(
+            a.encrypt_seeded(seed_a.clone()),
+            a.encrypt_seeded(seed_a.clone())
+        );
+        assert_ne!(seed_a, b.seed_with(crate_seed.clone()));
+
+        // An inline seed still wins over the crate seed
+        let inline: ObfuseInput = syn::parse_quote!("first", seed = "inline");
+        assert_eq!(inline.seed_with(crate_seed).as_deref(), Some("inline"));
+    }
+
+    #[test]
+    fn test_c_string_literal() {
+        let input: ObfuseInput = syn::parse_quote!(c"nul\xff", seed = "s");
+        let Payload::Plaintext { literal, .. } = &input.payload else {
+            panic!("expected a plaintext payload");
+        };
+        assert_eq!(literal.bytes(), b"nul\xff\0");
+        assert!(input.require_nonempty().is_ok());
+
+        // Only the terminator: empty for `obfuse_nonempty!`
+        let empty: ObfuseInput = syn::parse_quote!(c"");
+        assert!(empty.require_nonempty().is_err());
+
+        let raw: ObfuseInput = syn::parse_quote!(cr#"say "hi""#);
+        assert!(raw.encrypt_for_fuzzing().is_some());
+    }
+
+    #[test]
+    fn test_explicit_seed_wins() {
+        let input: ObfuseInput = syn::parse_quote!("value", seed = "explicit");
+        assert_eq!(
+            input.seed_with(Some(EPOCH.to_owned())).as_deref(),
+            Some("explicit")
+        );
+    }
+}
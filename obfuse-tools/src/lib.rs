@@ -0,0 +1,212 @@
+//! Source scanning for string literals that should be wrapped in `obfuse!`.
+//!
+//! A linting aid: [`scan_source`] parses every `.rs` file under a path with
+//! `syn` and reports string literals that look like secrets (well-known key
+//! formats, URLs with passwords, high-entropy tokens) and are not already
+//! the input of an `obfuse` macro.
+//!
+//! ```ignore
+//! for finding in obfuse_tools::scan_source("src")? {
+//!     eprintln!("{finding}");
+//! }
+//! ```
+//!
+//! Findings never carry the full literal: [`Finding::preview`] keeps only
+//! its first few characters, so scan output is safe to log in CI.
+//!
+//! The checks are heuristics. Expect false positives (hashes, test vectors)
+//! and false negatives (short or low-entropy passwords).
+
+#![forbid(unsafe_code)]
+#![deny(missing_docs)]
+#![deny(clippy::all)]
+#![warn(clippy::pedantic)]
+
+mod detect;
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use syn::visit::{self, Visit};
+
+pub use detect::FindingKind;
+
+/// Macros whose string-literal input is already obfuscated.
+const OBFUSE_MACROS: &[&str] = &[
+    "obfuse",
+    "obfuse_array",
+    "obfuse_const",
+    "obfuse_eager",
+    "obfuse_honey",
+    "obfuse_inline",
+    "obfuse_nonempty",
+    "obfuse_once",
+    "obfuse_static",
+];
+
+/// Number of leading characters kept in [`Finding::preview`].
+const PREVIEW_CHARS: usize = 4;
+
+/// A string literal that looks like an unobfuscated secret.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    /// File containing the literal; empty for [`scan_str`].
+    pub path: PathBuf,
+
+    /// 1-based line of the literal's opening quote.
+    pub line: usize,
+
+    /// 1-based column of the literal's opening quote, in characters.
+    pub column: usize,
+
+    /// Why the literal was flagged.
+    pub kind: FindingKind,
+
+    /// The first few characters of the literal followed by `...`, so the
+    /// finding can be located without repeating the secret.
+    pub preview: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {} (\"{}\"); wrap it in obfuse!",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.kind,
+            self.preview
+        )
+    }
+}
+
+/// Scans `path` (a `.rs` file, or a directory searched recursively) for
+/// string literals that look like secrets and are not wrapped in `obfuse!`.
+///
+/// Directories named `target` and hidden directories are skipped. Findings
+/// are ordered by path, then position.
+///
+/// # Errors
+///
+/// Returns an error if a file or directory cannot be read, or an
+/// [`io::ErrorKind::InvalidData`] error naming the file if a `.rs` file does
+/// not parse.
+pub fn scan_source(path: impl AsRef<Path>) -> io::Result<Vec<Finding>> {
+    let mut files = Vec::new();
+    collect_rs_files(path.as_ref(), &mut files)?;
+    files.sort();
+
+    let mut findings = Vec::new();
+    for file in files {
+        let source = fs::read_to_string(&file)?;
+        let found = scan_str(&source).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {e}", file.display()),
+            )
+        })?;
+        findings.extend(found.into_iter().map(|finding| Finding {
+            path: file.clone(),
+            ..finding
+        }));
+    }
+    Ok(findings)
+}
+
+/// Scans Rust source text, as [`scan_source`] does for each file.
+///
+/// # Errors
+///
+/// Returns the parse error if `source` is not a valid Rust file.
+pub fn scan_str(source: &str) -> syn::Result<Vec<Finding>> {
+    let file = syn::parse_file(source)?;
+    let mut scanner = Scanner::default();
+    scanner.visit_file(&file);
+    Ok(scanner.findings)
+}
+
+fn collect_rs_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path.to_path_buf());
+        }
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let skipped = name == "target" || name.to_string_lossy().starts_with('.');
+        if entry.file_type()?.is_dir() && skipped {
+            continue;
+        }
+        collect_rs_files(&entry.path(), files)?;
+    }
+    Ok(())
+}
+
+/// Collects findings while walking a parsed file.
+#[derive(Default)]
+struct Scanner {
+    findings: Vec<Finding>,
+}
+
+impl Scanner {
+    fn check(&mut self, lit: &syn::LitStr) {
+        let value = lit.value();
+        let Some(kind) = detect::classify(&value) else {
+            return;
+        };
+
+        let start = lit.span().start();
+        let mut preview: String = value.chars().take(PREVIEW_CHARS).collect();
+        preview.push_str("...");
+        self.findings.push(Finding {
+            path: PathBuf::new(),
+            line: start.line,
+            column: start.column + 1,
+            kind,
+            preview,
+        });
+    }
+
+    /// Checks string literals inside a macro's tokens, which `syn` does not
+    /// parse into expressions (e.g. `println!`, `vec!`).
+    fn check_tokens(&mut self, tokens: proc_macro2::TokenStream) {
+        for token in tokens {
+            match token {
+                proc_macro2::TokenTree::Literal(literal) => {
+                    if let syn::Lit::Str(lit) = syn::Lit::new(literal) {
+                        self.check(&lit);
+                    }
+                }
+                proc_macro2::TokenTree::Group(group) => self.check_tokens(group.stream()),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Scanner {
+    fn visit_lit_str(&mut self, lit: &'ast syn::LitStr) {
+        self.check(lit);
+    }
+
+    // Doc comments and other attribute arguments are not runtime strings
+    fn visit_attribute(&mut self, _attr: &'ast syn::Attribute) {}
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let is_obfuse = mac
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| OBFUSE_MACROS.iter().any(|name| segment.ident == name));
+        if !is_obfuse {
+            self.check_tokens(mac.tokens.clone());
+        }
+        visit::visit_macro(self, mac);
+    }
+}
@@ -0,0 +1,58 @@
+//! Scans the fixture tree under `tests/fixtures`.
+
+use std::path::Path;
+
+use obfuse_tools::{FindingKind, scan_source, scan_str};
+
+fn fixtures() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tree"))
+}
+
+#[test]
+fn test_wrapped_secrets_are_not_reported() {
+    let findings = scan_source(fixtures().join("wrapped.rs")).unwrap();
+    assert_eq!(findings, []);
+}
+
+#[test]
+fn test_unwrapped_secrets_are_reported() {
+    let file = fixtures().join("nested/unwrapped.rs");
+    let findings = scan_source(&file).unwrap();
+
+    let found: Vec<_> = findings
+        .iter()
+        .map(|f| (f.line, f.column, f.kind))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (2, 23, FindingKind::KeyPattern("an AWS access key ID")),
+            (5, 15, FindingKind::CredentialUrl),
+            (7, 26, FindingKind::HighEntropy),
+        ]
+    );
+
+    // Output locates the literal without repeating it
+    assert!(findings.iter().all(|f| f.path == file));
+    assert_eq!(findings[0].preview, "AKIA...");
+    let line = findings[1].to_string();
+    assert!(line.contains("unwrapped.rs:5:15"), "{line}");
+    assert!(!line.contains("hunter2"), "{line}");
+}
+
+#[test]
+fn test_directory_scan_recurses_and_skips_hidden() {
+    let findings = scan_source(fixtures()).unwrap();
+
+    assert_eq!(findings.len(), 3);
+    assert!(
+        findings
+            .iter()
+            .all(|f| f.path.ends_with("nested/unwrapped.rs"))
+    );
+}
+
+#[test]
+fn test_unparsable_source_is_an_error() {
+    assert!(scan_str("fn broken( {").is_err());
+}
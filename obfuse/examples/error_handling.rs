@@ -11,12 +11,13 @@ fn main() {
         Err(ObfuseError::AllocationFailed) => {
             eprintln!("Out of memory during decryption");
         }
-        Err(ObfuseError::AuthenticationFailed) => {
+        Err(ObfuseError::AuthenticationFailed(_)) => {
             eprintln!("Decryption failed - binary may be corrupted");
         }
         Err(ObfuseError::InvalidUtf8(e)) => {
             eprintln!("Invalid UTF-8: {e}");
         }
+        Err(e) => eprintln!("Decryption failed: {e}"),
     }
 
     // Using Result with ? operator
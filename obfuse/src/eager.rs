@@ -0,0 +1,32 @@
+//! Startup registry for strings declared with `obfuse_eager!`.
+
+use obfuse_core::{ObfuseError, ObfuseStr};
+
+/// A string registered by `obfuse_eager!`.
+///
+/// Submitted by the macro expansion and should not be constructed directly.
+#[doc(hidden)]
+pub struct EagerEntry(pub &'static ObfuseStr);
+
+inventory::collect!(EagerEntry);
+
+/// Decrypts every string declared with `obfuse_eager!`, in any crate linked
+/// into the program.
+///
+/// Call this once at startup to pay the decryption cost up front instead of
+/// on first access, for predictable latency later. Strings already decrypted
+/// are skipped.
+///
+/// # Errors
+///
+/// Every registered string is attempted; if any fails to decrypt, the first
+/// error is returned.
+pub fn warm_all() -> Result<(), ObfuseError> {
+    let mut result = Ok(());
+    for entry in inventory::iter::<EagerEntry> {
+        if let Err(e) = entry.0.try_decrypt() {
+            result = result.and(Err(e));
+        }
+    }
+    result
+}
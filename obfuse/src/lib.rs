@@ -27,9 +27,76 @@
 //!
 //! - `aes-256-gcm` (default) - AES-256 in GCM mode (strongest)
 //! - `aes-128-gcm` - AES-128 in GCM mode
+//! - `aes-256-ccm` - AES-256 in CCM mode, for compliance profiles without GCM
 //! - `chacha20-poly1305` - ChaCha20-Poly1305 AEAD
 //! - `xor` - Simple XOR cipher (fast, weakest)
 //!
+//! `polymorphic` extends `xor` with a random per-string program of
+//! XOR/add/rotate/multiply steps, stored in the otherwise unused nonce. It
+//! also enables `obfuse_inline!`, which unrolls the inverse program into
+//! each call site and returns a fresh `Zeroizing<String>` with no
+//! `ObfuseStr` behind it.
+//!
+//! `multi-aes` relaxes this for AES: both AES-128-GCM and AES-256-GCM are
+//! compiled in, and each string picks one with `obfuse!("...", aes = 128)`
+//! (AES-256 is the default). Ciphertexts carry a one-byte key-size tag.
+//!
+//! Optional extras:
+//!
+//! - `manifest` - HMAC manifest over embedded ciphertexts for tamper detection
+//!   (see `obfuse_const!`)
+//! - `decoy-code` - Unreachable decoy decryption path to mislead static analysis
+//! - `defmt` - Redacted `defmt::Format` implementation for `ObfuseStr`
+//! - `secrecy` - `ObfuseStr::to_secret_string` converting into `secrecy::SecretString`
+//! - `access-metrics` - `ObfuseStr::access_count`, counting plaintext accesses
+//!   to spot abnormal access patterns
+//! - `miette` - `miette::Diagnostic` for `ObfuseError`, with error codes such
+//!   as `obfuse::auth_failed` and help pointing at likely causes
+//! - `permute-ciphertext` - Stores each ciphertext shuffled by a permutation
+//!   seeded from its nonce, so carving contiguous bytes doesn't yield the blob
+//! - `ciphertext-padding` - Junk bytes before and after each stored
+//!   ciphertext, sized and filled from the nonce
+//! - `honeytoken` - `obfuse_honey!`, declaring decoy secrets whose first
+//!   decryption runs a callback, e.g. to alert a SIEM
+//! - `eager` - `obfuse_eager!` and `warm_all()`, decrypting every registered
+//!   string at startup instead of on first access
+//! - `runtime-encrypt` - `ObfuseStr::from_reader`, encrypting a secret read
+//!   from stdin, a pipe or a file under a fresh random key, and
+//!   `ObfuseStr::encrypt_with`, encrypting with a user-supplied `Cipher`
+//! - `decrypt-noinline` - Keeps the cipher's `decrypt` routine out of line,
+//!   as a single cold function, instead of letting the compiler inline it
+//! - `password-hash` - `ObfuseStr::verify_against`, checking the secret
+//!   against a stored Argon2 hash
+//! - `tokio` - `ObfuseStr::warm_async`, pre-decrypting a static secret on
+//!   Tokio's blocking pool instead of the reactor thread
+//! - `host-pepper` - Mixes `OBFUSE_PEPPER`, a secret supplied only at build
+//!   time, into seeded keys so the source tree alone cannot reproduce them
+//! - `shuffle-layout` - Emits each `obfuse_const!` block's statics in a
+//!   build-random order, so adjacent secrets aren't laid out in source order
+//! - `serialize-redacted` - `ObfuseStr: Serialize` as `"[REDACTED]"`; fields
+//!   opt into the ciphertext export with `obfuse::serde_ciphertext`
+//! - `transport-safe` - Redacting `Display` and `serde::Serialize`, so only
+//!   an explicit `reveal()` or `as_str()` yields the plaintext
+//! - `serde-config` - `ObfuseStr: Deserialize` from a plaintext string,
+//!   encrypted under a fresh random key as soon as a config file is parsed
+//! - `anti-debug` - Refuses to decrypt while a debugger is attached, with
+//!   `ObfuseError::DebuggerDetected`; best effort, Linux and Android only
+//! - `utf16` - `ObfuseStr::with_utf16`/`with_utf16_nul`, passing a wiped
+//!   UTF-16 copy to a closure for Win32 `W` APIs
+//! - `streaming` - Chunked `aead::stream` ciphertext, one tag per 64 KiB,
+//!   for very large secrets; AEAD algorithms only
+//! - `pii-redact` - `ObfuseStr::reveal_redacted_for_log`, masking emails,
+//!   card numbers or custom regexes before a secret is logged
+//! - `redaction-policy` - `set_redaction_policy` and `ObfuseStr::debug_with`,
+//!   showing the ciphertext length, the algorithm or nothing at all in
+//!   `Debug` output instead of the default `[REDACTED]` struct
+//!
+//! Hardening presets, combinable with any algorithm:
+//!
+//! - `profile-light` - `permute-ciphertext` and `decrypt-noinline`
+//! - `profile-paranoid` - `profile-light` plus `ciphertext-padding`,
+//!   `decoy-code` and `shuffle-layout`
+//!
 //! # Usage
 //!
 //! ## Basic Usage
@@ -73,12 +140,13 @@
 //!         Err(ObfuseError::AllocationFailed) => {
 //!             eprintln!("Out of memory during decryption");
 //!         }
-//!         Err(ObfuseError::AuthenticationFailed) => {
+//!         Err(ObfuseError::AuthenticationFailed(_)) => {
 //!             eprintln!("Decryption failed - binary may be corrupted");
 //!         }
 //!         Err(ObfuseError::InvalidUtf8(e)) => {
 //!             eprintln!("Invalid UTF-8: {e}");
 //!         }
+//!         Err(e) => eprintln!("Decryption failed: {e}"),
 //!     }
 //! }
 //! ```
@@ -88,8 +156,58 @@
 #![deny(clippy::all)]
 #![warn(clippy::pedantic)]
 
-// Re-export the macro
-pub use obfuse_macros::obfuse;
+#[cfg(feature = "eager")]
+mod eager;
+
+// Re-export the macros
+pub use obfuse_macros::{
+    obfuse, obfuse_array, obfuse_const, obfuse_nonempty, obfuse_once, obfuse_static,
+};
 
 // Re-export core types
-pub use obfuse_core::{ObfuseError, ObfuseStr};
+pub use obfuse_core::{
+    AlignedBytesGuard, DecryptErrorHook, ObfuseBlob, ObfuseError, ObfuseOnceStr, ObfuseStr,
+    ObfuseStrArray, ParseSecretError, Revealed, Secret, SecretBytesGuard, SecretVec,
+    on_decrypt_error,
+};
+
+#[cfg(feature = "manifest")]
+pub use obfuse_core::{Manifest, manifest_hmac};
+
+#[cfg(feature = "pii-redact")]
+pub use obfuse_core::RedactConfig;
+#[cfg(feature = "permute-ciphertext")]
+pub use obfuse_core::permute_ciphertext;
+#[cfg(feature = "serialize-redacted")]
+pub use obfuse_core::serde_ciphertext;
+#[cfg(feature = "runtime-encrypt")]
+pub use obfuse_core::{CUSTOM_ALGORITHM, Cipher};
+#[cfg(feature = "ciphertext-padding")]
+pub use obfuse_core::{MAX_PADDING, pad_ciphertext};
+#[cfg(feature = "redaction-policy")]
+pub use obfuse_core::{Redaction, RedactionPolicy, set_redaction_policy};
+
+#[cfg(feature = "honeytoken")]
+pub use obfuse_macros::obfuse_honey;
+
+#[cfg(feature = "polymorphic")]
+pub use obfuse_macros::obfuse_inline;
+
+#[cfg(feature = "eager")]
+pub use eager::warm_all;
+#[cfg(feature = "eager")]
+pub use obfuse_macros::obfuse_eager;
+
+/// Items used by macro expansions; not public API.
+#[cfg(any(feature = "eager", feature = "polymorphic"))]
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "eager")]
+    pub use crate::eager::EagerEntry;
+    #[cfg(feature = "eager")]
+    pub use inventory;
+    #[cfg(feature = "polymorphic")]
+    pub use obfuse_core::mul_inverse;
+    #[cfg(feature = "polymorphic")]
+    pub use zeroize::Zeroizing;
+}
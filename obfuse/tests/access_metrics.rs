@@ -0,0 +1,26 @@
+//! Tests for the `access-metrics` access counter.
+
+#![cfg(feature = "access-metrics")]
+
+use obfuse::obfuse;
+
+#[test]
+fn test_access_count_includes_cache_hits() {
+    let secret = obfuse!("counted");
+    assert_eq!(secret.access_count(), 0);
+
+    // First access decrypts, the rest hit the cache
+    assert_eq!(secret.as_str(), "counted");
+    assert_eq!(secret.as_bytes(), b"counted");
+    let _ = secret.try_as_str().unwrap();
+    assert_eq!(secret.len(), 7);
+    assert_eq!(secret.access_count(), 4);
+}
+
+#[test]
+fn test_fresh_copies_are_not_counted() {
+    let secret = obfuse!("uncounted");
+    let _ = secret.reveal();
+    let _ = secret.reveal_bytes().unwrap();
+    assert_eq!(secret.access_count(), 0);
+}
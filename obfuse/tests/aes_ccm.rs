@@ -0,0 +1,54 @@
+//! Tests for the AES-256-CCM backend.
+
+#![cfg(all(
+    feature = "aes-256-ccm",
+    not(any(feature = "aes-256-gcm", feature = "aes-128-gcm"))
+))]
+
+use obfuse::obfuse;
+
+#[test]
+fn test_round_trip() {
+    assert_eq!(obfuse!("").as_str(), "");
+    assert_eq!(obfuse!("ccm secret").as_str(), "ccm secret");
+    assert_eq!(obfuse!("seeded", seed = "ccm").as_str(), "seeded");
+    assert_eq!(obfuse_core::ALGORITHM, "AES-256-CCM");
+}
+
+// Padded, permuted and streamed ciphertexts change the stored length
+#[cfg(not(any(
+    feature = "permute-ciphertext",
+    feature = "ciphertext-padding",
+    feature = "streaming"
+)))]
+#[test]
+fn test_ciphertext_carries_16_byte_tag() {
+    let secret = obfuse!("sixteen + tag");
+    assert_eq!(secret.ciphertext().len(), "sixteen + tag".len() + 16);
+}
+
+// Padded ciphertexts start with junk bytes that are not authenticated
+#[cfg(not(feature = "ciphertext-padding"))]
+#[test]
+fn test_tampered_ciphertext_fails_authentication() {
+    use obfuse::{ObfuseError, ObfuseStr};
+
+    const KEY: [u8; 32] = [0x11; 32];
+    const NONCE: [u8; 12] = [0x22; 12];
+
+    let secret = obfuse!(
+        "tamper me",
+        key = "1111111111111111111111111111111111111111111111111111111111111111",
+        nonce = "222222222222222222222222",
+    );
+    let intact = ObfuseStr::new_from_owned(secret.ciphertext().to_vec(), KEY, NONCE);
+    assert_eq!(intact.as_str(), "tamper me");
+
+    let mut ciphertext = secret.ciphertext().to_vec();
+    ciphertext[0] ^= 1;
+    let tampered = ObfuseStr::new_from_owned(ciphertext, KEY, NONCE);
+    assert!(matches!(
+        tampered.try_as_str(),
+        Err(ObfuseError::AuthenticationFailed(_))
+    ));
+}
@@ -0,0 +1,15 @@
+//! Tests for the `anti-debug` feature.
+//!
+//! These run without a debugger attached; under one, decryption is refused
+//! by design and they fail.
+
+#![cfg(feature = "anti-debug")]
+
+use obfuse::obfuse;
+
+#[test]
+fn test_decrypts_without_debugger() {
+    let secret = obfuse!("no tracer here");
+    assert_eq!(secret.try_as_str().unwrap(), "no tracer here");
+    assert_eq!(secret.reveal().as_str(), "no tracer here");
+}
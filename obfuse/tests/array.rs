@@ -0,0 +1,43 @@
+//! Tests for `obfuse_array!` and `ObfuseStrArray`.
+
+use obfuse::obfuse_array;
+
+#[test]
+fn test_array_round_trips_every_element() {
+    let elements = ["alpha", "", "gamma with spaces", "δέλτα", "\0nul\0"];
+    let array = obfuse_array!["alpha", "", "gamma with spaces", "δέλτα", "\0nul\0"];
+
+    assert_eq!(array.len(), elements.len());
+    assert!(!array.is_empty());
+    for (index, expected) in elements.iter().enumerate() {
+        assert_eq!(array.get(index), Some(*expected));
+        assert_eq!(array.try_get(index).unwrap(), Some(*expected));
+    }
+}
+
+#[test]
+fn test_array_rejects_out_of_bounds_index() {
+    let array = obfuse_array!["only"];
+    assert_eq!(array.get(1), None);
+    assert_eq!(array.try_get(usize::MAX).unwrap(), None);
+    assert_eq!(array.get(0), Some("only"));
+}
+
+#[test]
+fn test_array_accepts_options() {
+    let array = obfuse_array!["a", "b", seed = "array_seed", layers = 2];
+    assert_eq!(array.get(0), Some("a"));
+    assert_eq!(array.get(1), Some("b"));
+
+    let trailing = obfuse_array!["x", "y",];
+    assert_eq!(trailing.len(), 2);
+    assert_eq!(trailing.get(1), Some("y"));
+}
+
+#[test]
+fn test_array_debug_is_redacted() {
+    let array = obfuse_array!["hidden", "values"];
+    let debug = format!("{array:?}");
+    assert!(debug.contains("len: 2"));
+    assert!(!debug.contains("hidden"));
+}
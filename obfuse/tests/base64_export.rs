@@ -0,0 +1,89 @@
+//! Round-trip tests for `ObfuseStr::to_base64` and `ObfuseStr::from_base64`.
+
+use obfuse::{ObfuseError, ObfuseStr, obfuse};
+
+#[test]
+fn test_export_round_trips() {
+    let secret = obfuse!("copy-pasteable secret");
+    let export = secret.to_base64().unwrap();
+    assert!(!secret.is_decrypted());
+    assert!(!export.contains("copy-pasteable"));
+
+    let imported = ObfuseStr::from_base64(&export).unwrap();
+    assert_eq!(imported.as_str(), "copy-pasteable secret");
+    assert_eq!(imported.ciphertext(), secret.ciphertext());
+    assert_eq!(imported.to_base64().unwrap(), export);
+
+    assert_eq!(
+        ObfuseStr::from_base64(&obfuse!("").to_base64().unwrap())
+            .unwrap()
+            .as_str(),
+        ""
+    );
+}
+
+#[test]
+fn test_layered_export_round_trips() {
+    let export = obfuse!("onion export", layers = 3).to_base64().unwrap();
+    assert!(export.ends_with("|3"));
+    assert_eq!(
+        ObfuseStr::from_base64(&export).unwrap().as_str(),
+        "onion export"
+    );
+}
+
+#[test]
+fn test_malformed_exports_are_rejected() {
+    let export = obfuse!("original").to_base64().unwrap();
+    let fields: Vec<&str> = export.split('|').collect();
+    let with = |index: usize, value: &str| {
+        let mut fields = fields.clone();
+        fields[index] = value;
+        fields.join("|")
+    };
+
+    let cases = [
+        String::new(),
+        fields[..3].join("|"),
+        format!("{export}|2|extra"),
+        format!("{export}|0"),
+        with(0, "ROT13"),
+        with(2, "not base64!"),
+        with(2, &fields[2][1..]),
+        with(1, "AAAA"),
+        with(3, "AAAA"),
+    ];
+    for case in &cases {
+        assert!(
+            matches!(
+                ObfuseStr::from_base64(case),
+                Err(ObfuseError::InvalidEncoding(_))
+            ),
+            "{case:?}"
+        );
+    }
+}
+
+#[cfg(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+))]
+#[test]
+fn test_corrupted_ciphertext_fails_authentication() {
+    let export = obfuse!("original").to_base64().unwrap();
+    let mut fields: Vec<String> = export.split('|').map(String::from).collect();
+
+    // Still valid base64 of the same length, but all zero bytes
+    fields[2] = fields[2]
+        .chars()
+        .map(|c| if c == '=' { c } else { 'A' })
+        .collect();
+
+    let tampered = ObfuseStr::from_base64(&fields.join("|")).unwrap();
+    assert!(matches!(
+        tampered.try_as_str(),
+        Err(ObfuseError::AuthenticationFailed(_))
+    ));
+}
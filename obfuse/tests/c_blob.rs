@@ -0,0 +1,76 @@
+//! Tests for `ObfuseStr::from_c_blob`.
+
+#![cfg(all(
+    feature = "aes-256-gcm",
+    not(any(feature = "multi-aes", feature = "streaming"))
+))]
+
+use obfuse::{ObfuseBlob, ObfuseError, ObfuseStr};
+
+/// "pre-encrypted secret" under key 00..1f and nonce 00..0b.
+const CIPHERTEXT: &str = "3770b336a08ba169f431e3eed5c90b08e0a4e2404e419b946bf965bf95de5ed5e51fbace";
+
+/// Returns the blob a C code generator would emit, and its ciphertext.
+fn mocked_blob() -> (ObfuseBlob, Vec<u8>) {
+    let ciphertext: Vec<u8> = (0..CIPHERTEXT.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&CIPHERTEXT[i..i + 2], 16).unwrap())
+        .collect();
+    let nonce = std::array::from_fn(|i| u8::try_from(i).unwrap());
+
+    #[cfg(feature = "ciphertext-padding")]
+    let ciphertext = obfuse::pad_ciphertext(&ciphertext, &nonce);
+    #[cfg(feature = "permute-ciphertext")]
+    let ciphertext = obfuse::permute_ciphertext(&ciphertext, &nonce);
+
+    let blob = ObfuseBlob {
+        ciphertext_len: ciphertext.len(),
+        key: std::array::from_fn(|i| u8::try_from(i).unwrap()),
+        nonce,
+        layers: 1,
+    };
+    (blob, ciphertext)
+}
+
+#[test]
+fn test_from_c_blob() {
+    let (blob, ciphertext) = mocked_blob();
+
+    let secret = ObfuseStr::from_c_blob(&blob, &ciphertext).unwrap();
+    drop(ciphertext);
+    assert_eq!(secret.as_str(), "pre-encrypted secret");
+}
+
+#[test]
+fn test_from_c_blob_rejects_mismatched_length() {
+    let (blob, ciphertext) = mocked_blob();
+
+    assert!(matches!(
+        ObfuseStr::from_c_blob(&blob, &ciphertext[1..]),
+        Err(ObfuseError::InvalidBlob(
+            "ciphertext length does not match the blob"
+        ))
+    ));
+}
+
+#[test]
+fn test_from_c_blob_rejects_bad_layers() {
+    let (mut blob, ciphertext) = mocked_blob();
+
+    for layers in [0, obfuse_core::MAX_LAYERS + 1] {
+        blob.layers = layers;
+        assert!(matches!(
+            ObfuseStr::from_c_blob(&blob, &ciphertext),
+            Err(ObfuseError::InvalidBlob("layer count out of range"))
+        ));
+    }
+}
+
+#[test]
+fn test_wrong_key_fails_on_access() {
+    let (mut blob, ciphertext) = mocked_blob();
+    blob.key[0] ^= 1;
+
+    let secret = ObfuseStr::from_c_blob(&blob, &ciphertext).unwrap();
+    assert!(secret.try_as_str().is_err());
+}
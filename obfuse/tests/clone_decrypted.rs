@@ -0,0 +1,64 @@
+//! Tests that `try_clone_decrypted` copies are wiped before they are freed.
+//!
+//! A wrapping global allocator inspects every deallocation of the plaintext's
+//! size while armed, recording whether the block was already zeroed.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use obfuse::obfuse;
+
+const PLAINTEXT: &str = "handed to a worker thread";
+
+struct WipeCheck;
+
+static ARMED: AtomicBool = AtomicBool::new(false);
+static WIPED: AtomicUsize = AtomicUsize::new(0);
+static DIRTY: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for WipeCheck {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if ARMED.load(Ordering::SeqCst) && layout.size() == PLAINTEXT.len() {
+            let block = unsafe { std::slice::from_raw_parts(ptr, layout.size()) };
+            let counter = if block.iter().all(|&b| b == 0) {
+                &WIPED
+            } else {
+                &DIRTY
+            };
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: WipeCheck = WipeCheck;
+
+#[test]
+fn test_clone_decrypted_wipes_on_drop() {
+    let secret = obfuse!("handed to a worker thread");
+
+    let copy = secret.try_clone_decrypted().unwrap();
+    assert!(secret.is_decrypted());
+
+    let copy = std::thread::spawn(move || {
+        assert_eq!(copy.as_slice(), PLAINTEXT.as_bytes());
+        copy
+    })
+    .join()
+    .unwrap();
+
+    ARMED.store(true, Ordering::SeqCst);
+    drop(copy);
+    ARMED.store(false, Ordering::SeqCst);
+
+    assert_eq!(WIPED.load(Ordering::SeqCst), 1);
+    assert_eq!(DIRTY.load(Ordering::SeqCst), 0);
+
+    // The cache is untouched
+    assert_eq!(secret.as_str(), PLAINTEXT);
+}
@@ -0,0 +1,66 @@
+//! Tests for `ObfuseStr::encrypt_with` and the `Cipher` trait.
+
+#![cfg(feature = "runtime-encrypt")]
+
+use obfuse::{Cipher, ObfuseError, ObfuseStr};
+
+/// ROT13 over ASCII letters, shifted further by the key's first byte.
+struct Rot13;
+
+fn rotate(bytes: &[u8], shift: u8) -> Vec<u8> {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            b'a'..=b'z' => b'a' + (b - b'a' + shift) % 26,
+            b'A'..=b'Z' => b'A' + (b - b'A' + shift) % 26,
+            _ => b,
+        })
+        .collect()
+}
+
+impl Cipher for Rot13 {
+    const KEY_SIZE: usize = 1;
+    const NONCE_SIZE: usize = 0;
+
+    fn encrypt(plaintext: &[u8], key: &[u8], nonce: &[u8]) -> Vec<u8> {
+        assert_eq!((key.len(), nonce.len()), (1, 0));
+        rotate(plaintext, 13 + key[0] % 26)
+    }
+
+    fn decrypt(ciphertext: &[u8], key: &[u8], _nonce: &[u8]) -> Result<Box<[u8]>, ObfuseError> {
+        Ok(rotate(ciphertext, 26 - (13 + key[0] % 26) % 26).into_boxed_slice())
+    }
+}
+
+#[test]
+fn test_custom_cipher_round_trips() {
+    let secret = ObfuseStr::encrypt_with::<Rot13>(b"Hello, World!").unwrap();
+    assert!(!secret.is_decrypted());
+    assert_eq!(secret.as_str(), "Hello, World!");
+    assert_eq!(&*secret.reveal(), "Hello, World!");
+
+    let empty = ObfuseStr::encrypt_with::<Rot13>(b"").unwrap();
+    assert_eq!(empty.as_str(), "");
+}
+
+#[test]
+fn test_replacing_switches_back_to_builtin_cipher() {
+    let mut secret = ObfuseStr::encrypt_with::<Rot13>(b"rotated").unwrap();
+    secret
+        .replace_from_reader(&mut std::io::Cursor::new("built-in"))
+        .unwrap();
+    assert_eq!(secret.as_str(), "built-in");
+}
+
+#[test]
+fn test_custom_cipher_is_reported_and_not_exported() {
+    use obfuse::{CUSTOM_ALGORITHM, Secret};
+
+    let secret = ObfuseStr::encrypt_with::<Rot13>(b"rotated").unwrap();
+    assert_eq!(Secret::algorithm(&secret), CUSTOM_ALGORITHM);
+    assert!(matches!(secret.to_base64(), Err(ObfuseError::CustomCipher)));
+
+    let builtin = ObfuseStr::from_reader(&mut std::io::Cursor::new("built-in")).unwrap();
+    assert_eq!(Secret::algorithm(&builtin), obfuse_core::ALGORITHM);
+    assert!(builtin.to_base64().is_ok());
+}
@@ -0,0 +1,17 @@
+//! Tests that the decoy decryption path never affects real decryption.
+
+#![cfg(feature = "decoy-code")]
+
+use obfuse::obfuse;
+
+#[test]
+fn test_decryption_unaffected() {
+    let secret = obfuse!("decoy guarded");
+    assert_eq!(secret.try_as_str().unwrap(), "decoy guarded");
+
+    let empty = obfuse!("");
+    assert_eq!(empty.as_str(), "");
+
+    let seeded = obfuse!("seeded decoy", seed = "decoy_seed");
+    assert_eq!(seeded.as_str(), "seeded decoy");
+}
@@ -0,0 +1,59 @@
+//! Tests for the global `on_decrypt_error` hook.
+//!
+//! The hook is process-global, so this file holds a single test.
+
+#![cfg(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+))]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use obfuse::{ObfuseError, ObfuseStr, obfuse, on_decrypt_error};
+
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// A copy of `secret` with one ciphertext byte flipped.
+fn tampered(secret: &ObfuseStr) -> ObfuseStr {
+    let export = secret.to_base64().unwrap();
+    let mut fields: Vec<String> = export.split('|').map(String::from).collect();
+    fields[2] = fields[2]
+        .chars()
+        .map(|c| if c == '=' { c } else { 'A' })
+        .collect();
+    ObfuseStr::from_base64(&fields.join("|")).unwrap()
+}
+
+fn hook(algorithm: &'static str, error: &ObfuseError) {
+    CALLS.fetch_add(1, Ordering::SeqCst);
+    assert_eq!(algorithm, obfuse_core::ALGORITHM);
+    assert!(matches!(error, ObfuseError::AuthenticationFailed(_)));
+
+    // Failing again from inside the hook must not recurse
+    assert!(tampered(&obfuse!("nested")).try_as_str().is_err());
+    panic!("a broken hook must not break decryption");
+}
+
+#[test]
+fn test_hook_reports_tampered_instances() {
+    let good = obfuse!("monitored secret");
+    let bad = tampered(&good);
+
+    assert!(on_decrypt_error(hook));
+    assert!(!on_decrypt_error(|_, _| {}));
+
+    assert_eq!(good.as_str(), "monitored secret");
+    assert_eq!(CALLS.load(Ordering::SeqCst), 0);
+
+    // The hook panics; the caller still just sees the error
+    assert!(matches!(
+        bad.try_as_str(),
+        Err(ObfuseError::AuthenticationFailed(_))
+    ));
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+    assert!(bad.reveal_bytes().is_err());
+    assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,11 @@
+//! Round trips that CI runs both with and without `decrypt-noinline`, so
+//! both code layouts are built and exercised.
+
+use obfuse::obfuse;
+
+#[test]
+fn test_decrypt_under_either_layout() {
+    assert_eq!(obfuse!("out of line").as_str(), "out of line");
+    assert_eq!(obfuse!("layered", layers = 3).as_str(), "layered");
+    assert_eq!(obfuse!("").as_str(), "");
+}
@@ -0,0 +1,52 @@
+//! Tests that `drop_now` wipes the cached plaintext before freeing it.
+//!
+//! A wrapping global allocator inspects every deallocation of the plaintext's
+//! size while armed, recording whether the block was already zeroed.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use obfuse::obfuse;
+
+const PLAINTEXT: &str = "wipe me right now";
+
+struct WipeCheck;
+
+static ARMED: AtomicBool = AtomicBool::new(false);
+static WIPED: AtomicUsize = AtomicUsize::new(0);
+static DIRTY: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for WipeCheck {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if ARMED.load(Ordering::SeqCst) && layout.size() == PLAINTEXT.len() {
+            let block = unsafe { std::slice::from_raw_parts(ptr, layout.size()) };
+            let counter = if block.iter().all(|&b| b == 0) {
+                &WIPED
+            } else {
+                &DIRTY
+            };
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: WipeCheck = WipeCheck;
+
+#[test]
+fn test_drop_now_wipes_cache() {
+    let secret = obfuse!("wipe me right now");
+    assert_eq!(secret.as_str(), PLAINTEXT);
+
+    ARMED.store(true, Ordering::SeqCst);
+    secret.drop_now();
+    ARMED.store(false, Ordering::SeqCst);
+
+    assert_eq!(WIPED.load(Ordering::SeqCst), 1);
+    assert_eq!(DIRTY.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,38 @@
+//! Tests for `obfuse_eager!` and `warm_all`.
+
+#![cfg(feature = "eager")]
+
+use obfuse::{ObfuseStr, obfuse_eager};
+
+static MODULE_SECRET: &ObfuseStr = obfuse_eager!("module scope");
+
+fn api_key() -> &'static ObfuseStr {
+    obfuse_eager!("api key", seed = "eager")
+}
+
+fn never_called() -> &'static ObfuseStr {
+    obfuse_eager!("registered without running")
+}
+
+#[test]
+fn test_warm_all_decrypts_registered() {
+    assert!(!MODULE_SECRET.is_decrypted());
+    assert!(!api_key().is_decrypted());
+
+    obfuse::warm_all().unwrap();
+
+    assert!(MODULE_SECRET.is_decrypted());
+    assert!(api_key().is_decrypted());
+    assert!(never_called().is_decrypted());
+    assert_eq!(api_key().as_str(), "api key");
+
+    // Already-warm strings are skipped
+    obfuse::warm_all().unwrap();
+}
+
+#[test]
+fn test_preload() {
+    let secret = obfuse::obfuse!("preloaded");
+    assert_eq!(secret.preload().as_str(), "preloaded");
+    assert!(secret.is_decrypted());
+}
@@ -0,0 +1,79 @@
+//! Tests for `ObfuseStr::from_reader`.
+
+#![cfg(feature = "runtime-encrypt")]
+
+use std::io::{self, Cursor, Read};
+
+use obfuse::ObfuseStr;
+
+#[test]
+fn test_from_reader_round_trips() {
+    let secret = ObfuseStr::from_reader(&mut Cursor::new("piped-token")).unwrap();
+    assert!(!secret.is_decrypted());
+    assert_eq!(secret.as_str(), "piped-token");
+
+    let empty = ObfuseStr::from_reader(&mut io::empty()).unwrap();
+    assert_eq!(empty.as_str(), "");
+}
+
+#[test]
+fn test_from_reader_spans_many_reads() {
+    // Several buffer growths, and bytes that are not valid UTF-8
+    let input: Vec<u8> = (0..5000u32).map(|i| i.to_le_bytes()[0]).collect();
+    let secret = ObfuseStr::from_reader(&mut Cursor::new(&input)).unwrap();
+    assert_eq!(secret.try_as_bytes().unwrap(), input.as_slice());
+}
+
+/// Fails with `Interrupted` once, then with `kind`.
+struct Flaky {
+    interrupted: bool,
+    kind: io::ErrorKind,
+}
+
+impl Read for Flaky {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        if self.interrupted {
+            Err(io::Error::from(self.kind))
+        } else {
+            self.interrupted = true;
+            Err(io::Error::from(io::ErrorKind::Interrupted))
+        }
+    }
+}
+
+#[test]
+fn test_from_reader_propagates_errors() {
+    let mut reader = Flaky {
+        interrupted: false,
+        kind: io::ErrorKind::BrokenPipe,
+    };
+    let error = ObfuseStr::from_reader(&mut reader).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn test_replace_from_reader() {
+    let mut secret = ObfuseStr::from_reader(&mut Cursor::new("first")).unwrap();
+    assert_eq!(secret.as_str(), "first");
+
+    secret
+        .replace_from_reader(&mut Cursor::new("second"))
+        .unwrap();
+    assert!(!secret.is_decrypted());
+    assert_eq!(secret.as_str(), "second");
+
+    secret
+        .replace_from_reader(&mut Cursor::new("third, longer"))
+        .unwrap();
+    assert_eq!(secret.as_str(), "third, longer");
+}
+
+#[test]
+fn test_replace_from_reader_rejects_embedded() {
+    let mut secret = obfuse::obfuse!("compiled in");
+    let error = secret
+        .replace_from_reader(&mut Cursor::new("new"))
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+    assert_eq!(secret.as_str(), "compiled in");
+}
@@ -0,0 +1,55 @@
+//! Tests for `obfuse_inline!`.
+
+#![cfg(feature = "polymorphic")]
+
+use obfuse::obfuse_inline;
+
+#[test]
+fn test_inline_decodes_several_strings() {
+    assert_eq!(obfuse_inline!("").as_str(), "");
+    assert_eq!(obfuse_inline!("a").as_str(), "a");
+    assert_eq!(obfuse_inline!("inline secret").as_str(), "inline secret");
+    assert_eq!(
+        obfuse_inline!("Hello, 世界! 🌍").as_str(),
+        "Hello, 世界! 🌍"
+    );
+    assert_eq!(
+        obfuse_inline!(r#"{"token": "abc"}"#).as_str(),
+        r#"{"token": "abc"}"#
+    );
+
+    // Longer than the key, so the key wraps around
+    let long = "0123456789abcdef".repeat(5);
+    assert_eq!(
+        *obfuse_inline!(
+            "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"
+        ),
+        long
+    );
+}
+
+#[test]
+fn test_inline_accepts_key_options() {
+    assert_eq!(obfuse_inline!("seeded", seed = "inline").as_str(), "seeded");
+    assert_eq!(
+        obfuse_inline!("chacha8", seed = "inline", rng = "chacha8").as_str(),
+        "chacha8"
+    );
+    assert_eq!(
+        obfuse_inline!(
+            "pinned",
+            key = "4242424242424242424242424242424242424242424242424242424242424242",
+            nonce = "000102030405060708090a0b"
+        )
+        .as_str(),
+        "pinned"
+    );
+}
+
+#[test]
+fn test_inline_decodes_fresh_copy_per_evaluation() {
+    let copies: Vec<_> = (0..3).map(|_| obfuse_inline!("again")).collect();
+    for copy in &copies {
+        assert_eq!(copy.as_str(), "again");
+    }
+}
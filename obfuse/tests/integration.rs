@@ -1,6 +1,23 @@
 //! Integration tests for the obfuse library.
 
-use obfuse::{ObfuseStr, obfuse};
+use obfuse::{ObfuseStr, obfuse, obfuse_const, obfuse_once, obfuse_static};
+
+obfuse_const! {
+    /// Documented static secret.
+    static STATIC_SECRET = "static value";
+    static SEEDED_SECRET = "seeded value", seed = "static_seed";
+}
+
+obfuse_const! {
+    static DUPLICATE_A = "duplicated", seed = "dedup";
+    static DUPLICATE_B = "duplicated", seed = "dedup";
+}
+
+static MODULE_SECRET: &ObfuseStr = obfuse_static!("module scope");
+
+fn shared_secret() -> &'static ObfuseStr {
+    obfuse_static!("shared", seed = "static_seed")
+}
 
 #[test]
 fn test_basic_decryption() {
@@ -65,6 +82,30 @@ fn test_try_decrypt() {
     assert!(secret.is_decrypted());
 }
 
+#[test]
+fn test_decrypt_if() {
+    let secret = obfuse!("optional");
+    assert_eq!(secret.decrypt_if(|| false).unwrap(), None);
+    assert!(!secret.is_decrypted());
+
+    assert_eq!(secret.decrypt_if(|| true).unwrap(), Some("optional"));
+    assert!(secret.is_decrypted());
+}
+
+#[test]
+fn test_needs_decryption_cost() {
+    let short = obfuse!("short");
+    // Longer by more than the up-to-62 junk bytes `ciphertext-padding` adds
+    let long = obfuse!(
+        "a considerably longer secret value, long enough that its ciphertext outgrows any padding"
+    );
+    assert!(long.needs_decryption_cost() > short.needs_decryption_cost());
+    assert!(short.needs_decryption_cost() > 0);
+
+    long.try_decrypt().unwrap();
+    assert_eq!(long.needs_decryption_cost(), 0);
+}
+
 #[test]
 fn test_as_bytes() {
     let secret = obfuse!("hello");
@@ -98,6 +139,25 @@ fn test_deref() {
     assert_eq!(upper, "HELLO");
 }
 
+#[test]
+fn test_content_predicates() {
+    let ascii = obfuse!("sk_live_1234");
+    assert!(ascii.is_ascii().unwrap());
+    assert!(!ascii.is_empty().unwrap());
+    assert_eq!(ascii.char_count().unwrap(), 12);
+    assert!(ascii.is_decrypted());
+
+    let unicode = obfuse!("clé-秘密");
+    assert!(!unicode.is_ascii().unwrap());
+    assert_eq!(unicode.char_count().unwrap(), 6);
+    assert_eq!(unicode.len(), 11);
+
+    let empty = obfuse!("");
+    assert!(empty.is_empty().unwrap());
+    assert!(empty.is_ascii().unwrap());
+    assert_eq!(empty.char_count().unwrap(), 0);
+}
+
 #[test]
 fn test_as_ref_str() {
     let secret = obfuse!("test");
@@ -112,6 +172,335 @@ fn test_as_ref_bytes() {
     assert_eq!(b, b"test");
 }
 
+#[test]
+fn test_as_os_str() {
+    let secret = obfuse!("secret.txt");
+    assert_eq!(secret.as_os_str(), std::ffi::OsStr::new("secret.txt"));
+    assert_eq!(secret.try_as_os_str().unwrap(), "secret.txt");
+}
+
+#[test]
+fn test_as_path() {
+    let secret = obfuse!("config/secret.toml");
+    let path = secret.try_as_path().unwrap();
+    assert_eq!(path, std::path::Path::new("config/secret.toml"));
+    assert_eq!(path.file_name().unwrap(), "secret.toml");
+}
+
+#[test]
+fn test_as_path_with_fs() {
+    let secret = obfuse!(".");
+    let canonical = std::fs::canonicalize(secret.as_path()).unwrap();
+    let expected = std::env::current_dir().unwrap().canonicalize().unwrap();
+    assert_eq!(canonical, expected);
+}
+
+#[test]
+fn test_as_c_str() {
+    let secret = obfuse!("c secret\0");
+    let c_str = secret.try_as_c_str().unwrap();
+    assert_eq!(c_str.to_bytes(), b"c secret");
+    assert_eq!(c_str.to_str().unwrap(), "c secret");
+    assert_eq!(secret.as_c_str(), c"c secret");
+}
+
+#[test]
+fn test_as_c_str_missing_nul() {
+    use obfuse::ObfuseError;
+
+    let secret = obfuse!("no terminator");
+    let err = secret.try_as_c_str().unwrap_err();
+    assert!(matches!(err, ObfuseError::InteriorNul(_)));
+}
+
+#[test]
+fn test_as_c_str_interior_nul() {
+    use obfuse::ObfuseError;
+    use std::error::Error;
+
+    let secret = obfuse!("inter\0ior\0");
+    let err = secret.try_as_c_str().unwrap_err();
+    assert!(matches!(err, ObfuseError::InteriorNul(_)));
+    assert!(err.source().is_some());
+
+    // The plaintext itself is still accessible
+    assert_eq!(secret.as_str(), "inter\0ior\0");
+}
+
+#[test]
+fn test_with_c_str() {
+    use obfuse::ObfuseError;
+
+    let unterminated = obfuse!("c secret");
+    let bytes = unterminated.with_c_str(|s| s.to_bytes().to_vec()).unwrap();
+    assert_eq!(bytes, b"c secret");
+    assert!(!unterminated.is_decrypted());
+
+    let terminated = obfuse!("c secret\0");
+    assert!(terminated.with_c_str(|s| s == c"c secret").unwrap());
+
+    let interior = obfuse!("inter\0ior");
+    let err = interior.with_c_str(|_| ()).unwrap_err();
+    assert!(matches!(err, ObfuseError::InteriorNul(_)));
+}
+
+#[test]
+fn test_c_string_literal() {
+    let secret = obfuse!(c"c literal");
+    assert_eq!(secret.as_c_str(), c"c literal");
+    assert_eq!(secret.as_bytes(), b"c literal\0");
+
+    // Arbitrary bytes, not just UTF-8
+    let bytes = obfuse!(c"caf\xe9", seed = "c_string");
+    assert_eq!(bytes.as_c_str().to_bytes(), b"caf\xe9");
+    assert!(bytes.try_as_str().is_err());
+
+    let raw = obfuse!(cr#"quote " and \n"#);
+    assert_eq!(raw.as_c_str(), cr#"quote " and \n"#);
+}
+
+#[test]
+fn test_raw_string_literal() {
+    let secret = obfuse!(r#"{"key": "C:\path\to\file", "escaped": "\""}"#);
+    assert_eq!(
+        secret.as_str(),
+        r#"{"key": "C:\path\to\file", "escaped": "\""}"#
+    );
+
+    let hashes = obfuse!(r##"contains "# inside"##, seed = "raw");
+    assert_eq!(hashes.as_str(), r##"contains "# inside"##);
+}
+
+#[test]
+fn test_reveal() {
+    let secret = obfuse!("revealed");
+    {
+        let revealed = secret.reveal();
+        assert_eq!(&*revealed, "revealed");
+        assert_eq!(revealed.as_bytes(), b"revealed");
+    }
+
+    // Revealing does not populate the cache
+    assert!(!secret.is_decrypted());
+}
+
+#[test]
+fn test_try_reveal() {
+    let secret = obfuse!("fallible");
+    let revealed = secret.try_reveal().unwrap();
+    assert_eq!(revealed.as_str(), "fallible");
+    assert!(!format!("{revealed:?}").contains("fallible"));
+}
+
+#[test]
+fn test_decrypt_append() {
+    let user = obfuse!("admin");
+    let pass = obfuse!("hunter2");
+
+    let mut credentials = Vec::new();
+    user.decrypt_append(&mut credentials).unwrap();
+    credentials.push(b':');
+    pass.decrypt_append(&mut credentials).unwrap();
+
+    assert_eq!(credentials, b"admin:hunter2");
+    assert!(!user.is_decrypted());
+    assert!(!pass.is_decrypted());
+}
+
+#[test]
+fn test_try_into_fixed() {
+    use obfuse::ObfuseError;
+
+    let key = obfuse!("0123456789abcdef0123456789abcdef");
+    let fixed = zeroize::Zeroizing::new(key.try_into_fixed::<32>().unwrap());
+    assert_eq!(&*fixed, b"0123456789abcdef0123456789abcdef");
+    assert!(!key.is_decrypted());
+
+    assert!(matches!(
+        key.try_into_fixed::<16>(),
+        Err(ObfuseError::WrongLength {
+            expected: 16,
+            actual: 32
+        })
+    ));
+    assert!(matches!(
+        obfuse!("").try_into_fixed::<1>(),
+        Err(ObfuseError::WrongLength {
+            expected: 1,
+            actual: 0
+        })
+    ));
+    assert_eq!(obfuse!("").try_into_fixed::<0>().unwrap(), []);
+}
+
+#[test]
+fn test_reveal_to_env() {
+    use std::ffi::OsStr;
+    use std::process::Command;
+
+    let secret = obfuse!("child-only secret");
+    let mut command = Command::new("env");
+    secret
+        .reveal_to_env(&mut command, "OBFUSE_CHILD_SECRET")
+        .unwrap();
+
+    let value = command
+        .get_envs()
+        .find(|(name, _)| *name == "OBFUSE_CHILD_SECRET")
+        .and_then(|(_, value)| value);
+    assert_eq!(value, Some(OsStr::new("child-only secret")));
+    assert!(std::env::var_os("OBFUSE_CHILD_SECRET").is_none());
+    assert!(!secret.is_decrypted());
+
+    #[cfg(unix)]
+    {
+        let output = command.output().unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(
+            stdout
+                .lines()
+                .any(|line| line == "OBFUSE_CHILD_SECRET=child-only secret")
+        );
+    }
+}
+
+#[test]
+fn test_xor_with() {
+    use obfuse::ObfuseError;
+
+    let secret = obfuse!("split me");
+    let mask = [0x5a, 0x01, 0xff, 0x00, 0x80, 0x7e, 0x33, 0xc4];
+
+    let share = secret.xor_with(&mask).unwrap();
+    assert_ne!(share.as_slice(), b"split me");
+    assert!(!secret.is_decrypted());
+
+    let recombined: Vec<u8> = share.iter().zip(&mask).map(|(s, m)| s ^ m).collect();
+    assert_eq!(recombined, b"split me");
+
+    assert!(matches!(
+        secret.xor_with(&mask[..7]),
+        Err(ObfuseError::WrongLength {
+            expected: 7,
+            actual: 8
+        })
+    ));
+}
+
+#[test]
+fn test_for_each_token() {
+    let list = obfuse!("a b  c");
+    let mut tokens = Vec::new();
+    list.for_each_token(|token| tokens.push(token.to_owned()))
+        .unwrap();
+    assert_eq!(tokens, ["a", "b", "c"]);
+    assert!(!list.is_decrypted());
+
+    let mut count = 0;
+    obfuse!(" \t\n ").for_each_token(|_| count += 1).unwrap();
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test_split_at_secret() {
+    let secret = obfuse!("user:pass:word");
+    let (user, pass) = secret.split_at_secret(':').unwrap().unwrap();
+
+    assert_eq!(&*user, "user");
+    assert_eq!(&*pass, "pass:word");
+    assert!(!secret.is_decrypted());
+
+    assert!(secret.split_at_secret('@').unwrap().is_none());
+}
+
+#[test]
+fn test_redacted_preview() {
+    let long = obfuse!("supersecretstring");
+    assert_eq!(long.redacted_preview(1).unwrap(), "s***************g");
+    assert_eq!(long.redacted_preview(3).unwrap(), "sup***********ing");
+    assert!(!long.is_decrypted());
+
+    // Short secrets and `keep = 0` are masked entirely
+    let short = obfuse!("abcd");
+    assert_eq!(short.redacted_preview(2).unwrap(), "****");
+    assert_eq!(short.redacted_preview(0).unwrap(), "****");
+    assert_eq!(obfuse!("").redacted_preview(1).unwrap(), "");
+
+    // Characters, not bytes
+    let unicode = obfuse!("密码是秘密的");
+    assert_eq!(unicode.redacted_preview(1).unwrap(), "密****的");
+}
+
+#[test]
+fn test_once_take_consumes() {
+    let token = obfuse_once!("setup-token-123");
+    assert_eq!(
+        format!("{token:?}"),
+        r#"ObfuseOnceStr { value: "[REDACTED]" }"#
+    );
+    assert_eq!(token.take().unwrap(), "setup-token-123");
+
+    let layered = obfuse_once!("layered once", layers = 2);
+    assert_eq!(layered.take().unwrap(), "layered once");
+}
+
+#[test]
+fn test_render_substitutes_placeholders() {
+    let template = obfuse!("user {user} logged in from {ip}");
+    let rendered = template
+        .render(&[("ip", "10.0.0.1"), ("user", "alice")])
+        .unwrap();
+    assert_eq!(rendered, "user alice logged in from 10.0.0.1");
+    assert!(!template.is_decrypted());
+
+    // Repeated placeholders and values containing braces
+    let repeated = obfuse!("{x}-{x}");
+    assert_eq!(repeated.render(&[("x", "{y}")]).unwrap(), "{y}-{y}");
+}
+
+#[test]
+fn test_render_escapes_braces() {
+    let template = obfuse!("{{literal}} {name} }}{{");
+    assert_eq!(template.render(&[("name", "n")]).unwrap(), "{literal} n }{");
+}
+
+#[test]
+fn test_render_keeps_unmatched_placeholders() {
+    let template = obfuse!("hello {name}, {missing} {unclosed");
+    assert_eq!(
+        template.render(&[("name", "bob")]).unwrap(),
+        "hello bob, {missing} {unclosed"
+    );
+    assert_eq!(obfuse!("a } b {").render(&[]).unwrap(), "a } b {");
+    assert_eq!(obfuse!("").render(&[("x", "y")]).unwrap(), "");
+}
+
+#[test]
+// The interior caches never change the plaintext, so the hash is stable
+#[allow(clippy::mutable_key_type)]
+fn test_hash_is_cached_and_consistent() {
+    use std::collections::HashMap;
+    use std::hash::{BuildHasher, RandomState};
+
+    let state = RandomState::new();
+    let secret = obfuse!("map key");
+    let first = state.hash_one(&secret);
+    assert!(secret.is_decrypted());
+    assert_eq!(state.hash_one(&secret), first);
+
+    // Equal plaintexts under different keys hash and compare equal
+    let same = obfuse!("map key", seed = "other");
+    assert_eq!(state.hash_one(&same), first);
+    assert!(secret == same);
+    assert!(secret != obfuse!("other key"));
+
+    let mut map = HashMap::new();
+    map.insert(secret, 1);
+    assert_eq!(map.get(&same), Some(&1));
+}
+
+// `transport-safe` redacts Display; see tests/transport_safe.rs
+#[cfg(not(feature = "transport-safe"))]
 #[test]
 fn test_display() {
     let secret = obfuse!("displayable");
@@ -149,6 +538,32 @@ fn test_deterministic_different_seeds() {
     assert_eq!(secret1.as_str(), secret2.as_str());
 }
 
+#[test]
+fn test_seed_rng_choice() {
+    let chacha8_a = obfuse!("rng choice", seed = "rng", rng = "chacha8");
+    let chacha8_b = obfuse!("rng choice", seed = "rng", rng = "chacha8");
+    let chacha20 = obfuse!("rng choice", seed = "rng", rng = "chacha20");
+    let default = obfuse!("rng choice", seed = "rng");
+
+    assert_eq!(chacha8_a.as_str(), "rng choice");
+    assert_eq!(chacha8_a.ciphertext(), chacha8_b.ciphertext());
+    assert_ne!(chacha8_a.ciphertext(), chacha20.ciphertext());
+    assert_eq!(chacha20.ciphertext(), default.ciphertext());
+}
+
+#[test]
+fn test_seed_kdf_choice() {
+    let v2_a = obfuse!("kdf choice", seed = "kdf", seed_kdf = "v2");
+    let v2_b = obfuse!("kdf choice", seed = "kdf", seed_kdf = "v2");
+    let v1 = obfuse!("kdf choice", seed = "kdf", seed_kdf = "v1");
+    let default = obfuse!("kdf choice", seed = "kdf");
+
+    assert_eq!(v2_a.as_str(), "kdf choice");
+    assert_eq!(v2_a.ciphertext(), v2_b.ciphertext());
+    assert_ne!(v2_a.ciphertext(), v1.ciphertext());
+    assert_eq!(v1.ciphertext(), default.ciphertext());
+}
+
 #[test]
 fn test_type_annotation() {
     let secret: ObfuseStr = obfuse!("typed");
@@ -191,3 +606,336 @@ fn test_concurrent_access() {
         handle.join().unwrap();
     }
 }
+
+#[test]
+fn test_obfuse_const() {
+    assert_eq!(STATIC_SECRET.as_str(), "static value");
+    assert!(STATIC_SECRET.is_decrypted());
+    assert_eq!(SEEDED_SECRET.as_str(), "seeded value");
+}
+
+#[test]
+fn test_obfuse_static_module_scope() {
+    assert_eq!(MODULE_SECRET.as_str(), "module scope");
+}
+
+#[test]
+fn test_obfuse_static_shared_across_calls() {
+    let first = shared_secret();
+    assert_eq!(first.as_str(), "shared");
+
+    // Every call returns the same, already-decrypted instance
+    let second = shared_secret();
+    assert!(std::ptr::eq(first, second));
+    assert!(second.is_decrypted());
+}
+
+#[test]
+fn test_obfuse_static_from_threads() {
+    let handles: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(|| shared_secret().as_str().len()))
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), "shared".len());
+    }
+}
+
+#[cfg(feature = "aes-256-gcm")]
+#[test]
+fn test_tampered_ciphertext_has_source() {
+    use obfuse::ObfuseError;
+    use std::error::Error;
+
+    // Valid AES-256-GCM payload with the first ciphertext byte flipped
+    let secret = obfuse!(
+        hex = "3670b336a08ba169f431e3eed5c90b08e0a4e2404e419b946bf965bf95de5ed5e51fbace",
+        key = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        nonce = "000102030405060708090a0b",
+    );
+
+    let err = secret.try_as_str().unwrap_err();
+    assert!(matches!(err, ObfuseError::AuthenticationFailed(_)));
+    assert!(err.source().is_some());
+    assert_eq!(
+        err.to_string(),
+        "authentication failed - ciphertext may be corrupted"
+    );
+}
+
+#[cfg(all(
+    feature = "aes-256-gcm",
+    not(any(feature = "multi-aes", feature = "streaming"))
+))]
+#[test]
+fn test_as_str_lossy() {
+    // AES-256-GCM payload decrypting to ff fe 00 80 c3 28 01 7f
+    let invalid = obfuse!(
+        hex = "b8fcd69b06cdc36454cd1cee814e38a11c034490d794901f",
+        key = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        nonce = "000102030405060708090a0b",
+    );
+
+    assert!(invalid.try_as_str().is_err());
+    assert_eq!(
+        invalid.as_str_lossy().unwrap(),
+        "\u{fffd}\u{fffd}\0\u{fffd}\u{fffd}(\u{1}\u{7f}"
+    );
+
+    let valid = obfuse!("plain");
+    assert!(matches!(
+        valid.as_str_lossy().unwrap(),
+        std::borrow::Cow::Borrowed("plain")
+    ));
+}
+
+#[test]
+fn test_obfuse_const_dedups_ciphertext() {
+    assert_eq!(DUPLICATE_A.as_str(), "duplicated");
+    assert_eq!(DUPLICATE_B.as_str(), "duplicated");
+    assert!(std::ptr::eq(
+        DUPLICATE_A.ciphertext(),
+        DUPLICATE_B.ciphertext()
+    ));
+}
+
+#[test]
+fn test_parse() {
+    let port = obfuse!("8080");
+    let port: u16 = port.parse().unwrap();
+    assert_eq!(port, 8080);
+}
+
+#[test]
+fn test_parse_invalid() {
+    use obfuse::ParseSecretError;
+
+    let secret = obfuse!("not a port");
+    let err = secret.parse::<u16>().unwrap_err();
+    assert!(matches!(err, ParseSecretError::Parse(_)));
+    assert!(
+        err.to_string()
+            .starts_with("failed to parse decrypted value")
+    );
+
+    // Parsing does not populate the cache
+    assert!(!secret.is_decrypted());
+}
+
+#[test]
+fn test_write_to() {
+    let secret = obfuse!("token");
+
+    let mut redacted = String::from("auth=");
+    secret.write_to(&mut redacted, false).unwrap();
+    assert_eq!(redacted, "auth=[REDACTED]");
+
+    let mut revealed = String::from("auth=");
+    secret.write_to(&mut revealed, true).unwrap();
+    assert_eq!(revealed, "auth=token");
+
+    // Writing does not populate the cache
+    assert!(!secret.is_decrypted());
+}
+
+#[test]
+fn test_bytes_eq() {
+    let token = obfuse!("\u{1}\u{2}binary\u{7f}");
+
+    assert!(token.bytes_eq(b"\x01\x02binary\x7f"));
+    assert!(token.try_bytes_eq(b"\x01\x02binary\x7f").unwrap());
+    assert!(!token.bytes_eq(b"\x01\x02binarz\x7f"));
+
+    // Length mismatches are unequal, whether shorter or longer
+    assert!(!token.bytes_eq(b"\x01\x02binary"));
+    assert!(!token.bytes_eq(b"\x01\x02binary\x7f\x00"));
+    assert!(!token.bytes_eq(b""));
+
+    // Comparing does not populate the cache
+    assert!(!token.is_decrypted());
+}
+
+#[test]
+fn test_secret_trait_objects() {
+    use obfuse::{ObfuseError, Secret};
+
+    /// A second secret type, standing in for one from another crate.
+    struct Pin([u8; 4]);
+
+    impl std::fmt::Debug for Pin {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("Pin([REDACTED])")
+        }
+    }
+
+    impl Secret for Pin {
+        fn reveal_scoped(&self, f: &mut dyn FnMut(&[u8])) -> Result<(), ObfuseError> {
+            f(&self.0);
+            Ok(())
+        }
+
+        fn algorithm(&self) -> &'static str {
+            "none"
+        }
+    }
+
+    let store: Vec<Box<dyn Secret>> = vec![Box::new(obfuse!("api token")), Box::new(Pin(*b"1234"))];
+
+    let mut revealed = Vec::new();
+    for secret in &store {
+        secret
+            .reveal_scoped(&mut |bytes| revealed.push(bytes.to_vec()))
+            .unwrap();
+    }
+    assert_eq!(revealed, [b"api token".to_vec(), b"1234".to_vec()]);
+
+    assert_eq!(store[0].algorithm(), obfuse_core::ALGORITHM);
+    assert_eq!(store[1].algorithm(), "none");
+    assert!(!format!("{store:?}").contains("api token"));
+}
+
+#[test]
+fn test_decrypt_aligned() {
+    let secret = obfuse!("sixteen byte key and then some");
+
+    let aligned = secret.decrypt_aligned::<16>().unwrap();
+    assert_eq!(&*aligned, b"sixteen byte key and then some");
+    assert_eq!(aligned.as_ptr().addr() % 16, 0);
+
+    let wide = secret.decrypt_aligned::<64>().unwrap();
+    assert_eq!(wide.as_ptr().addr() % 64, 0);
+    assert_eq!(wide.len(), 30);
+    assert!(!format!("{wide:?}").contains("sixteen"));
+
+    let empty = obfuse!("");
+    let empty = empty.decrypt_aligned::<32>().unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.as_ptr().addr() % 32, 0);
+
+    assert!(!secret.is_decrypted());
+}
+
+#[test]
+fn test_decrypt_cached_arc() {
+    let secret = obfuse!("shared across threads");
+
+    let shared = secret.decrypt_cached_arc().unwrap();
+    assert!(!secret.is_decrypted());
+    assert!(std::sync::Arc::ptr_eq(
+        &shared,
+        &secret.decrypt_cached_arc().unwrap()
+    ));
+
+    let clone = std::sync::Arc::clone(&shared);
+    let len = std::thread::spawn(move || {
+        assert_eq!(&*clone, "shared across threads");
+        clone.len()
+    })
+    .join()
+    .unwrap();
+    assert_eq!(len, 21);
+
+    // The Arc outlives the ObfuseStr
+    drop(secret);
+    assert_eq!(&*shared, "shared across threads");
+}
+
+#[test]
+fn test_checksum() {
+    let secret = obfuse!("db password");
+    let checksum = secret.checksum().unwrap();
+    assert_eq!(secret.checksum().unwrap(), checksum);
+
+    // Equal plaintexts under different keys agree; a changed value differs
+    assert_eq!(
+        obfuse!("db password", seed = "reloaded")
+            .checksum()
+            .unwrap(),
+        checksum
+    );
+    assert_ne!(obfuse!("db passw0rd").checksum().unwrap(), checksum);
+
+    // Checksumming does not populate the cache
+    assert!(!secret.is_decrypted());
+}
+
+#[test]
+fn test_timing_safe_contains_any() {
+    let token = obfuse!("role:admin");
+
+    assert!(
+        token
+            .timing_safe_contains_any(&["role:user", "role:admin", "role:ops"])
+            .unwrap()
+    );
+    assert_eq!(
+        token
+            .timing_safe_position(&["role:user", "role:admin", "role:ops"])
+            .unwrap(),
+        Some(1)
+    );
+
+    assert!(
+        !token
+            .timing_safe_contains_any(&["role:user", "role:admi", "role:admin!"])
+            .unwrap()
+    );
+    assert_eq!(token.timing_safe_position(&["role:user"]).unwrap(), None);
+
+    assert!(!token.timing_safe_contains_any(&[]).unwrap());
+    assert_eq!(token.timing_safe_position(&[]).unwrap(), None);
+
+    assert!(!token.is_decrypted());
+}
+
+#[test]
+fn test_as_str_timed() {
+    use std::time::Duration;
+
+    let secret = obfuse!("timed");
+
+    let (value, first) = secret.as_str_timed().unwrap();
+    assert_eq!(value, "timed");
+    assert!(first > Duration::ZERO);
+
+    // Cached access does no decryption work
+    let (value, second) = secret.as_str_timed().unwrap();
+    assert_eq!(value, "timed");
+    assert_eq!(second, Duration::ZERO);
+}
+
+#[test]
+fn test_decrypt_with_budget() {
+    use obfuse::ObfuseError;
+    use std::time::{Duration, Instant};
+
+    let secret = obfuse!("budgeted");
+    let expired = Instant::now();
+
+    assert!(matches!(
+        secret.decrypt_with_budget(expired),
+        Err(ObfuseError::TimedOut)
+    ));
+    assert!(!secret.is_decrypted());
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    assert_eq!(secret.decrypt_with_budget(deadline).unwrap(), "budgeted");
+
+    // A cached plaintext is returned even past the deadline
+    assert_eq!(secret.decrypt_with_budget(expired).unwrap(), "budgeted");
+}
+
+#[test]
+fn test_leak_outlives_the_secret() {
+    static LEAKED: std::sync::OnceLock<&'static str> = std::sync::OnceLock::new();
+
+    let leaked = obfuse!("forever").leak().unwrap();
+    LEAKED.set(leaked).unwrap();
+    assert_eq!(LEAKED.get().copied(), Some("forever"));
+
+    // An already cached plaintext is moved out and leaked as well
+    let cached = obfuse!("cached forever");
+    assert_eq!(cached.as_str(), "cached forever");
+    let leaked: &'static str = cached.leak().unwrap();
+    assert_eq!(leaked, "cached forever");
+}
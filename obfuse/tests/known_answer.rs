@@ -0,0 +1,111 @@
+//! Known-answer tests: pinned key and nonce against the GCM reference vectors.
+//!
+//! Vectors are test cases 2 (AES-128) and 13-14 (AES-256) from McGrew &
+//! Viega, "The Galois/Counter Mode of Operation (GCM)".
+//!
+//! `permute-ciphertext` stores the bytes shuffled (see `permute.rs`) and
+//! `ciphertext-padding` surrounds them with junk (see `padding.rs`). The
+//! vectors are one-shot GCM, which `streaming` builds do not accept.
+
+#![cfg(not(any(
+    feature = "permute-ciphertext",
+    feature = "ciphertext-padding",
+    feature = "streaming"
+)))]
+
+#[cfg(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+))]
+use obfuse::obfuse;
+
+/// Decodes a hex string, prefixing the `multi-aes` key-size tag when needed.
+#[cfg(feature = "aes-256-gcm")]
+#[cfg_attr(not(feature = "multi-aes"), allow(unused_variables))]
+fn expected(key_size: u8, hex: &str) -> Vec<u8> {
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap());
+
+    #[cfg(feature = "multi-aes")]
+    return std::iter::once(key_size).chain(bytes).collect();
+
+    #[cfg(not(feature = "multi-aes"))]
+    bytes.collect()
+}
+
+#[cfg(feature = "aes-256-gcm")]
+#[test]
+fn test_aes256_empty_plaintext() {
+    let secret = obfuse!(
+        "",
+        key = "0000000000000000000000000000000000000000000000000000000000000000",
+        nonce = "000000000000000000000000",
+    );
+
+    assert_eq!(
+        secret.ciphertext(),
+        expected(32, "530f8afbc74536b9a963b4f1c4cb738b")
+    );
+    assert_eq!(secret.as_str(), "");
+}
+
+#[cfg(feature = "aes-256-gcm")]
+#[test]
+fn test_aes256_zero_block() {
+    let secret = obfuse!(
+        "\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+        key = "0000000000000000000000000000000000000000000000000000000000000000",
+        nonce = "000000000000000000000000",
+    );
+
+    assert_eq!(
+        secret.ciphertext(),
+        expected(
+            32,
+            "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919"
+        )
+    );
+    assert_eq!(secret.as_bytes(), [0; 16]);
+}
+
+#[cfg(feature = "multi-aes")]
+#[test]
+fn test_aes128_zero_block() {
+    let secret = obfuse!(
+        "\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+        aes = 128,
+        key = "00000000000000000000000000000000",
+        nonce = "000000000000000000000000",
+    );
+
+    assert_eq!(
+        secret.ciphertext(),
+        expected(
+            16,
+            "0388dace60b6a392f328c2b971b2fe78ab6e47d42cec13bdf53a67b21257bddf"
+        )
+    );
+    assert_eq!(secret.as_bytes(), [0; 16]);
+}
+
+// XOR ignores the nonce
+#[cfg(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+))]
+#[test]
+fn test_pinned_nonce_with_seed() {
+    // Only the nonce is pinned; the key still comes from the seed
+    let a = obfuse!("pinned", seed = "kat", nonce = "0102030405060708090a0b0c");
+    let b = obfuse!("pinned", seed = "kat", nonce = "0102030405060708090a0b0c");
+    let c = obfuse!("pinned", seed = "kat", nonce = "0c0b0a090807060504030201");
+
+    assert_eq!(a.ciphertext(), b.ciphertext());
+    assert_ne!(a.ciphertext(), c.ciphertext());
+    assert_eq!(c.as_str(), "pinned");
+}
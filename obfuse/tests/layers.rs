@@ -0,0 +1,58 @@
+//! Round-trip tests for onion encryption (`layers = N`).
+
+use obfuse::{obfuse, obfuse_const};
+
+obfuse_const! {
+    static LAYERED = "const layered", layers = 4;
+}
+
+#[test]
+fn test_every_layer_count_round_trips() {
+    let secrets = [
+        obfuse!("onion", layers = 1),
+        obfuse!("onion", layers = 2),
+        obfuse!("onion", layers = 3),
+        obfuse!("onion", layers = 4),
+        obfuse!("onion", layers = 5),
+        obfuse!("onion", layers = 6),
+        obfuse!("onion", layers = 7),
+        obfuse!("onion", layers = 8),
+    ];
+
+    for secret in &secrets {
+        assert_eq!(secret.as_str(), "onion");
+        assert_eq!(&*secret.reveal(), "onion");
+    }
+
+    // Each outer layer wraps the previous key and nonce; padding junk can
+    // outweigh one layer's growth, so only unpadded lengths are ordered
+    #[cfg(not(feature = "ciphertext-padding"))]
+    let lengths: Vec<_> = secrets.iter().map(|s| s.ciphertext().len()).collect();
+    #[cfg(not(feature = "ciphertext-padding"))]
+    assert!(lengths.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn test_layered_empty_and_unicode() {
+    assert_eq!(obfuse!("", layers = 3).as_str(), "");
+    assert_eq!(
+        obfuse!("Hello, 世界! 🌍", layers = 8).as_str(),
+        "Hello, 世界! 🌍"
+    );
+}
+
+#[test]
+fn test_seeded_layers_are_deterministic() {
+    let a = obfuse!("onion", seed = "layers", layers = 3);
+    let b = obfuse!("onion", seed = "layers", layers = 3);
+    let single = obfuse!("onion", seed = "layers");
+
+    assert_eq!(a.ciphertext(), b.ciphertext());
+    assert_ne!(a.ciphertext(), single.ciphertext());
+    assert_eq!(a.as_str(), "onion");
+}
+
+#[test]
+fn test_obfuse_const_layers() {
+    assert_eq!(LAYERED.as_str(), "const layered");
+}
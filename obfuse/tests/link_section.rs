@@ -0,0 +1,64 @@
+//! Tests that the `section` option places ciphertext in a named linker section.
+//!
+//! Inspects the section headers of the test binary itself, so this only runs
+//! on 64-bit little-endian ELF targets.
+
+#![cfg(all(
+    target_os = "linux",
+    target_pointer_width = "64",
+    target_endian = "little"
+))]
+
+use obfuse::{ObfuseStr, obfuse, obfuse_const, obfuse_static};
+
+obfuse_const! {
+    static CONST_BLOB = "const payload", section = ".obft_const";
+}
+
+static STATIC_BLOB: &ObfuseStr = obfuse_static!("static payload", section = ".obft_static");
+
+/// Returns the names of all sections in the running executable.
+fn section_names() -> Vec<String> {
+    let elf = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let read_u16 = |at: usize| usize::from(u16::from_le_bytes([elf[at], elf[at + 1]]));
+    let read_u32 = |at: usize| u32::from_le_bytes(elf[at..at + 4].try_into().unwrap()) as usize;
+    let read_u64 = |at: usize| {
+        usize::try_from(u64::from_le_bytes(elf[at..at + 8].try_into().unwrap())).unwrap()
+    };
+
+    let header_offset = read_u64(0x28);
+    let header_size = read_u16(0x3a);
+    let header_count = read_u16(0x3c);
+    let string_table = header_offset + read_u16(0x3e) * header_size;
+    let string_offset = read_u64(string_table + 0x18);
+
+    (0..header_count)
+        .map(|i| {
+            let start = string_offset + read_u32(header_offset + i * header_size);
+            let end = start + elf[start..].iter().position(|&b| b == 0).unwrap();
+            String::from_utf8_lossy(&elf[start..end]).into_owned()
+        })
+        .collect()
+}
+
+#[test]
+fn test_section_present() {
+    // Decrypting also keeps the statics from being optimized out
+    assert_eq!(CONST_BLOB.as_str(), "const payload");
+    assert_eq!(STATIC_BLOB.as_str(), "static payload");
+
+    let local = obfuse!("local payload", section = ".obft_local");
+    assert_eq!(local.as_str(), "local payload");
+
+    let names = section_names();
+    for expected in [".obft_const", ".obft_static", ".obft_local"] {
+        assert!(names.iter().any(|n| n == expected), "missing {expected}");
+    }
+}
+
+#[test]
+fn test_default_section() {
+    let secret = obfuse!("default section", section, seed = "section_seed");
+    assert_eq!(secret.as_str(), "default section");
+    assert!(section_names().iter().any(|n| n == ".obfuse"));
+}
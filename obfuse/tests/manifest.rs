@@ -0,0 +1,49 @@
+//! Tests for the ciphertext tamper-detection manifest.
+
+#![cfg(feature = "manifest")]
+
+use obfuse::{Manifest, ObfuseStr, manifest_hmac, obfuse, obfuse_const};
+
+obfuse_const! {
+    static FIRST = "first secret";
+    static SECOND = "second secret", seed = "manifest_seed";
+    manifest SECRETS;
+}
+
+static ORIGINAL: ObfuseStr = obfuse!("patch me", seed = "patch_seed");
+static SWAPPED: ObfuseStr = obfuse!("patch me", seed = "other_seed");
+static ORIGINAL_SET: [&ObfuseStr; 1] = [&ORIGINAL];
+static SWAPPED_SET: [&ObfuseStr; 1] = [&SWAPPED];
+
+#[test]
+fn test_manifest_verifies() {
+    assert!(SECRETS.verify());
+    assert_eq!(&SECRETS.compute(), SECRETS.expected());
+
+    // Verification works on ciphertext only
+    assert!(!FIRST.is_decrypted());
+    assert_eq!(SECOND.as_str(), "second secret");
+}
+
+#[test]
+fn test_manifest_detects_patched_byte() {
+    let key = [7u8; 32];
+
+    let expected = manifest_hmac(&key, [ORIGINAL.ciphertext()]);
+    assert!(Manifest::new(&ORIGINAL_SET, key, expected).verify());
+
+    // Live ciphertext differs from the one the manifest was built over
+    let mut patched = ORIGINAL.ciphertext().to_vec();
+    patched[0] ^= 0x01;
+    let expected = manifest_hmac(&key, [patched.as_slice()]);
+    assert!(!Manifest::new(&ORIGINAL_SET, key, expected).verify());
+}
+
+#[test]
+fn test_manifest_detects_swapped_ciphertext() {
+    let key = [7u8; 32];
+    let expected = manifest_hmac(&key, [ORIGINAL.ciphertext()]);
+
+    assert_eq!(SWAPPED.as_str(), ORIGINAL.as_str());
+    assert!(!Manifest::new(&SWAPPED_SET, key, expected).verify());
+}
@@ -0,0 +1,70 @@
+//! Tests for the `miette` diagnostic codes.
+
+#![cfg(feature = "miette")]
+
+use std::ffi::CStr;
+
+use miette::Diagnostic;
+use obfuse::ObfuseError;
+
+fn code(error: &ObfuseError) -> String {
+    error.code().expect("every variant has a code").to_string()
+}
+
+#[test]
+fn test_every_variant_has_code_and_help() {
+    let cases = [
+        (ObfuseError::AllocationFailed, "obfuse::alloc_failed"),
+        (
+            ObfuseError::AuthenticationFailed(Box::new(std::fmt::Error)),
+            "obfuse::auth_failed",
+        ),
+        (
+            ObfuseError::from(String::from_utf8(vec![0xff]).unwrap_err().utf8_error()),
+            "obfuse::invalid_utf8",
+        ),
+        (
+            ObfuseError::from(CStr::from_bytes_with_nul(b"no nul").unwrap_err()),
+            "obfuse::invalid_c_str",
+        ),
+        (ObfuseError::TruncatedLayer, "obfuse::truncated_layer"),
+        (ObfuseError::MalformedArray, "obfuse::malformed_array"),
+        (ObfuseError::SizeLimitExceeded, "obfuse::size_limit"),
+        (
+            ObfuseError::InvalidHash(Box::new(std::fmt::Error)),
+            "obfuse::invalid_hash",
+        ),
+        (ObfuseError::DebuggerDetected, "obfuse::debugger_detected"),
+        (
+            ObfuseError::InvalidEncoding("bad base64"),
+            "obfuse::invalid_encoding",
+        ),
+        (
+            ObfuseError::InvalidBlob("layer count out of range"),
+            "obfuse::invalid_blob",
+        ),
+        (
+            ObfuseError::WrongLength {
+                expected: 32,
+                actual: 31,
+            },
+            "obfuse::wrong_length",
+        ),
+        (ObfuseError::TimedOut, "obfuse::timed_out"),
+        (ObfuseError::CustomCipher, "obfuse::custom_cipher"),
+    ];
+
+    for (error, expected) in &cases {
+        assert_eq!(code(error), *expected);
+        assert!(error.help().is_some());
+    }
+}
+
+#[test]
+fn test_into_report() {
+    let report = miette::Report::new(ObfuseError::AllocationFailed);
+    assert_eq!(
+        report.to_string(),
+        "memory allocation failed during decryption"
+    );
+}
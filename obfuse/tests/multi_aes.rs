@@ -0,0 +1,61 @@
+//! Tests for mixing AES-128-GCM and AES-256-GCM strings in one binary.
+
+#![cfg(feature = "multi-aes")]
+
+use obfuse::{obfuse, obfuse_const};
+
+obfuse_const! {
+    static FAST = "tier one", aes = 128;
+    static STRONG = "tier two", aes = 256;
+}
+
+#[test]
+fn test_mixed_key_sizes() {
+    let fast = obfuse!("aes-128 secret", aes = 128);
+    let strong = obfuse!("aes-256 secret");
+    let seeded = obfuse!("seeded", seed = "multi", aes = 128);
+
+    assert_eq!(fast.as_str(), "aes-128 secret");
+    assert_eq!(strong.as_str(), "aes-256 secret");
+    assert_eq!(seeded.as_str(), "seeded");
+
+    // The key-size tag is shuffled along with the rest when permuted
+    #[cfg(not(feature = "permute-ciphertext"))]
+    {
+        assert_eq!(fast.ciphertext()[0], 16);
+        assert_eq!(strong.ciphertext()[0], 32);
+    }
+}
+
+#[test]
+fn test_mixed_statics() {
+    assert_eq!(FAST.as_str(), "tier one");
+    assert_eq!(STRONG.as_str(), "tier two");
+}
+
+// The payloads are one-shot GCM; `streaming` builds expect chunked ciphertext
+#[cfg(not(feature = "streaming"))]
+#[test]
+fn test_pre_encrypted_key_size_from_key_length() {
+    // AES-256-GCM payload; a 32-byte key selects AES-256
+    let secret = obfuse!(
+        hex = "3770b336a08ba169f431e3eed5c90b08e0a4e2404e419b946bf965bf95de5ed5e51fbace",
+        key = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        nonce = "000102030405060708090a0b",
+    );
+    assert_eq!(secret.as_str(), "pre-encrypted secret");
+}
+
+#[cfg(not(feature = "streaming"))]
+#[test]
+fn test_pre_encrypted_aes128() {
+    // AES-128-GCM payload; a 16-byte key selects AES-128
+    let secret = obfuse!(
+        hex = "f209d4e35729cf743bb318e659c2140b1ec2954c3b597f03975ecc09993f4e",
+        key = "000102030405060708090a0b0c0d0e0f",
+        nonce = "000102030405060708090a0b",
+    );
+    assert_eq!(secret.as_str(), "aes-128 payload");
+    #[cfg(not(feature = "permute-ciphertext"))]
+    assert_eq!(secret.ciphertext()[0], 16);
+}
@@ -0,0 +1,90 @@
+//! Tests for `ObfuseStr::new_from_owned`.
+//!
+//! A wrapping global allocator inspects every deallocation of the ciphertext's
+//! size while armed, recording whether the block was already zeroed.
+
+#![cfg(all(
+    feature = "aes-256-gcm",
+    not(any(feature = "multi-aes", feature = "streaming"))
+))]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use obfuse::ObfuseStr;
+
+/// "pre-encrypted secret" under key 00..1f and nonce 00..0b.
+const CIPHERTEXT: &str = "3770b336a08ba169f431e3eed5c90b08e0a4e2404e419b946bf965bf95de5ed5e51fbace";
+
+struct WipeCheck;
+
+static ARMED: AtomicBool = AtomicBool::new(false);
+/// Size of the owned ciphertext, which `ciphertext-padding` makes vary.
+static WATCHED: AtomicUsize = AtomicUsize::new(0);
+static WIPED: AtomicUsize = AtomicUsize::new(0);
+static DIRTY: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for WipeCheck {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if ARMED.load(Ordering::SeqCst) && layout.size() == WATCHED.load(Ordering::SeqCst) {
+            let block = unsafe { std::slice::from_raw_parts(ptr, layout.size()) };
+            let counter = if block.iter().all(|&b| b == 0) {
+                &WIPED
+            } else {
+                &DIRTY
+            };
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: WipeCheck = WipeCheck;
+
+/// Returns owned ciphertext, key and nonce for the test vector.
+fn parts() -> (Vec<u8>, [u8; 32], [u8; 12]) {
+    let ciphertext: Vec<u8> = (0..CIPHERTEXT.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&CIPHERTEXT[i..i + 2], 16).unwrap())
+        .collect();
+    let key = std::array::from_fn(|i| u8::try_from(i).unwrap());
+    let nonce = std::array::from_fn(|i| u8::try_from(i).unwrap());
+
+    #[cfg(feature = "ciphertext-padding")]
+    let ciphertext = obfuse::pad_ciphertext(&ciphertext, &nonce);
+    #[cfg(feature = "permute-ciphertext")]
+    let ciphertext = obfuse::permute_ciphertext(&ciphertext, &nonce);
+
+    (ciphertext, key, nonce)
+}
+
+#[test]
+fn test_owned_parts() {
+    let (ciphertext, key, nonce) = parts();
+
+    let mut wrong_key = key;
+    wrong_key[0] ^= 1;
+    let wrong = ObfuseStr::new_from_owned(ciphertext.clone(), wrong_key, nonce);
+    assert!(wrong.try_as_str().is_err());
+    drop(wrong);
+
+    let secret = ObfuseStr::new_from_owned(ciphertext.clone(), key, nonce);
+
+    assert_eq!(secret.as_str(), "pre-encrypted secret");
+    assert_eq!(secret.ciphertext(), ciphertext);
+    WATCHED.store(ciphertext.len(), Ordering::SeqCst);
+    drop(ciphertext);
+
+    ARMED.store(true, Ordering::SeqCst);
+    drop(secret);
+    ARMED.store(false, Ordering::SeqCst);
+
+    // Only the owned ciphertext has this size; the plaintext is shorter
+    assert_eq!(WIPED.load(Ordering::SeqCst), 1);
+    assert_eq!(DIRTY.load(Ordering::SeqCst), 0);
+}
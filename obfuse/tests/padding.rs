@@ -0,0 +1,98 @@
+//! Tests for the `ciphertext-padding` junk prefix and suffix.
+
+#![cfg(feature = "ciphertext-padding")]
+
+use obfuse::{MAX_PADDING, obfuse, obfuse_const};
+
+obfuse_const! {
+    static PADDED = "const padded";
+}
+
+#[test]
+fn test_round_trip() {
+    let secrets = [
+        (obfuse!(""), ""),
+        (obfuse!("a"), "a"),
+        (obfuse!("padded secret"), "padded secret"),
+        (obfuse!("Hello, 世界! 🌍"), "Hello, 世界! 🌍"),
+        (
+            obfuse!("layered and padded", layers = 3),
+            "layered and padded",
+        ),
+    ];
+
+    for (secret, expected) in &secrets {
+        assert_eq!(secret.as_str(), *expected);
+    }
+    assert_eq!(PADDED.as_str(), "const padded");
+}
+
+#[test]
+fn test_stored_length_varies() {
+    // Same plaintext length, but each expansion gets its own nonce
+    let lengths = [
+        obfuse!("same-length-0").ciphertext().len(),
+        obfuse!("same-length-1").ciphertext().len(),
+        obfuse!("same-length-2").ciphertext().len(),
+        obfuse!("same-length-3").ciphertext().len(),
+        obfuse!("same-length-4").ciphertext().len(),
+        obfuse!("same-length-5").ciphertext().len(),
+        obfuse!("same-length-6").ciphertext().len(),
+        obfuse!("same-length-7").ciphertext().len(),
+    ];
+    let unpadded = obfuse_unpadded_len(b"same-length-0".len());
+
+    assert!(lengths.iter().any(|&len| len != lengths[0]), "{lengths:?}");
+    for len in lengths {
+        assert!((unpadded..=unpadded + 2 * (MAX_PADDING - 1)).contains(&len));
+    }
+}
+
+#[cfg(all(
+    feature = "aes-256-gcm",
+    not(any(
+        feature = "multi-aes",
+        feature = "permute-ciphertext",
+        feature = "streaming"
+    ))
+))]
+#[test]
+fn test_ciphertext_sits_between_junk() {
+    // GCM test case 14: sixteen zero bytes under an all-zero key and nonce
+    let canonical: Vec<u8> = (0..64)
+        .step_by(2)
+        .map(|i| {
+            let hex = "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919";
+            u8::from_str_radix(&hex[i..i + 2], 16).unwrap()
+        })
+        .collect();
+
+    let secret = obfuse!(
+        "\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+        key = "0000000000000000000000000000000000000000000000000000000000000000",
+        nonce = "000000000000000000000000",
+    );
+
+    assert_eq!(
+        secret.ciphertext(),
+        obfuse::pad_ciphertext(&canonical, &[0; 12])
+    );
+    assert!(
+        secret
+            .ciphertext()
+            .windows(canonical.len())
+            .any(|window| window == canonical)
+    );
+    assert_eq!(secret.as_bytes(), [0; 16]);
+}
+
+/// Stored length of an unpadded single-layer ciphertext for `len` bytes.
+fn obfuse_unpadded_len(len: usize) -> usize {
+    let aead = cfg!(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
+        feature = "chacha20-poly1305"
+    ));
+    len + if aead { 16 } else { 0 } + usize::from(cfg!(feature = "multi-aes"))
+}
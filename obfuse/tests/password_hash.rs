@@ -0,0 +1,37 @@
+//! Tests for `ObfuseStr::verify_against`.
+
+#![cfg(feature = "password-hash")]
+
+use obfuse::{ObfuseError, obfuse};
+
+/// "correct horse battery staple" with cheap test parameters.
+const HASH: &str = "$argon2id$v=19$m=64,t=1,p=1$b2JmdXNlLXRlc3Qtc2FsdA$JYL7bgAK52JTBkjMZ4TOavTzLZdFIw0d/PURue1Pp8o";
+
+#[test]
+fn test_verify_against_known_hash() {
+    let password = obfuse!("correct horse battery staple");
+    assert!(password.verify_against(HASH).unwrap());
+    assert!(!password.is_decrypted());
+
+    let wrong = obfuse!("correct horse battery stapler");
+    assert!(!wrong.verify_against(HASH).unwrap());
+}
+
+#[test]
+fn test_verify_against_rejects_malformed_hash() {
+    let password = obfuse!("correct horse battery staple");
+    for hash in [
+        "",
+        "not a hash",
+        "$argon2id$v=19$m=64,t=1,p=1$",
+        "$bcrypt$v=1$abc$def",
+    ] {
+        assert!(
+            matches!(
+                password.verify_against(hash),
+                Err(ObfuseError::InvalidHash(_))
+            ),
+            "{hash:?} should be rejected"
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Tests for the `permute-ciphertext` stored byte order.
+
+#![cfg(feature = "permute-ciphertext")]
+
+use obfuse::{obfuse, obfuse_const};
+
+obfuse_const! {
+    static PERMUTED = "const permuted";
+}
+
+#[test]
+fn test_round_trip() {
+    let secrets = [
+        obfuse!(""),
+        obfuse!("a"),
+        obfuse!("permuted secret"),
+        obfuse!("Hello, 世界! 🌍"),
+        obfuse!("layered and permuted", layers = 3),
+    ];
+    let expected = [
+        "",
+        "a",
+        "permuted secret",
+        "Hello, 世界! 🌍",
+        "layered and permuted",
+    ];
+
+    for (secret, expected) in secrets.iter().zip(expected) {
+        assert_eq!(secret.as_str(), expected);
+    }
+    assert_eq!(PERMUTED.as_str(), "const permuted");
+}
+
+#[cfg(all(feature = "aes-256-gcm", not(feature = "multi-aes")))]
+#[test]
+fn test_stored_bytes_differ_from_canonical() {
+    // GCM test case 14: sixteen zero bytes under an all-zero key and nonce
+    let canonical: Vec<u8> = (0..64)
+        .step_by(2)
+        .map(|i| {
+            let hex = "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919";
+            u8::from_str_radix(&hex[i..i + 2], 16).unwrap()
+        })
+        .collect();
+
+    let secret = obfuse!(
+        "\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+        key = "0000000000000000000000000000000000000000000000000000000000000000",
+        nonce = "000000000000000000000000",
+    );
+
+    assert_ne!(secret.ciphertext(), canonical);
+
+    // Padding is added before the shuffle
+    #[cfg(feature = "ciphertext-padding")]
+    let canonical = obfuse::pad_ciphertext(&canonical, &[0; 12]);
+    assert_eq!(
+        secret.ciphertext(),
+        obfuse::permute_ciphertext(&canonical, &[0; 12])
+    );
+    assert_eq!(secret.as_bytes(), [0; 16]);
+}
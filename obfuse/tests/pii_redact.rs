@@ -0,0 +1,73 @@
+//! Tests for `ObfuseStr::reveal_redacted_for_log`.
+
+#![cfg(feature = "pii-redact")]
+
+use obfuse::{RedactConfig, obfuse};
+
+#[test]
+fn test_masks_embedded_email() {
+    let dsn = obfuse!("postgres://alice.smith@example.com@db.internal:5432/app");
+    let line = dsn
+        .reveal_redacted_for_log(&RedactConfig::default())
+        .unwrap();
+
+    assert_eq!(line, "postgres://[REDACTED]@db.internal:5432/app");
+    assert!(!dsn.is_decrypted());
+}
+
+#[test]
+fn test_masks_card_numbers() {
+    let note = obfuse!("card 4111 1111 1111 1111 or 5500-0000-0000-0004, order 12345");
+    assert_eq!(
+        note.reveal_redacted_for_log(&RedactConfig::default())
+            .unwrap(),
+        "card [REDACTED] or [REDACTED], order 12345"
+    );
+}
+
+#[test]
+fn test_keeps_digit_runs_failing_luhn() {
+    // A millisecond timestamp and a 16-digit ID match the card pattern but
+    // are not card numbers
+    let line = obfuse!("ts=1700000000123 id=1234567890123456 card=4111111111111111");
+    assert_eq!(
+        line.reveal_redacted_for_log(&RedactConfig::default())
+            .unwrap(),
+        "ts=1700000000123 id=1234567890123456 card=[REDACTED]"
+    );
+}
+
+#[test]
+fn test_leaves_non_pii_content() {
+    let url = obfuse!("https://api.example.com/v1/status?retries=3");
+    assert_eq!(
+        url.reveal_redacted_for_log(&RedactConfig::default())
+            .unwrap(),
+        "https://api.example.com/v1/status?retries=3"
+    );
+
+    // An empty config masks nothing
+    let email = obfuse!("ops@example.com");
+    assert_eq!(
+        email
+            .reveal_redacted_for_log(&RedactConfig::empty())
+            .unwrap(),
+        "ops@example.com"
+    );
+}
+
+#[test]
+fn test_custom_patterns() {
+    let config = RedactConfig::empty()
+        .with_pattern(r"token=\w+")
+        .unwrap()
+        .with_replacement("token=$1***");
+    let url = obfuse!("https://example.com/?token=abc123&page=2");
+
+    // The replacement is literal; `$1` is not expanded
+    assert_eq!(
+        url.reveal_redacted_for_log(&config).unwrap(),
+        "https://example.com/?token=$1***&page=2"
+    );
+    assert!(RedactConfig::empty().with_pattern("(").is_err());
+}
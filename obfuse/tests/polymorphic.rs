@@ -0,0 +1,45 @@
+//! Tests for per-string polymorphic XOR transforms.
+
+#![cfg(all(
+    feature = "polymorphic",
+    not(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
+        feature = "chacha20-poly1305"
+    ))
+))]
+
+use obfuse::obfuse;
+
+#[test]
+fn test_each_string_decrypts() {
+    let strings = [
+        obfuse!(""),
+        obfuse!("a"),
+        obfuse!("polymorphic secret"),
+        obfuse!("Hello, 世界! 🌍"),
+        obfuse!("seeded", seed = "polymorphic"),
+    ];
+    let expected = ["", "a", "polymorphic secret", "Hello, 世界! 🌍", "seeded"];
+
+    for (secret, expected) in strings.iter().zip(expected) {
+        assert_eq!(secret.as_str(), expected);
+    }
+}
+
+#[test]
+fn test_programs_differ_between_strings() {
+    // Same plaintext and key, so differing ciphertext comes from the program
+    let first = obfuse!(
+        "same plaintext",
+        key = "4242424242424242424242424242424242424242424242424242424242424242"
+    );
+    let second = obfuse!(
+        "same plaintext",
+        key = "4242424242424242424242424242424242424242424242424242424242424242"
+    );
+
+    assert_ne!(first.ciphertext(), second.ciphertext());
+    assert_eq!(first.as_str(), second.as_str());
+}
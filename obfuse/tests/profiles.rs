@@ -0,0 +1,50 @@
+//! Round trips under the `profile-light` and `profile-paranoid` presets.
+//!
+//! CI runs this file once per algorithm for each profile.
+
+#![cfg(feature = "profile-light")]
+
+use obfuse::{ObfuseStr, obfuse, obfuse_const};
+
+obfuse_const! {
+    static FIRST = "profile const";
+    static SECOND = "seeded profile const", seed = "profiles";
+}
+
+#[test]
+fn test_macros_round_trip() {
+    assert_eq!(obfuse!("profile local").as_str(), "profile local");
+    assert_eq!(obfuse!("", seed = "profiles").as_str(), "");
+    assert_eq!(obfuse!("layered", layers = 3).as_str(), "layered");
+    assert_eq!(FIRST.as_str(), "profile const");
+    assert_eq!(SECOND.as_str(), "seeded profile const");
+}
+
+#[test]
+fn test_export_round_trip() {
+    let secret = obfuse!("exported under a profile");
+    let restored = ObfuseStr::from_base64(&secret.to_base64().unwrap()).unwrap();
+    assert_eq!(restored.as_str(), "exported under a profile");
+}
+
+#[cfg(feature = "profile-paranoid")]
+mod paranoid {
+    use obfuse::obfuse_const;
+
+    // Shuffled emission, padded and permuted storage, multiple layers
+    obfuse_const! {
+        static A = "alpha", layers = 2;
+        static B = "bravo";
+        static C = "charlie", seed = "paranoid";
+        static D = "delta", layers = 4, seed = "paranoid";
+        static E = "";
+    }
+
+    #[test]
+    fn test_hardened_const_block_round_trips() {
+        assert_eq!(
+            [A.as_str(), B.as_str(), C.as_str(), D.as_str(), E.as_str()],
+            ["alpha", "bravo", "charlie", "delta", ""]
+        );
+    }
+}
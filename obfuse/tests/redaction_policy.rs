@@ -0,0 +1,104 @@
+//! Tests for the `redaction-policy` feature.
+
+#![cfg(feature = "redaction-policy")]
+
+use std::fmt;
+
+use obfuse::{ObfuseStr, Redaction, RedactionPolicy, obfuse};
+
+/// Shows nothing but a fixed marker.
+struct Stars;
+
+impl RedactionPolicy for Stars {
+    fn fmt_debug(&self, _secret: &ObfuseStr, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+#[test]
+fn test_builtin_policies() {
+    let secret = obfuse!("policy");
+    let len = secret.ciphertext().len();
+
+    assert_eq!(
+        format!("{:?}", secret.debug_with(&Redaction::Full)),
+        r#"ObfuseStr { value: "[REDACTED]", decrypted: false }"#
+    );
+    assert_eq!(
+        format!("{:?}", secret.debug_with(&Redaction::Length)),
+        format!(r#"ObfuseStr {{ ciphertext_len: {len}, value: "[REDACTED]", decrypted: false }}"#)
+    );
+    assert_eq!(
+        format!("{:?}", secret.debug_with(&Redaction::Algorithm)),
+        format!(
+            r#"ObfuseStr {{ algorithm: "{}", value: "[REDACTED]", decrypted: false }}"#,
+            obfuse_core::ALGORITHM
+        )
+    );
+    assert_eq!(
+        format!("{:?}", secret.debug_with(&Redaction::Nothing)),
+        "ObfuseStr { .. }"
+    );
+}
+
+#[test]
+fn test_policies_never_decrypt() {
+    let secret = obfuse!("stay encrypted");
+    for policy in [
+        Redaction::Full,
+        Redaction::Length,
+        Redaction::Algorithm,
+        Redaction::Nothing,
+    ] {
+        let debug = format!("{:#?}", secret.debug_with(&policy));
+        assert!(!debug.contains("stay encrypted"), "{policy:?}");
+    }
+    assert!(!secret.is_decrypted());
+
+    assert_eq!(secret.as_str(), "stay encrypted");
+    let debug = format!("{:?}", secret.debug_with(&Redaction::Full));
+    assert!(debug.contains("decrypted: true"));
+    assert!(!debug.contains("stay encrypted"));
+}
+
+#[test]
+fn test_custom_policy() {
+    let secret = obfuse!("custom");
+    assert_eq!(format!("{:?}", secret.debug_with(&Stars)), "***");
+}
+
+// The global policy is process-wide, so all assertions about it live in
+// one test
+#[test]
+fn test_global_policy_composes_with_debug_with() {
+    #[derive(Debug)]
+    struct Config {
+        token: ObfuseStr,
+    }
+
+    let config = Config {
+        token: obfuse!("global"),
+    };
+    assert_eq!(
+        format!("{config:?}"),
+        r#"Config { token: ObfuseStr { value: "[REDACTED]", decrypted: false } }"#
+    );
+
+    obfuse::set_redaction_policy(&Redaction::Nothing);
+    assert_eq!(format!("{config:?}"), "Config { token: ObfuseStr { .. } }");
+
+    // A per-site policy overrides the global one in both directions
+    assert_eq!(
+        format!("{:?}", config.token.debug_with(&Redaction::Full)),
+        r#"ObfuseStr { value: "[REDACTED]", decrypted: false }"#
+    );
+    obfuse::set_redaction_policy(&Stars);
+    assert_eq!(format!("{config:?}"), "Config { token: *** }");
+    assert_eq!(
+        format!("{:?}", config.token.debug_with(&Redaction::Nothing)),
+        "ObfuseStr { .. }"
+    );
+
+    obfuse::set_redaction_policy(&Redaction::Full);
+    assert!(format!("{config:?}").contains("[REDACTED]"));
+}
@@ -0,0 +1,72 @@
+//! Tests that `reveal_bytes` wipes its copy before the memory is freed.
+//!
+//! A wrapping global allocator inspects every deallocation of the expected
+//! size while armed, recording whether the block was already zeroed.
+
+#![cfg(all(
+    feature = "aes-256-gcm",
+    not(any(feature = "multi-aes", feature = "streaming"))
+))]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use obfuse::obfuse;
+
+/// Non-UTF-8 plaintext encrypted under key 00..1f and nonce 00..0b.
+const PLAINTEXT: [u8; 8] = [0xff, 0xfe, 0x00, 0x80, 0xc3, 0x28, 0x01, 0x7f];
+
+struct WipeCheck;
+
+static ARMED: AtomicBool = AtomicBool::new(false);
+static WIPED: AtomicUsize = AtomicUsize::new(0);
+static DIRTY: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for WipeCheck {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if ARMED.load(Ordering::SeqCst) && layout.size() == PLAINTEXT.len() {
+            let block = unsafe { std::slice::from_raw_parts(ptr, layout.size()) };
+            let counter = if block.iter().all(|&b| b == 0) {
+                &WIPED
+            } else {
+                &DIRTY
+            };
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: WipeCheck = WipeCheck;
+
+#[test]
+fn test_reveal_bytes_wipes_on_drop() {
+    let secret = obfuse!(
+        hex = "b8fcd69b06cdc36454cd1cee814e38a11c034490d794901f",
+        key = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        nonce = "000102030405060708090a0b",
+    );
+
+    // Not valid UTF-8, so only the bytes API can reveal it
+    assert!(secret.try_reveal().is_err());
+
+    let guard = secret.reveal_bytes().unwrap();
+    assert_eq!(&*guard, PLAINTEXT);
+    assert_eq!(
+        format!("{guard:?}"),
+        r#"SecretBytesGuard { value: "[REDACTED]" }"#
+    );
+    assert!(!secret.is_decrypted());
+
+    ARMED.store(true, Ordering::SeqCst);
+    drop(guard);
+    ARMED.store(false, Ordering::SeqCst);
+
+    assert_eq!(WIPED.load(Ordering::SeqCst), 1);
+    assert_eq!(DIRTY.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,16 @@
+//! Tests for `secrecy` interop.
+
+#![cfg(feature = "secrecy")]
+
+use obfuse::obfuse;
+use secrecy::ExposeSecret;
+
+#[test]
+fn test_to_secret_string() {
+    let secret = obfuse!("trust boundary");
+    let promoted = secret.to_secret_string().unwrap();
+
+    assert_eq!(promoted.expose_secret(), "trust boundary");
+    // The conversion decrypts a fresh copy rather than the cache
+    assert!(!secret.is_decrypted());
+}
@@ -0,0 +1,89 @@
+//! Tests for `SecretVec` and `ObfuseStr::decrypt_into_secret_vec`.
+//!
+//! A wrapping global allocator inspects every deallocation of the buffer's
+//! original capacity while armed, recording whether the block was already
+//! zeroed.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use obfuse::{SecretVec, obfuse};
+
+/// An unusual capacity, so unrelated deallocations are unlikely to match.
+const CAPACITY: usize = 23;
+
+struct WipeCheck;
+
+static ARMED: AtomicBool = AtomicBool::new(false);
+static WIPED: AtomicUsize = AtomicUsize::new(0);
+static DIRTY: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for WipeCheck {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if ARMED.load(Ordering::SeqCst) && layout.size() == CAPACITY {
+            let block = unsafe { std::slice::from_raw_parts(ptr, layout.size()) };
+            let counter = if block.iter().all(|&b| b == 0) {
+                &WIPED
+            } else {
+                &DIRTY
+            };
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: WipeCheck = WipeCheck;
+
+#[test]
+fn test_realloc_wipes_old_buffer() {
+    let short = obfuse!("twenty-three byte token");
+    let long = obfuse!("a secret too long for the buffer's first allocation");
+
+    let mut buffer = SecretVec::with_capacity(CAPACITY);
+    short.decrypt_into_secret_vec(&mut buffer).unwrap();
+    assert_eq!(buffer.as_slice(), b"twenty-three byte token");
+    assert_eq!(buffer.capacity(), CAPACITY);
+
+    ARMED.store(true, Ordering::SeqCst);
+    long.decrypt_into_secret_vec(&mut buffer).unwrap();
+    ARMED.store(false, Ordering::SeqCst);
+
+    assert_eq!(
+        buffer.as_slice(),
+        b"a secret too long for the buffer's first allocation"
+    );
+    assert!(buffer.capacity() > CAPACITY);
+    assert!(WIPED.load(Ordering::SeqCst) >= 1);
+    assert_eq!(DIRTY.load(Ordering::SeqCst), 0);
+    assert!(!long.is_decrypted());
+}
+
+#[test]
+fn test_reuses_allocation() {
+    let mut buffer = SecretVec::with_capacity(64);
+    obfuse!("first secret")
+        .decrypt_into_secret_vec(&mut buffer)
+        .unwrap();
+    obfuse!("second")
+        .decrypt_into_secret_vec(&mut buffer)
+        .unwrap();
+
+    assert_eq!(&*buffer, b"second");
+    assert_eq!(buffer.capacity(), 64);
+    assert!(!format!("{buffer:?}").contains("second"));
+
+    buffer.clear();
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.capacity(), 64);
+
+    buffer.try_extend_from_slice(b"abc").unwrap();
+    buffer.try_shrink_to_fit().unwrap();
+    assert_eq!(buffer.capacity(), 3);
+    assert_eq!(&*buffer, b"abc");
+}
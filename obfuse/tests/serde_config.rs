@@ -0,0 +1,42 @@
+//! Tests for the `serde-config` feature.
+
+#![cfg(feature = "serde-config")]
+
+use obfuse::ObfuseStr;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Config {
+    host: String,
+    password: ObfuseStr,
+}
+
+#[test]
+fn test_deserialize_encrypts_plaintext() {
+    let config: Config =
+        serde_json::from_str(r#"{"host":"db.internal","password":"hunter2-config"}"#).unwrap();
+
+    assert_eq!(config.host, "db.internal");
+    assert!(!config.password.is_decrypted());
+    assert!(
+        !config
+            .password
+            .ciphertext()
+            .windows(b"hunter2-config".len())
+            .any(|w| w == b"hunter2-config")
+    );
+    assert_eq!(config.password.as_str(), "hunter2-config");
+}
+
+#[test]
+fn test_deserialize_escaped_string() {
+    // Escapes force serde_json to hand over an owned String
+    let secret: ObfuseStr = serde_json::from_str(r#""line\nbreak""#).unwrap();
+    assert_eq!(secret.as_str(), "line\nbreak");
+}
+
+#[test]
+fn test_deserialize_rejects_non_strings() {
+    let err = serde_json::from_str::<ObfuseStr>("42").err().unwrap();
+    assert!(err.to_string().contains("plaintext secret string"), "{err}");
+}
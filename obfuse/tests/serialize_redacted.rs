@@ -0,0 +1,51 @@
+//! Tests for the `serialize-redacted` feature.
+
+#![cfg(feature = "serialize-redacted")]
+
+use obfuse::{ObfuseStr, obfuse};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+struct Event {
+    name: &'static str,
+    api_key: ObfuseStr,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Cached {
+    #[serde(with = "obfuse::serde_ciphertext")]
+    token: ObfuseStr,
+}
+
+#[test]
+fn test_serialize_redacts_by_default() {
+    let event = Event {
+        name: "login",
+        api_key: obfuse!("sk-live-123"),
+    };
+
+    let json = serde_json::to_string(&event).unwrap();
+    assert_eq!(json, r#"{"name":"login","api_key":"[REDACTED]"}"#);
+    assert!(!event.api_key.is_decrypted());
+}
+
+#[test]
+fn test_ciphertext_opt_in_round_trips() {
+    let cached = Cached {
+        token: obfuse!("refresh-token"),
+    };
+
+    let json = serde_json::to_string(&cached).unwrap();
+    assert!(!json.contains("refresh-token"));
+    assert!(json.contains(&cached.token.to_base64().unwrap()));
+    assert!(!cached.token.is_decrypted());
+
+    let restored: Cached = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.token.as_str(), "refresh-token");
+}
+
+#[test]
+fn test_ciphertext_opt_in_rejects_malformed_export() {
+    let result = serde_json::from_str::<Cached>(r#"{"token":"not an export"}"#);
+    assert!(result.is_err());
+}
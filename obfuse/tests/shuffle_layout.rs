@@ -0,0 +1,41 @@
+//! Tests for the `shuffle-layout` feature: only the layout changes.
+
+#![cfg(feature = "shuffle-layout")]
+
+use obfuse::obfuse_const;
+
+obfuse_const! {
+    static FIRST = "first";
+    static SECOND = "second";
+    static THIRD = "third", seed = "layout";
+    static FOURTH = "fourth";
+    static FIFTH = "fifth";
+    static SIXTH = "sixth";
+}
+
+#[test]
+fn test_shuffled_statics_decrypt() {
+    let values = [&FIRST, &SECOND, &THIRD, &FOURTH, &FIFTH, &SIXTH].map(|s| s.as_str());
+    assert_eq!(
+        values,
+        ["first", "second", "third", "fourth", "fifth", "sixth"]
+    );
+}
+
+#[cfg(feature = "manifest")]
+mod manifest {
+    use obfuse::obfuse_const;
+
+    obfuse_const! {
+        static A = "a";
+        static B = "b";
+        static C = "c";
+        manifest SECRETS;
+    }
+
+    #[test]
+    fn test_manifest_follows_declaration_order() {
+        assert!(SECRETS.verify());
+        assert_eq!([A.as_str(), B.as_str(), C.as_str()], ["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,65 @@
+//! Tests for the `streaming` feature.
+
+#![cfg(feature = "streaming")]
+
+use obfuse::obfuse;
+
+#[test]
+fn test_streamed_literal_round_trips() {
+    assert_eq!(obfuse!("streamed secret").as_str(), "streamed secret");
+    assert_eq!(obfuse!("").as_str(), "");
+}
+
+#[test]
+fn test_streamed_layers_round_trip() {
+    let secret = obfuse!("layered and streamed", layers = 3);
+    assert_eq!(secret.as_str(), "layered and streamed");
+}
+
+#[cfg(feature = "runtime-encrypt")]
+#[test]
+fn test_streamed_multi_chunk_round_trips() {
+    use obfuse::ObfuseStr;
+    use std::io::Cursor;
+
+    // Sizes around the 64 KiB chunk boundary, up to several chunks
+    for len in [65_535, 65_536, 65_537, 3 * 65_536 + 5] {
+        let input: String = "0123456789abcdef".chars().cycle().take(len).collect();
+        let secret = ObfuseStr::from_reader(&mut Cursor::new(&input)).unwrap();
+        assert_eq!(secret.as_str(), input, "len {len}");
+    }
+}
+
+#[cfg(feature = "runtime-encrypt")]
+#[test]
+fn test_budgeted_decrypt_times_out_on_multi_chunk_secret() {
+    use obfuse::{ObfuseError, ObfuseStr, on_decrypt_error};
+    use std::io::Cursor;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    // A timeout is not a decryption failure, so the hook must stay silent
+    static REPORTED: AtomicUsize = AtomicUsize::new(0);
+    on_decrypt_error(|_, _| {
+        REPORTED.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let input = "x".repeat(16 * 65_536);
+    let secret = ObfuseStr::from_reader(&mut Cursor::new(&input)).unwrap();
+
+    // Time a full decryption, then allow a quarter of it: the deadline
+    // passes the check before the first chunk and expires mid-stream
+    let start = Instant::now();
+    drop(secret.reveal());
+    let full = start.elapsed();
+
+    assert!(matches!(
+        secret.decrypt_with_budget(Instant::now() + full / 4),
+        Err(ObfuseError::TimedOut)
+    ));
+    assert!(!secret.is_decrypted());
+    assert_eq!(REPORTED.load(Ordering::SeqCst), 0);
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    assert_eq!(secret.decrypt_with_budget(deadline).unwrap(), input);
+}
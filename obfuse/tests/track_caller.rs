@@ -0,0 +1,83 @@
+//! Tests that the panicking accessors report the caller's location.
+//!
+//! The panic hook is process-wide, so this file holds a single test.
+
+use std::panic::{self, Location};
+use std::sync::Mutex;
+
+use obfuse::obfuse;
+
+static PANIC_LINE: Mutex<Option<(String, u32)>> = Mutex::new(None);
+
+/// A copy of `secret` whose ciphertext no longer authenticates, so even the
+/// byte-level accessors (`OsStr`, `Path`) fail.
+#[cfg(any(
+    feature = "aes-256-gcm",
+    feature = "aes-128-gcm",
+    feature = "aes-256-ccm",
+    feature = "chacha20-poly1305"
+))]
+fn tampered(secret: &obfuse::ObfuseStr) -> obfuse::ObfuseStr {
+    let export = secret.to_base64().unwrap();
+    let mut fields: Vec<String> = export.split('|').map(String::from).collect();
+    fields[2] = fields[2]
+        .chars()
+        .map(|c| if c == '=' { c } else { 'A' })
+        .collect();
+    obfuse::ObfuseStr::from_base64(&fields.join("|")).unwrap()
+}
+
+/// Runs `f`, which must panic, and returns the file and line it reported.
+fn panic_location(f: impl FnOnce() + panic::UnwindSafe) -> (String, u32) {
+    assert!(panic::catch_unwind(f).is_err());
+    PANIC_LINE.lock().unwrap().take().unwrap()
+}
+
+#[test]
+fn test_panics_point_at_caller() {
+    panic::set_hook(Box::new(|info| {
+        let location = info.location().unwrap();
+        *PANIC_LINE.lock().unwrap() = Some((location.file().to_owned(), location.line()));
+    }));
+
+    // Valid C string, invalid UTF-8
+    let secret = obfuse!(c"caf\xe9");
+
+    let here = Location::caller().file().to_owned();
+    let (file, line) = panic_location(|| {
+        let _ = secret.as_str();
+    });
+    assert_eq!((file.as_str(), line), (here.as_str(), line!() - 2));
+
+    let (file, line) = panic_location(|| {
+        let _ = secret.len();
+    });
+    assert_eq!((file.as_str(), line), (here.as_str(), line!() - 2));
+
+    let (file, line) = panic_location(|| {
+        let _: &str = secret.as_ref();
+    });
+    assert_eq!((file.as_str(), line), (here.as_str(), line!() - 2));
+
+    #[cfg(any(
+        feature = "aes-256-gcm",
+        feature = "aes-128-gcm",
+        feature = "aes-256-ccm",
+        feature = "chacha20-poly1305"
+    ))]
+    {
+        let secret = tampered(&obfuse!("/etc/app.toml"));
+
+        let (file, line) = panic_location(|| {
+            let _ = secret.as_path();
+        });
+        assert_eq!((file.as_str(), line), (here.as_str(), line!() - 2));
+
+        let (file, line) = panic_location(|| {
+            let _: &std::path::Path = secret.as_ref();
+        });
+        assert_eq!((file.as_str(), line), (here.as_str(), line!() - 2));
+    }
+
+    let _ = panic::take_hook();
+}
@@ -0,0 +1,35 @@
+//! Tests for the `transport-safe` feature.
+
+#![cfg(feature = "transport-safe")]
+
+use obfuse::{ObfuseStr, obfuse};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Message {
+    user: &'static str,
+    token: ObfuseStr,
+}
+
+#[test]
+fn test_serialize_redacts() {
+    let message = Message {
+        user: "alice",
+        token: obfuse!("bearer-abc123"),
+    };
+
+    let json = serde_json::to_string(&message).unwrap();
+    assert_eq!(json, r#"{"user":"alice","token":"[REDACTED]"}"#);
+    assert!(!message.token.is_decrypted());
+
+    // The plaintext is still reachable explicitly
+    assert_eq!(message.token.reveal().as_str(), "bearer-abc123");
+}
+
+#[test]
+fn test_display_redacts() {
+    let secret = obfuse!("displayable");
+    assert_eq!(format!("{secret}"), "[REDACTED]");
+    assert_eq!(secret.to_string(), "[REDACTED]");
+    assert!(!secret.is_decrypted());
+}
@@ -0,0 +1,51 @@
+//! Compile-time (UI) tests for the `obfuse!` macro.
+//!
+//! The fixtures embed AES-256-GCM ciphertext, so they only run with the
+//! default algorithm (and not with `multi-aes`, which accepts other key sizes,
+//! or `streaming`, which expects chunked ciphertext).
+
+#[cfg(all(
+    feature = "aes-256-gcm",
+    not(any(feature = "multi-aes", feature = "streaming"))
+))]
+#[test]
+fn pre_encrypted_input() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/hex_valid.rs");
+    t.pass("tests/ui/base64_valid.rs");
+    t.compile_fail("tests/ui/hex_invalid_digit.rs");
+    t.compile_fail("tests/ui/hex_odd_length.rs");
+    t.compile_fail("tests/ui/hex_wrong_key_length.rs");
+    t.compile_fail("tests/ui/hex_missing_nonce.rs");
+    t.compile_fail("tests/ui/base64_invalid.rs");
+    t.compile_fail("tests/ui/pinned_wrong_nonce_length.rs");
+}
+
+#[test]
+fn option_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/rng_unknown.rs");
+    t.compile_fail("tests/ui/seed_kdf_unknown.rs");
+    t.compile_fail("tests/ui/layers_out_of_range.rs");
+    t.compile_fail("tests/ui/option_unknown.rs");
+}
+
+#[test]
+fn nonempty() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/nonempty_valid.rs");
+    t.compile_fail("tests/ui/nonempty_empty.rs");
+}
+
+#[test]
+fn once_is_consumed() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/once_take_twice.rs");
+}
+
+#[cfg(feature = "aes-256-gcm")]
+#[test]
+fn must_use_accessors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/must_use_reveal.rs");
+}
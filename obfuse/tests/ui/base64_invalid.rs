@@ -0,0 +1,9 @@
+use obfuse::obfuse;
+
+fn main() {
+    let _secret = obfuse!(
+        base64 = "N3CzNqCLoWn0MePu1ckLCOCk4kBOQZuUa/llv5XeXtXlH7r!",
+        key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=",
+        nonce = "AAECAwQFBgcICQoL",
+    );
+}
@@ -0,0 +1,10 @@
+use obfuse::obfuse;
+
+fn main() {
+    let secret = obfuse!(
+        base64 = "N3CzNqCLoWn0MePu1ckLCOCk4kBOQZuUa/llv5XeXtXlH7rO",
+        key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=",
+        nonce = "AAECAwQFBgcICQoL",
+    );
+    assert_eq!(secret.as_str(), "pre-encrypted secret");
+}
@@ -0,0 +1,10 @@
+use obfuse::obfuse;
+
+fn main() {
+    let secret = obfuse!(
+        hex = "3770b336a08ba169f431e3eed5c90b08e0a4e2404e419b946bf965bf95de5ed5e51fbace",
+        key = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        nonce = "000102030405060708090a0b",
+    );
+    assert_eq!(secret.as_str(), "pre-encrypted secret");
+}
@@ -0,0 +1,9 @@
+use obfuse::obfuse;
+
+fn main() {
+    let _secret = obfuse!(
+        hex = "3770b336a08ba169f431e3eed5c90b08e0a4e2404e419b946bf965bf95de5ed5e51fbace",
+        key = "000102030405060708090a0b0c0d0e0f",
+        nonce = "000102030405060708090a0b",
+    );
+}
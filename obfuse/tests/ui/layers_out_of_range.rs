@@ -0,0 +1,6 @@
+use obfuse::obfuse;
+
+fn main() {
+    let _none = obfuse!("value", layers = 0);
+    let _many = obfuse!("value", layers = 9);
+}
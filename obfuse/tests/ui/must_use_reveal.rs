@@ -0,0 +1,9 @@
+#![deny(unused_must_use)]
+
+use obfuse::obfuse;
+
+fn main() {
+    let secret = obfuse!("unused");
+    secret.reveal();
+    secret.as_str();
+}
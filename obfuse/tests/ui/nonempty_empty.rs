@@ -0,0 +1,5 @@
+use obfuse::obfuse_nonempty;
+
+fn main() {
+    let _secret = obfuse_nonempty!("");
+}
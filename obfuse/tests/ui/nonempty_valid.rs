@@ -0,0 +1,6 @@
+use obfuse::obfuse_nonempty;
+
+fn main() {
+    let secret = obfuse_nonempty!("api key", seed = "s");
+    assert_eq!(secret.as_str(), "api key");
+}
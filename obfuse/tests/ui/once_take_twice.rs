@@ -0,0 +1,7 @@
+use obfuse::obfuse_once;
+
+fn main() {
+    let token = obfuse_once!("one-time token");
+    let _first = token.take();
+    let _second = token.take();
+}
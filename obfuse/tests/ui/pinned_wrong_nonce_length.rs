@@ -0,0 +1,9 @@
+use obfuse::obfuse;
+
+fn main() {
+    let _secret = obfuse!(
+        "known answer",
+        key = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        nonce = "0001020304",
+    );
+}
@@ -0,0 +1,5 @@
+use obfuse::obfuse;
+
+fn main() {
+    let _secret = obfuse!("value", seed = "s", seed_kdf = "v9");
+}
@@ -0,0 +1,27 @@
+//! Tests for `ObfuseStr::with_utf16` and `ObfuseStr::with_utf16_nul`.
+
+#![cfg(feature = "utf16")]
+
+use obfuse::obfuse;
+
+#[test]
+fn test_with_utf16_encodes_code_units() {
+    // U+1D11E needs a surrogate pair
+    let secret = obfuse!("h\u{e9}llo \u{1d11e}");
+    let units = secret.with_utf16(<[u16]>::to_vec).unwrap();
+
+    assert_eq!(units, [0x68, 0xe9, 0x6c, 0x6c, 0x6f, 0x20, 0xd834, 0xdd1e]);
+    assert!(!secret.is_decrypted());
+}
+
+#[test]
+fn test_with_utf16_nul_terminates_once() {
+    let units = obfuse!("pw").with_utf16_nul(<[u16]>::to_vec).unwrap();
+    assert_eq!(units, [0x70, 0x77, 0]);
+
+    let terminated = obfuse!("pw\0").with_utf16_nul(<[u16]>::to_vec).unwrap();
+    assert_eq!(terminated, [0x70, 0x77, 0]);
+
+    let empty = obfuse!("").with_utf16_nul(<[u16]>::len).unwrap();
+    assert_eq!(empty, 1);
+}
@@ -0,0 +1,23 @@
+//! Tests for `ObfuseStr::warm_async`.
+
+#![cfg(feature = "tokio")]
+
+use obfuse::{ObfuseStr, obfuse_static};
+
+static SECRET: &ObfuseStr = obfuse_static!("warmed off the reactor");
+
+fn assert_send_static<T: Send + 'static>(value: T) -> T {
+    value
+}
+
+#[tokio::test]
+async fn test_warm_async_populates_cache() {
+    assert!(!SECRET.is_decrypted());
+
+    assert_send_static(SECRET.warm_async()).await.unwrap();
+    assert!(SECRET.is_decrypted());
+    assert_eq!(SECRET.as_str(), "warmed off the reactor");
+
+    // Warming again is a cheap cache hit
+    SECRET.warm_async().await.unwrap();
+}
@@ -0,0 +1,61 @@
+//! Tests that `with_c_str` hands out a valid C string and wipes it afterward.
+//!
+//! A wrapping global allocator inspects every deallocation of the terminated
+//! buffer's size while armed, recording whether the block was already zeroed.
+
+#![cfg(all(feature = "aes-256-gcm", not(feature = "multi-aes")))]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::ffi::{CStr, c_char};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use obfuse::obfuse;
+
+const PLAINTEXT: &str = "ffi secret";
+
+struct WipeCheck;
+
+static ARMED: AtomicBool = AtomicBool::new(false);
+static WIPED: AtomicUsize = AtomicUsize::new(0);
+static DIRTY: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for WipeCheck {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if ARMED.load(Ordering::SeqCst) && layout.size() == PLAINTEXT.len() + 1 {
+            let block = unsafe { std::slice::from_raw_parts(ptr, layout.size()) };
+            let counter = if block.iter().all(|&b| b == 0) {
+                &WIPED
+            } else {
+                &DIRTY
+            };
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: WipeCheck = WipeCheck;
+
+/// Stands in for a C function taking a `const char *`.
+extern "C" fn c_strlen(s: *const c_char) -> usize {
+    unsafe { CStr::from_ptr(s) }.to_bytes().len()
+}
+
+#[test]
+fn test_with_c_str_wipes_after_call() {
+    let secret = obfuse!("ffi secret");
+
+    ARMED.store(true, Ordering::SeqCst);
+    let len = secret.with_c_str(|s| c_strlen(s.as_ptr())).unwrap();
+    ARMED.store(false, Ordering::SeqCst);
+
+    assert_eq!(len, PLAINTEXT.len());
+    assert!(!secret.is_decrypted());
+    assert_eq!(WIPED.load(Ordering::SeqCst), 1);
+    assert_eq!(DIRTY.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,49 @@
+//! Tests for embedding `ObfuseStr` in zeroizing containers.
+
+use obfuse::{ObfuseStr, obfuse};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+struct Credentials {
+    user: ObfuseStr,
+    password: ObfuseStr,
+    attempts: u32,
+}
+
+#[test]
+fn test_derived_zeroize_on_drop() {
+    let credentials = Credentials {
+        user: obfuse!("admin"),
+        password: obfuse!("hunter2"),
+        attempts: 3,
+    };
+
+    assert_eq!(credentials.user.as_str(), "admin");
+    assert_eq!(credentials.password.as_str(), "hunter2");
+    assert_eq!(credentials.attempts, 3);
+
+    // Dropping must not write to the borrowed static ciphertext
+    drop(credentials);
+
+    let again = obfuse!("hunter2", seed = "zeroize");
+    assert_eq!(again.as_str(), "hunter2");
+}
+
+#[test]
+fn test_explicit_zeroize_leaves_static_ciphertext() {
+    let mut credentials = Credentials {
+        user: obfuse!("static ciphertext", seed = "zeroize"),
+        password: obfuse!("other"),
+        attempts: 1,
+    };
+    let before = credentials.user.ciphertext().to_vec();
+
+    credentials.zeroize();
+    assert_eq!(credentials.attempts, 0);
+    assert_eq!(credentials.user.ciphertext(), before);
+
+    // The same seeded ciphertext still decrypts after the wipe
+    let fresh = obfuse!("static ciphertext", seed = "zeroize");
+    assert_eq!(fresh.ciphertext(), before);
+    assert_eq!(fresh.as_str(), "static ciphertext");
+}